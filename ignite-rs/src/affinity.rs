@@ -0,0 +1,223 @@
+use crate::utils::string_to_java_hashcode;
+use crate::{Date, Decimal, Enum, HashCode, Time, Timestamp, Uuid};
+
+/// Returns the partition of the key, the same as Ignite's RendezvousAffinityFunction does.
+/// The key is mapped by its affinity hash code. Zero partitions result in zero
+pub fn partition<K: HashCode + ?Sized>(key: &K, partitions: u32) -> u32 {
+    if partitions == 0 {
+        return 0;
+    }
+    let hash = key.affinity_hash_code();
+    if partitions.is_power_of_two() {
+        let mask = partitions - 1;
+        (hash ^ ((hash as u32) >> 16) as i32) as u32 & mask
+    } else {
+        (i64::from(hash) % i64::from(partitions)).unsigned_abs() as u32
+    }
+}
+
+/// java.lang.Long.hashCode()
+fn long_hash_code(value: i64) -> i32 {
+    (value ^ ((value as u64) >> 32) as i64) as i32
+}
+
+impl<T: HashCode + ?Sized> HashCode for &T {
+    fn hash_code(&self) -> i32 {
+        (**self).hash_code()
+    }
+
+    fn affinity_hash_code(&self) -> i32 {
+        (**self).affinity_hash_code()
+    }
+}
+
+impl HashCode for u8 {
+    fn hash_code(&self) -> i32 {
+        i32::from(*self as i8) // java byte is signed
+    }
+}
+
+impl HashCode for u16 {
+    fn hash_code(&self) -> i32 {
+        i32::from(*self) // java char
+    }
+}
+
+impl HashCode for i16 {
+    fn hash_code(&self) -> i32 {
+        i32::from(*self)
+    }
+}
+
+impl HashCode for i32 {
+    fn hash_code(&self) -> i32 {
+        *self
+    }
+}
+
+impl HashCode for i64 {
+    fn hash_code(&self) -> i32 {
+        long_hash_code(*self)
+    }
+}
+
+impl HashCode for f32 {
+    fn hash_code(&self) -> i32 {
+        // java.lang.Float.floatToIntBits() collapses all NaNs into the canonical one
+        if self.is_nan() {
+            0x7fc0_0000
+        } else {
+            self.to_bits() as i32
+        }
+    }
+}
+
+impl HashCode for f64 {
+    fn hash_code(&self) -> i32 {
+        // java.lang.Double.doubleToLongBits() collapses all NaNs into the canonical one
+        if self.is_nan() {
+            long_hash_code(0x7ff8_0000_0000_0000)
+        } else {
+            long_hash_code(self.to_bits() as i64)
+        }
+    }
+}
+
+impl HashCode for bool {
+    fn hash_code(&self) -> i32 {
+        if *self {
+            1231
+        } else {
+            1237
+        }
+    }
+}
+
+impl HashCode for str {
+    fn hash_code(&self) -> i32 {
+        string_to_java_hashcode(self)
+    }
+}
+
+impl HashCode for String {
+    fn hash_code(&self) -> i32 {
+        string_to_java_hashcode(self)
+    }
+}
+
+impl HashCode for Uuid {
+    fn hash_code(&self) -> i32 {
+        let hilo = self.most_sig_bits ^ self.least_sig_bits;
+        ((hilo >> 32) as i32) ^ (hilo as i32)
+    }
+}
+
+impl HashCode for Enum {
+    /// BinaryEnumObjectImpl.hashCode()
+    fn hash_code(&self) -> i32 {
+        self.type_id.wrapping_mul(31).wrapping_add(self.ordinal)
+    }
+}
+
+impl HashCode for Date {
+    fn hash_code(&self) -> i32 {
+        long_hash_code(self.millis)
+    }
+}
+
+impl HashCode for Time {
+    fn hash_code(&self) -> i32 {
+        long_hash_code(self.millis)
+    }
+}
+
+impl HashCode for Timestamp {
+    /// java.sql.Timestamp uses java.util.Date's hash code, so nanos are ignored
+    fn hash_code(&self) -> i32 {
+        long_hash_code(self.millis)
+    }
+}
+
+impl HashCode for Decimal {
+    /// java.math.BigDecimal.hashCode(): `31 * unscaledValue.hashCode() + scale`
+    fn hash_code(&self) -> i32 {
+        // BigInteger's hash code is computed over the magnitude's big-endian 32-bit words
+        let magnitude = self.magnitude();
+        let padding = (4 - magnitude.len() % 4) % 4;
+        let mut words = vec![0u8; padding];
+        words.extend_from_slice(magnitude);
+        let mut hash: i32 = 0;
+        for word in words.chunks(4) {
+            let word = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+            hash = hash.wrapping_mul(31).wrapping_add(word as i32);
+        }
+        if self.is_negative() {
+            hash = hash.wrapping_neg();
+        }
+        hash.wrapping_mul(31).wrapping_add(self.scale())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::partition;
+    use crate::{Date, Decimal, Enum, HashCode, Timestamp, Uuid};
+
+    // expected values are taken from Java's hashCode() of the corresponding types
+    #[test]
+    fn test_primitive_hash_codes() {
+        assert_eq!(200u8.hash_code(), -56);
+        assert_eq!(0xFFFFu16.hash_code(), 65535);
+        assert_eq!((-5i16).hash_code(), -5);
+        assert_eq!((1i64 << 32).hash_code(), 1);
+        assert_eq!((-1i64).hash_code(), 0);
+        assert_eq!(1.0f32.hash_code(), 1_065_353_216);
+        assert_eq!(f32::NAN.hash_code(), 2_143_289_344);
+        assert_eq!(1.0f64.hash_code(), 1_072_693_248);
+        assert_eq!(f64::NAN.hash_code(), 2_146_959_360);
+        assert_eq!(true.hash_code(), 1231);
+        assert_eq!(false.hash_code(), 1237);
+    }
+
+    #[test]
+    fn test_object_hash_codes() {
+        assert_eq!("hello".hash_code(), 99_162_322);
+        assert_eq!("hello".to_string().hash_code(), 99_162_322);
+        // surrogate pair: "😀"
+        assert_eq!("😀".hash_code(), 1_772_899);
+
+        let uuid: Uuid = "00000001-0000-0002-0000-000300000004".parse().unwrap();
+        assert_eq!(uuid.hash_code(), 4); // (1 ^ 3) ^ (2 ^ 4)
+
+        let enum_value = Enum {
+            type_id: 10,
+            ordinal: 2,
+        };
+        assert_eq!(enum_value.hash_code(), 312);
+
+        assert_eq!(Date { millis: 1 << 32 }.hash_code(), 1);
+        let timestamp = Timestamp {
+            millis: 1_500,
+            nanos: 999,
+        };
+        assert_eq!(timestamp.hash_code(), 1_500);
+
+        // new BigDecimal("2.0").hashCode()
+        assert_eq!("2.0".parse::<Decimal>().unwrap().hash_code(), 621);
+        assert_eq!("-2.0".parse::<Decimal>().unwrap().hash_code(), -619);
+        // unscaled value 2^32 has words [1, 0]: 31 * (31 * 1 + 0) + 0
+        assert_eq!("4294967296".parse::<Decimal>().unwrap().hash_code(), 961);
+        assert_eq!(Decimal::from(0).hash_code(), 0);
+    }
+
+    #[test]
+    fn test_partition() {
+        // power of two: (h ^ (h >>> 16)) & mask
+        assert_eq!(partition(&1_048_577i32, 1024), 17);
+        assert_eq!(partition(&-1i32, 1024), 1023 & (-1i32 ^ 0xFFFF) as u32);
+        // otherwise: abs(h % parts)
+        assert_eq!(partition(&-7i32, 5), 2);
+        assert_eq!(partition(&12i32, 5), 2);
+        assert_eq!(partition(&12i32, 0), 0);
+    }
+}
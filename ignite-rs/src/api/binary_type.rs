@@ -0,0 +1,90 @@
+use std::io;
+use std::io::{Read, Write};
+
+use crate::error::IgniteResult;
+use crate::protocol::binary_type::{BinaryField, BinarySchema, BinaryType};
+use crate::protocol::{read_bool, read_i32, read_string_not_null, write_i32};
+use crate::{ReadableReq, ReadableType, WriteableReq};
+
+/// Get Binary Type 3002
+pub(crate) struct BinaryTypeGetReq {
+    pub(crate) type_id: i32,
+}
+
+impl WriteableReq for BinaryTypeGetReq {
+    fn write(&self, writer: &mut dyn Write) -> io::Result<()> {
+        write_i32(writer, self.type_id)
+    }
+
+    fn size(&self) -> usize {
+        4 // type id
+    }
+}
+
+pub(crate) struct BinaryTypeGetResp {
+    pub(crate) binary_type: Option<BinaryType>,
+}
+
+impl ReadableReq for BinaryTypeGetResp {
+    fn read(reader: &mut impl Read) -> IgniteResult<Self> {
+        if !read_bool(reader)? {
+            return Ok(BinaryTypeGetResp { binary_type: None });
+        }
+
+        let type_id = read_i32(reader)?;
+        let type_name = read_string_not_null(reader)?;
+        let affinity_key_field_name = String::read(reader)?;
+
+        let fields_count = read_i32(reader)?;
+        let mut fields = Vec::new();
+        for _ in 0..fields_count {
+            let name = read_string_not_null(reader)?;
+            let type_id = read_i32(reader)?;
+            let field_id = read_i32(reader)?;
+            fields.push(BinaryField {
+                name,
+                type_id,
+                field_id,
+            });
+        }
+
+        let enum_values = if read_bool(reader)? {
+            let count = read_i32(reader)?;
+            let mut values = Vec::new();
+            for _ in 0..count {
+                let name = read_string_not_null(reader)?;
+                let ordinal = read_i32(reader)?;
+                values.push((name, ordinal));
+            }
+            Some(values)
+        } else {
+            None
+        };
+
+        let schemas_count = read_i32(reader)?;
+        let mut schemas = Vec::new();
+        for _ in 0..schemas_count {
+            let schema_id = read_i32(reader)?;
+            let ids_count = read_i32(reader)?;
+            let mut field_ids = Vec::new();
+            for _ in 0..ids_count {
+                field_ids.push(read_i32(reader)?);
+            }
+            schemas.push(BinarySchema {
+                schema_id,
+                field_ids,
+            });
+        }
+
+        Ok(BinaryTypeGetResp {
+            binary_type: Some(BinaryType {
+                type_id,
+                type_name,
+                affinity_key_field_name,
+                fields,
+                enum_values,
+                schemas,
+            }),
+        })
+    }
+}
@@ -1,11 +1,13 @@
 use std::io::{Read, Write};
 
 use crate::cache::CacheConfiguration;
-use crate::error::{IgniteError, IgniteResult};
+use crate::error::IgniteResult;
 use crate::protocol::cache_config::{get_cache_configuration_bytes, read_cache_configuration};
-use crate::protocol::{read_i32, write_i32, write_string_type_code, write_u8};
+use crate::protocol::{
+    read_i32, read_string_not_null, write_i32, write_string_type_code, write_u8,
+};
 use crate::utils::string_to_java_hashcode;
-use crate::{ReadableReq, ReadableType, WriteableReq};
+use crate::{ReadableReq, WriteableReq};
 use std::io;
 
 // https://apacheignite.readme.io/docs/binary-client-protocol-cache-configuration-operations#op_cache_get_configuration
@@ -35,10 +37,7 @@ impl ReadableReq for CacheGetNamesResp {
 
         let mut names = Vec::<String>::new();
         for _ in 0..count {
-            match String::read(reader)? {
-                None => return Err(IgniteError::from("NULL is not expected")),
-                Some(n) => names.push(n),
-            };
+            names.push(read_string_not_null(reader)?);
         }
 
         Ok(CacheGetNamesResp { names })
@@ -51,7 +50,7 @@ pub(crate) struct CacheCreateWithNameReq<'a> {
 }
 
 impl CacheCreateWithNameReq<'_> {
-    pub(crate) fn from(name: &str) -> CacheCreateWithNameReq {
+    pub(crate) fn from(name: &str) -> CacheCreateWithNameReq<'_> {
         CacheCreateWithNameReq { name }
     }
 }
@@ -72,7 +71,7 @@ pub(crate) struct CacheGetOrCreateWithNameReq<'a> {
 }
 
 impl CacheGetOrCreateWithNameReq<'_> {
-    pub(crate) fn from(name: &str) -> CacheGetOrCreateWithNameReq {
+    pub(crate) fn from(name: &str) -> CacheGetOrCreateWithNameReq<'_> {
         CacheGetOrCreateWithNameReq { name }
     }
 }
@@ -123,7 +122,7 @@ pub(crate) struct CacheGetConfigReq<'a> {
 }
 
 impl CacheGetConfigReq<'_> {
-    pub(crate) fn from(name: &str) -> CacheGetConfigReq {
+    pub(crate) fn from(name: &str) -> CacheGetConfigReq<'_> {
         CacheGetConfigReq { name }
     }
 }
@@ -158,7 +157,7 @@ pub(crate) struct CacheDestroyReq<'a> {
 }
 
 impl CacheDestroyReq<'_> {
-    pub(crate) fn from(name: &str) -> CacheDestroyReq {
+    pub(crate) fn from(name: &str) -> CacheDestroyReq<'_> {
         CacheDestroyReq { name }
     }
 }
@@ -1,7 +1,10 @@
+pub(crate) mod binary_type;
 pub(crate) mod cache_config;
 pub(crate) mod key_value;
 
-pub(crate) enum OpCode {
+/// Operations of the binary client protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OpCode {
     Handshake = 1,
     //cache configuration
     CacheGetNames = 1050,
@@ -35,10 +38,54 @@ pub(crate) enum OpCode {
     CacheGetSize = 1020,
     // sql & scan queries - https://ignite.apache.org/docs/latest/binary-client-protocol/sql-and-scan-queries
     QueryScan = 2000,
+    // binary type metadata - https://ignite.apache.org/docs/latest/binary-client-protocol/binary-type-metadata
+    BinaryTypeGet = 3002,
 }
 
-impl Into<i16> for OpCode {
-    fn into(self) -> i16 {
-        self as i16
+impl OpCode {
+    /// Whether repeating the operation gives the same result and the same cache state.
+    /// Only such operations are retried after a connection failure
+    pub(crate) fn is_idempotent(self) -> bool {
+        match self {
+            OpCode::CacheGetNames
+            | OpCode::CacheGetOrCreateWithName
+            | OpCode::CacheGetOrCreateWithConfiguration
+            | OpCode::CacheGetConfiguration
+            | OpCode::CacheGet
+            | OpCode::CachePut
+            | OpCode::CacheGetAll
+            | OpCode::CachePutAll
+            | OpCode::CacheReplace
+            | OpCode::CacheContainsKey
+            | OpCode::CacheContainsKeys
+            | OpCode::CacheClear
+            | OpCode::CacheClearKey
+            | OpCode::CacheClearKeys
+            | OpCode::CacheRemoveKeys
+            | OpCode::CacheRemoveAll
+            | OpCode::CacheGetSize
+            | OpCode::BinaryTypeGet => true,
+            // the result of a repeated operation depends on whether the first one was applied
+            OpCode::Handshake
+            | OpCode::CacheCreateWithName
+            | OpCode::CacheCreateWithConfiguration
+            | OpCode::CacheDestroy
+            | OpCode::CachePutIfAbsent
+            | OpCode::CacheGetAndPut
+            | OpCode::CacheGetAndReplace
+            | OpCode::CacheGetAndRemove
+            | OpCode::CacheGetAndPutIfAbsent
+            | OpCode::CacheReplaceIfEquals
+            | OpCode::CacheRemoveKey
+            | OpCode::CacheRemoveIfEquals => false,
+            // a repeated scan opens another cursor, while the first one is never closed
+            OpCode::QueryScan => false,
+        }
+    }
+}
+
+impl From<OpCode> for i16 {
+    fn from(code: OpCode) -> i16 {
+        code as i16
     }
 }
@@ -18,6 +18,7 @@ use crate::connection::Connection;
 use crate::{ReadableType, WritableType};
 use std::marker::PhantomData;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Clone, Debug)]
 pub enum AtomicityMode {
@@ -32,7 +33,9 @@ impl TryFrom<i32> for AtomicityMode {
         match value {
             0 => Ok(Transactional),
             1 => Ok(Atomic),
-            _ => Err(IgniteError::from("Cannot read AtomicityMode")),
+            _ => Err(IgniteError::Protocol(
+                "Cannot read AtomicityMode".to_owned(),
+            )),
         }
     }
 }
@@ -52,7 +55,7 @@ impl TryFrom<i32> for CacheMode {
             0 => Ok(Local),
             1 => Ok(Replicated),
             2 => Ok(Partitioned),
-            _ => Err(IgniteError::from("Cannot read CacheMode")),
+            _ => Err(IgniteError::Protocol("Cannot read CacheMode".to_owned())),
         }
     }
 }
@@ -76,7 +79,9 @@ impl TryFrom<i32> for PartitionLossPolicy {
             2 => Ok(ReadWriteSafe),
             3 => Ok(ReadWriteAll),
             4 => Ok(Ignore),
-            _ => Err(IgniteError::from("Cannot read PartitionLossPolicy")),
+            _ => Err(IgniteError::Protocol(
+                "Cannot read PartitionLossPolicy".to_owned(),
+            )),
         }
     }
 }
@@ -96,7 +101,9 @@ impl TryFrom<i32> for RebalanceMode {
             0 => Ok(RebalanceMode::Sync),
             1 => Ok(Async),
             2 => Ok(RebalanceMode::None),
-            _ => Err(IgniteError::from("Cannot read RebalanceMode")),
+            _ => Err(IgniteError::Protocol(
+                "Cannot read RebalanceMode".to_owned(),
+            )),
         }
     }
 }
@@ -116,7 +123,9 @@ impl TryFrom<i32> for WriteSynchronizationMode {
             0 => Ok(FullSync),
             1 => Ok(FullAsync),
             2 => Ok(PrimarySync),
-            _ => Err(IgniteError::from("Cannot read WriteSynchronizationMode")),
+            _ => Err(IgniteError::Protocol(
+                "Cannot read WriteSynchronizationMode".to_owned(),
+            )),
         }
     }
 }
@@ -129,9 +138,9 @@ pub enum CachePeekMode {
     Backup = 3,
 }
 
-impl Into<u8> for CachePeekMode {
-    fn into(self) -> u8 {
-        self as u8
+impl From<CachePeekMode> for u8 {
+    fn from(mode: CachePeekMode) -> u8 {
+        mode as u8
     }
 }
 
@@ -150,7 +159,7 @@ impl TryFrom<u8> for IndexType {
             0 => Ok(Sorted),
             1 => Ok(Fulltext),
             2 => Ok(GeoSpatial),
-            _ => Err(IgniteError::from("Cannot read IndexType")),
+            _ => Err(IgniteError::Protocol("Cannot read IndexType".to_owned())),
         }
     }
 }
@@ -239,19 +248,118 @@ pub struct CacheKeyConfiguration {
     pub affinity_key_field_name: String,
 }
 
+/// Describes a SQL table of the cache: its key and value types, columns and indexes.
+/// Use QueryEntity::builder() or #[derive(IgniteQueryEntity)] to create it
 #[derive(Clone, Debug)]
 pub struct QueryEntity {
     pub(crate) key_type: String,
     pub(crate) value_type: String,
     pub(crate) table: String,
-    pub(crate) key_field: String,
-    pub(crate) value_field: String,
+    pub(crate) key_field: Option<String>,
+    pub(crate) value_field: Option<String>,
     pub(crate) query_fields: Vec<QueryField>,
     pub(crate) field_aliases: Vec<(String, String)>,
     pub(crate) query_indexes: Vec<QueryIndex>,
-    pub(crate) default_value: Option<String>, //TODO: find the issue where this field is listed
 }
 
+impl QueryEntity {
+    /// Creates a builder of the entity with Java type names of the key and the value.
+    /// The table is named after the value type by default, like in Java
+    pub fn builder(key_type: &str, value_type: &str) -> QueryEntityBuilder {
+        let table = value_type.rsplit('.').next().unwrap_or(value_type);
+        QueryEntityBuilder {
+            entity: QueryEntity {
+                key_type: key_type.to_owned(),
+                value_type: value_type.to_owned(),
+                table: table.to_owned(),
+                key_field: None,
+                value_field: None,
+                query_fields: Vec::new(),
+                field_aliases: Vec::new(),
+                query_indexes: Vec::new(),
+            },
+        }
+    }
+
+    pub fn key_type(&self) -> &str {
+        &self.key_type
+    }
+
+    pub fn value_type(&self) -> &str {
+        &self.value_type
+    }
+
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// Name of the field which represents the whole key, if the key is not a complex object
+    pub fn key_field(&self) -> Option<&str> {
+        self.key_field.as_deref()
+    }
+
+    /// Name of the field which represents the whole value, if the value is not a complex object
+    pub fn value_field(&self) -> Option<&str> {
+        self.value_field.as_deref()
+    }
+
+    pub fn fields(&self) -> &[QueryField] {
+        &self.query_fields
+    }
+
+    /// Pairs of field names and their SQL aliases
+    pub fn field_aliases(&self) -> &[(String, String)] {
+        &self.field_aliases
+    }
+
+    pub fn indexes(&self) -> &[QueryIndex] {
+        &self.query_indexes
+    }
+}
+
+pub struct QueryEntityBuilder {
+    entity: QueryEntity,
+}
+
+impl QueryEntityBuilder {
+    pub fn table(mut self, table: &str) -> Self {
+        self.entity.table = table.to_owned();
+        self
+    }
+
+    pub fn key_field(mut self, name: &str) -> Self {
+        self.entity.key_field = Some(name.to_owned());
+        self
+    }
+
+    pub fn value_field(mut self, name: &str) -> Self {
+        self.entity.value_field = Some(name.to_owned());
+        self
+    }
+
+    pub fn field(mut self, field: QueryField) -> Self {
+        self.entity.query_fields.push(field);
+        self
+    }
+
+    pub fn alias(mut self, field: &str, alias: &str) -> Self {
+        self.entity
+            .field_aliases
+            .push((field.to_owned(), alias.to_owned()));
+        self
+    }
+
+    pub fn index(mut self, index: QueryIndex) -> Self {
+        self.entity.query_indexes.push(index);
+        self
+    }
+
+    pub fn build(self) -> QueryEntity {
+        self.entity
+    }
+}
+
+/// Column of the SQL table
 #[derive(Clone, Debug)]
 pub struct QueryField {
     pub(crate) name: String,
@@ -262,6 +370,82 @@ pub struct QueryField {
     pub(crate) scale: i32,
 }
 
+impl QueryField {
+    /// Creates a builder of the field with the Java type name of its values, like "java.lang.Long"
+    pub fn builder(name: &str, type_name: &str) -> QueryFieldBuilder {
+        QueryFieldBuilder {
+            field: QueryField {
+                name: name.to_owned(),
+                type_name: type_name.to_owned(),
+                key_field: false,
+                not_null_constraint: false,
+                precision: -1,
+                scale: -1,
+            },
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    /// Whether the field belongs to the key rather than to the value
+    pub fn is_key_field(&self) -> bool {
+        self.key_field
+    }
+
+    pub fn is_not_null(&self) -> bool {
+        self.not_null_constraint
+    }
+
+    /// Precision of the field. -1 if not set
+    pub fn precision(&self) -> i32 {
+        self.precision
+    }
+
+    /// Scale of the field. -1 if not set
+    pub fn scale(&self) -> i32 {
+        self.scale
+    }
+}
+
+pub struct QueryFieldBuilder {
+    field: QueryField,
+}
+
+impl QueryFieldBuilder {
+    /// Marks the field as a part of the key
+    pub fn key_field(mut self) -> Self {
+        self.field.key_field = true;
+        self
+    }
+
+    pub fn not_null(mut self) -> Self {
+        self.field.not_null_constraint = true;
+        self
+    }
+
+    /// Precision of decimals or max length of strings
+    pub fn precision(mut self, precision: i32) -> Self {
+        self.field.precision = precision;
+        self
+    }
+
+    pub fn scale(mut self, scale: i32) -> Self {
+        self.field.scale = scale;
+        self
+    }
+
+    pub fn build(self) -> QueryField {
+        self.field
+    }
+}
+
+/// Index of the SQL table
 #[derive(Clone, Debug)]
 pub struct QueryIndex {
     pub(crate) index_name: String,
@@ -270,6 +454,58 @@ pub struct QueryIndex {
     pub(crate) fields: Vec<(String, bool)>,
 }
 
+impl QueryIndex {
+    pub fn builder(name: &str, index_type: IndexType) -> QueryIndexBuilder {
+        QueryIndexBuilder {
+            index: QueryIndex {
+                index_name: name.to_owned(),
+                index_type,
+                inline_size: -1,
+                fields: Vec::new(),
+            },
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.index_name
+    }
+
+    pub fn index_type(&self) -> &IndexType {
+        &self.index_type
+    }
+
+    /// Inline size of the index. -1 if not set
+    pub fn inline_size(&self) -> i32 {
+        self.inline_size
+    }
+
+    /// Pairs of field names and descending flags
+    pub fn fields(&self) -> &[(String, bool)] {
+        &self.fields
+    }
+}
+
+pub struct QueryIndexBuilder {
+    index: QueryIndex,
+}
+
+impl QueryIndexBuilder {
+    pub fn inline_size(mut self, inline_size: i32) -> Self {
+        self.index.inline_size = inline_size;
+        self
+    }
+
+    /// Adds the field to the index. Fields are indexed in the order they are added
+    pub fn field(mut self, name: &str, descending: bool) -> Self {
+        self.index.fields.push((name.to_owned(), descending));
+        self
+    }
+
+    pub fn build(self) -> QueryIndex {
+        self.index
+    }
+}
+
 /// Ignite key-value cache. This cache is strongly typed and reading/writing some other
 /// types leads to errors.
 /// All caches created from the single IgniteClient shares the common TCP connection
@@ -277,6 +513,7 @@ pub struct Cache<K: WritableType + ReadableType, V: WritableType + ReadableType>
     id: i32,
     pub _name: String,
     conn: Arc<Connection>,
+    timeout: Option<Duration>,
     k_phantom: PhantomData<K>,
     v_phantom: PhantomData<V>,
 }
@@ -287,6 +524,21 @@ impl<K: WritableType + ReadableType, V: WritableType + ReadableType> Cache<K, V>
             id,
             _name: name,
             conn,
+            timeout: None,
+            k_phantom: PhantomData,
+            v_phantom: PhantomData,
+        }
+    }
+
+    /// Returns the same cache which operations fail with `IgniteError::Timeout`
+    /// if they don't complete within the timeout, including their retries.
+    /// The connection of the timed out operation is replaced, so no response is read half-way
+    pub fn with_timeout(&self, timeout: Duration) -> Cache<K, V> {
+        Cache {
+            id: self.id,
+            _name: self._name.clone(),
+            conn: self.conn.clone(),
+            timeout: Some(timeout),
             k_phantom: PhantomData,
             v_phantom: PhantomData,
         }
@@ -298,31 +550,44 @@ impl<K: WritableType + ReadableType, V: WritableType + ReadableType> Cache<K, V>
             .send_and_read(
                 OpCode::QueryScan,
                 CacheReq::QueryScan::<K, V>(self.id, page_size),
+                self.timeout,
             )
             .map(|resp: QueryScanResp<K, V>| resp.val)
     }
 
     pub fn get(&self, key: &K) -> IgniteResult<Option<V>> {
         self.conn
-            .send_and_read(OpCode::CacheGet, CacheReq::Get::<K, V>(self.id, key))
+            .send_and_read(
+                OpCode::CacheGet,
+                CacheReq::Get::<K, V>(self.id, key),
+                self.timeout,
+            )
             .map(|resp: CacheDataObjectResp<V>| resp.val)
     }
 
     pub fn get_all(&self, keys: &[K]) -> IgniteResult<Vec<(Option<K>, Option<V>)>> {
         self.conn
-            .send_and_read(OpCode::CacheGetAll, CacheReq::GetAll::<K, V>(self.id, keys))
+            .send_and_read(
+                OpCode::CacheGetAll,
+                CacheReq::GetAll::<K, V>(self.id, keys),
+                self.timeout,
+            )
             .map(|resp: CachePairsResp<K, V>| resp.val)
     }
 
     pub fn put(&self, key: &K, value: &V) -> IgniteResult<()> {
-        self.conn
-            .send(OpCode::CachePut, CacheReq::Put::<K, V>(self.id, key, value))
+        self.conn.send(
+            OpCode::CachePut,
+            CacheReq::Put::<K, V>(self.id, key, value),
+            self.timeout,
+        )
     }
 
     pub fn put_all(&self, pairs: &[(K, V)]) -> IgniteResult<()> {
         self.conn.send(
             OpCode::CachePutAll,
             CacheReq::PutAll::<K, V>(self.id, pairs),
+            self.timeout,
         )
     }
 
@@ -331,6 +596,7 @@ impl<K: WritableType + ReadableType, V: WritableType + ReadableType> Cache<K, V>
             .send_and_read(
                 OpCode::CacheContainsKey,
                 CacheReq::ContainsKey::<K, V>(self.id, key),
+                self.timeout,
             )
             .map(|resp: CacheBoolResp| resp.flag)
     }
@@ -340,6 +606,7 @@ impl<K: WritableType + ReadableType, V: WritableType + ReadableType> Cache<K, V>
             .send_and_read(
                 OpCode::CacheContainsKeys,
                 CacheReq::ContainsKeys::<K, V>(self.id, keys),
+                self.timeout,
             )
             .map(|resp: CacheBoolResp| resp.flag)
     }
@@ -349,6 +616,7 @@ impl<K: WritableType + ReadableType, V: WritableType + ReadableType> Cache<K, V>
             .send_and_read(
                 OpCode::CacheGetAndPut,
                 CacheReq::GetAndPut::<K, V>(self.id, key, value),
+                self.timeout,
             )
             .map(|resp: CacheDataObjectResp<V>| resp.val)
     }
@@ -358,6 +626,7 @@ impl<K: WritableType + ReadableType, V: WritableType + ReadableType> Cache<K, V>
             .send_and_read(
                 OpCode::CacheGetAndReplace,
                 CacheReq::GetAndReplace::<K, V>(self.id, key, value),
+                self.timeout,
             )
             .map(|resp: CacheDataObjectResp<V>| resp.val)
     }
@@ -367,6 +636,7 @@ impl<K: WritableType + ReadableType, V: WritableType + ReadableType> Cache<K, V>
             .send_and_read(
                 OpCode::CacheGetAndRemove,
                 CacheReq::GetAndRemove::<K, V>(self.id, key),
+                self.timeout,
             )
             .map(|resp: CacheDataObjectResp<V>| resp.val)
     }
@@ -376,6 +646,7 @@ impl<K: WritableType + ReadableType, V: WritableType + ReadableType> Cache<K, V>
             .send_and_read(
                 OpCode::CachePutIfAbsent,
                 CacheReq::PutIfAbsent::<K, V>(self.id, key, value),
+                self.timeout,
             )
             .map(|resp: CacheBoolResp| resp.flag)
     }
@@ -385,6 +656,7 @@ impl<K: WritableType + ReadableType, V: WritableType + ReadableType> Cache<K, V>
             .send_and_read(
                 OpCode::CacheGetAndPutIfAbsent,
                 CacheReq::GetAndPutIfAbsent::<K, V>(self.id, key, value),
+                self.timeout,
             )
             .map(|resp: CacheDataObjectResp<V>| resp.val)
     }
@@ -394,6 +666,7 @@ impl<K: WritableType + ReadableType, V: WritableType + ReadableType> Cache<K, V>
             .send_and_read(
                 OpCode::CacheReplace,
                 CacheReq::Replace::<K, V>(self.id, key, value),
+                self.timeout,
             )
             .map(|resp: CacheBoolResp| resp.flag)
     }
@@ -403,19 +676,24 @@ impl<K: WritableType + ReadableType, V: WritableType + ReadableType> Cache<K, V>
             .send_and_read(
                 OpCode::CacheReplaceIfEquals,
                 CacheReq::ReplaceIfEquals::<K, V>(self.id, key, old, new),
+                self.timeout,
             )
             .map(|resp: CacheBoolResp| resp.flag)
     }
 
     pub fn clear(&self) -> IgniteResult<()> {
-        self.conn
-            .send(OpCode::CacheClear, CacheReq::Clear::<K, V>(self.id))
+        self.conn.send(
+            OpCode::CacheClear,
+            CacheReq::Clear::<K, V>(self.id),
+            self.timeout,
+        )
     }
 
     pub fn clear_key(&self, key: &K) -> IgniteResult<()> {
         self.conn.send(
             OpCode::CacheClearKey,
             CacheReq::ClearKey::<K, V>(self.id, key),
+            self.timeout,
         )
     }
 
@@ -423,6 +701,7 @@ impl<K: WritableType + ReadableType, V: WritableType + ReadableType> Cache<K, V>
         self.conn.send(
             OpCode::CacheClearKeys,
             CacheReq::ClearKeys::<K, V>(self.id, keys),
+            self.timeout,
         )
     }
 
@@ -431,6 +710,7 @@ impl<K: WritableType + ReadableType, V: WritableType + ReadableType> Cache<K, V>
             .send_and_read(
                 OpCode::CacheRemoveKey,
                 CacheReq::RemoveKey::<K, V>(self.id, key),
+                self.timeout,
             )
             .map(|resp: CacheBoolResp| resp.flag)
     }
@@ -440,6 +720,7 @@ impl<K: WritableType + ReadableType, V: WritableType + ReadableType> Cache<K, V>
             .send_and_read(
                 OpCode::CacheRemoveIfEquals,
                 CacheReq::RemoveIfEquals::<K, V>(self.id, key, value),
+                self.timeout,
             )
             .map(|resp: CacheBoolResp| resp.flag)
     }
@@ -450,6 +731,7 @@ impl<K: WritableType + ReadableType, V: WritableType + ReadableType> Cache<K, V>
             .send_and_read(
                 OpCode::CacheGetSize,
                 CacheReq::GetSize::<K, V>(self.id, modes),
+                self.timeout,
             )
             .map(|resp: CacheSizeResp| resp.size)
     }
@@ -460,6 +742,7 @@ impl<K: WritableType + ReadableType, V: WritableType + ReadableType> Cache<K, V>
             .send_and_read(
                 OpCode::CacheGetSize,
                 CacheReq::GetSize::<K, V>(self.id, modes),
+                self.timeout,
             )
             .map(|resp: CacheSizeResp| resp.size)
     }
@@ -469,6 +752,7 @@ impl<K: WritableType + ReadableType, V: WritableType + ReadableType> Cache<K, V>
             .send_and_read(
                 OpCode::CacheGetSize,
                 CacheReq::GetSize::<K, V>(self.id, modes),
+                self.timeout,
             )
             .map(|resp: CacheSizeResp| resp.size)
     }
@@ -477,11 +761,15 @@ impl<K: WritableType + ReadableType, V: WritableType + ReadableType> Cache<K, V>
         self.conn.send(
             OpCode::CacheRemoveKeys,
             CacheReq::RemoveKeys::<K, V>(self.id, keys),
+            self.timeout,
         )
     }
 
     pub fn remove_all(&self) -> IgniteResult<()> {
-        self.conn
-            .send(OpCode::CacheRemoveAll, CacheReq::RemoveAll::<K, V>(self.id))
+        self.conn.send(
+            OpCode::CacheRemoveAll,
+            CacheReq::RemoveAll::<K, V>(self.id),
+            self.timeout,
+        )
     }
 }
@@ -1,85 +1,296 @@
 use std::io::{Read, Write};
-use std::net::TcpStream;
 
 use crate::api::OpCode;
-use crate::error::{IgniteError, IgniteResult};
+use crate::error::{IgniteError, IgniteResult, STATUS_AUTH_FAILED};
 use crate::handshake::handshake;
+use crate::protocol::binary_type::{with_schemas, SchemaRegistry};
 use crate::protocol::Flag::{Failure, Success};
-use crate::protocol::{read_i32, read_i64, write_i16, write_i32, write_i64, Flag};
+use crate::protocol::{
+    read_i32, read_i64, read_message, with_decode_limits, write_i16, write_i32, write_i64,
+    DecodeLimits, Flag,
+};
+#[cfg(any(feature = "rustls-tls", feature = "native-tls"))]
+use crate::transport::TlsTransport;
+use crate::transport::{TcpTransport, Transport, TransportStream};
 use crate::{ClientConfig, ReadableReq};
 use crate::{ReadableType, WriteableReq};
 use bufstream::BufStream;
-#[cfg(feature = "ssl")]
-use rustls;
 use std::io;
+use std::io::Cursor;
 use std::option::Option::Some;
-#[allow(unused_imports)]
-use std::sync::{Arc, Mutex};
-#[cfg(feature = "ssl")]
-use webpki;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
 const DFLT_READ_BUF_SIZE: usize = 1024;
 const DFLT_WRITE_BUF_SIZE: usize = 1024;
 const REQ_HEADER_SIZE_BYTES: i32 = 10;
 
+type Stream = BufStream<Box<dyn TransportStream>>;
+
+/// Stream shared by the requests. Only one request uses it at a time
+struct StreamSlot {
+    /// None after a failed request. Re-established by the next request
+    stream: Option<Stream>,
+    /// Whether a request uses the stream now
+    busy: bool,
+}
+
+/// Point in time the operation must be completed by
+struct Deadline {
+    timeout: Duration,
+    at: Instant,
+}
+
+impl Deadline {
+    fn new(timeout: Duration) -> Deadline {
+        Deadline {
+            timeout,
+            at: Instant::now() + timeout,
+        }
+    }
+
+    /// Time left until the deadline. Fails if the deadline has expired
+    fn remaining(&self) -> IgniteResult<Duration> {
+        match self.at.checked_duration_since(Instant::now()) {
+            Some(remaining) if remaining > Duration::from_millis(0) => Ok(remaining),
+            _ => Err(IgniteError::Timeout(self.timeout)),
+        }
+    }
+}
+
 pub struct Connection {
-    #[cfg(not(feature = "ssl"))]
-    stream: Mutex<BufStream<TcpStream>>,
-    #[cfg(feature = "ssl")]
-    stream: Mutex<BufStream<rustls::StreamOwned<rustls::ClientSession, TcpStream>>>,
+    conf: ClientConfig,
+    transport: Arc<dyn Transport>,
+    limits: DecodeLimits,
+    schemas: Arc<SchemaRegistry>,
+    slot: Mutex<StreamSlot>,
+    idle: Condvar,
 }
 
 impl Connection {
     pub(crate) fn new(conf: &ClientConfig) -> IgniteResult<Connection> {
-        match TcpStream::connect(&conf.addr) {
-            Ok(stream) => {
-                // apply tcp configs
-                Connection::configure_tcp(&stream, conf)?;
-
-                // wrap in tls stream if this feature enabled
-                #[cfg(feature = "ssl")]
-                let mut stream = Connection::wrap_tls_stream(&conf.tls_conf, stream)?;
-
-                // wrap in buffered stream
-                let mut buffered_stream = BufStream::with_capacities(
-                    conf.tcp_read_buff_size.unwrap_or(DFLT_READ_BUF_SIZE),
-                    conf.tcp_write_buff_size.unwrap_or(DFLT_WRITE_BUF_SIZE),
-                    stream,
-                );
-
-                // try initial handshake
-                match handshake(&mut buffered_stream, conf) {
-                    Ok(_) => Ok(Connection {
-                        stream: Mutex::new(buffered_stream),
-                    }),
-                    Err(err) => Err(err),
-                }
+        let deadline = conf.operation_timeout.map(Deadline::new);
+        let transport = Connection::transport(conf);
+        Ok(Connection {
+            conf: conf.clone(),
+            limits: conf.decode_limits(),
+            schemas: Arc::default(),
+            slot: Mutex::new(StreamSlot {
+                stream: Some(Connection::connect(
+                    conf,
+                    transport.as_ref(),
+                    deadline.as_ref(),
+                )?),
+                busy: false,
+            }),
+            idle: Condvar::new(),
+            transport,
+        })
+    }
+
+    /// Schemas of the binary types known to this connection
+    pub(crate) fn schemas(&self) -> &SchemaRegistry {
+        &self.schemas
+    }
+
+    /// Configured transport or TCP, encrypted if TLS is configured
+    fn transport(conf: &ClientConfig) -> Arc<dyn Transport> {
+        let transport = conf
+            .transport
+            .clone()
+            .unwrap_or_else(|| Arc::new(TcpTransport::from_config(conf)));
+        #[cfg(any(feature = "rustls-tls", feature = "native-tls"))]
+        if let Some(tls_conf) = &conf.tls_conf {
+            return Arc::new(TlsTransport::new(transport, tls_conf.clone()));
+        }
+        transport
+    }
+
+    fn connect(
+        conf: &ClientConfig,
+        transport: &dyn Transport,
+        deadline: Option<&Deadline>,
+    ) -> IgniteResult<Stream> {
+        let mut stream = Connection::connect_any(&conf.addr, transport, deadline)?;
+        Connection::apply_deadline(stream.as_mut(), conf, deadline)?;
+
+        // wrap in buffered stream
+        let mut buffered_stream = BufStream::with_capacities(
+            conf.tcp_read_buff_size.unwrap_or(DFLT_READ_BUF_SIZE),
+            conf.tcp_write_buff_size.unwrap_or(DFLT_WRITE_BUF_SIZE),
+            stream,
+        );
+
+        // try initial handshake
+        handshake(&mut buffered_stream, conf)?;
+        Ok(buffered_stream)
+    }
+
+    /// Connects to the first available of the comma-separated addresses within the deadline
+    fn connect_any(
+        addrs: &str,
+        transport: &dyn Transport,
+        deadline: Option<&Deadline>,
+    ) -> IgniteResult<Box<dyn TransportStream>> {
+        let mut last_err = None;
+        for addr in addrs.split(',').map(str::trim) {
+            let timeout = deadline.map(Deadline::remaining).transpose()?;
+            match transport.connect(addr, timeout) {
+                Ok(stream) => return Ok(stream),
+                Err(err) => last_err = Some(err),
             }
-            Err(err) => Err(IgniteError::from(err)),
         }
+        Err(last_err.unwrap_or_else(|| {
+            IgniteError::from(io::Error::new(io::ErrorKind::InvalidInput, "No address"))
+        }))
     }
 
-    /// Send message and read response header. Acquires lock
-    pub(crate) fn send(&self, op_code: OpCode, data: impl WriteableReq) -> IgniteResult<()> {
-        let sock_lock = &mut *self.stream.lock().unwrap(); //acquire lock on socket
-        Connection::send_safe(sock_lock, op_code, data)
+    /// Send message and read response header. Acquires lock.
+    /// Default operation timeout is used if the timeout is not set
+    pub(crate) fn send(
+        &self,
+        op_code: OpCode,
+        data: impl WriteableReq,
+        timeout: Option<Duration>,
+    ) -> IgniteResult<()> {
+        self.with_retries(op_code, timeout, |stream| {
+            Connection::send_safe(stream, &self.limits, op_code, &data).map(|_| ())
+        })
     }
 
-    /// Send message, read response header and return a response. Acquires lock
+    /// Send message, read response header and return a response. Acquires lock.
+    /// Default operation timeout is used if the timeout is not set
     pub(crate) fn send_and_read<T: ReadableReq>(
         &self,
         op_code: OpCode,
         data: impl WriteableReq,
+        timeout: Option<Duration>,
+    ) -> IgniteResult<T> {
+        self.with_retries(op_code, timeout, |stream| {
+            with_schemas(&self.schemas, || {
+                Connection::send_and_read_safe(stream, &self.limits, op_code, &data)
+            })
+        })
+    }
+
+    /// Runs the request until it succeeds, the retry policy gives up or the deadline expires.
+    /// Only idempotent requests failed because of the connection are retried
+    fn with_retries<T>(
+        &self,
+        op_code: OpCode,
+        timeout: Option<Duration>,
+        request: impl Fn(&mut Stream) -> IgniteResult<T>,
     ) -> IgniteResult<T> {
-        let sock_lock = &mut *self.stream.lock().unwrap(); //acquire lock on socket
-        Connection::send_and_read_safe(sock_lock, op_code, data)
+        let deadline = timeout.or(self.conf.operation_timeout).map(Deadline::new);
+        let mut attempt = 0;
+        loop {
+            match self.with_stream(deadline.as_ref(), &request) {
+                Err(err) if err.is_connection_error() && op_code.is_idempotent() => {
+                    attempt += 1;
+                    match self.conf.retry_policy.retry_delay(attempt, &err) {
+                        Some(delay) => match &deadline {
+                            Some(deadline) => thread::sleep(delay.min(deadline.remaining()?)),
+                            None => thread::sleep(delay),
+                        },
+                        None => return Err(err),
+                    }
+                }
+                result => return result,
+            }
+        }
     }
 
+    /// Runs the request on the stream. Waits for other requests to complete.
+    /// The stream is dropped if the request fails, as the stream could be left in the middle
+    /// of a message. Server errors are the exception, as the whole response is read for them
+    fn with_stream<T>(
+        &self,
+        deadline: Option<&Deadline>,
+        request: impl Fn(&mut Stream) -> IgniteResult<T>,
+    ) -> IgniteResult<T> {
+        let mut guard = self.acquire(deadline)?;
+        let stream = match &mut guard.stream {
+            Some(stream) => stream,
+            None => guard.stream.get_or_insert(Connection::connect(
+                &self.conf,
+                self.transport.as_ref(),
+                deadline,
+            )?),
+        };
+        let result = Connection::apply_deadline(stream.get_mut().as_mut(), &self.conf, deadline)
+            .and_then(|_| request(stream));
+        match result {
+            Ok(_) | Err(IgniteError::Server { .. }) => result,
+            Err(err) => {
+                guard.stream = None;
+                match (deadline, err) {
+                    // socket timeout set by the deadline has expired
+                    (Some(deadline), IgniteError::Io(err))
+                        if err.kind() == io::ErrorKind::WouldBlock
+                            || err.kind() == io::ErrorKind::TimedOut =>
+                    {
+                        Err(IgniteError::Timeout(deadline.timeout))
+                    }
+                    (_, err) => Err(err),
+                }
+            }
+        }
+    }
+
+    /// Takes the stream for the request. Waits until the previous request returns it
+    fn acquire(&self, deadline: Option<&Deadline>) -> IgniteResult<StreamGuard<'_>> {
+        let mut slot = self.slot.lock().unwrap();
+        while slot.busy {
+            slot = match deadline {
+                Some(deadline) => {
+                    let remaining = deadline.remaining()?;
+                    self.idle.wait_timeout(slot, remaining).unwrap().0
+                }
+                None => self.idle.wait(slot).unwrap(),
+            };
+        }
+        slot.busy = true;
+        Ok(StreamGuard {
+            conn: self,
+            stream: slot.stream.take(),
+        })
+    }
+
+    /// Limits socket timeouts by the time left until the deadline
+    fn apply_deadline(
+        stream: &mut dyn TransportStream,
+        conf: &ClientConfig,
+        deadline: Option<&Deadline>,
+    ) -> IgniteResult<()> {
+        let (read_timeout, write_timeout) = match deadline {
+            Some(deadline) => {
+                let remaining = deadline.remaining()?;
+                (
+                    Some(
+                        conf.tcp_read_timeout
+                            .map_or(remaining, |t| t.min(remaining)),
+                    ),
+                    Some(
+                        conf.tcp_write_timeout
+                            .map_or(remaining, |t| t.min(remaining)),
+                    ),
+                )
+            }
+            None => (conf.tcp_read_timeout, conf.tcp_write_timeout),
+        };
+        stream.set_timeouts(read_timeout, write_timeout)?;
+        Ok(())
+    }
+
+    /// Sends message and reads the whole response.
+    /// Returns the response positioned after its header
     fn send_safe<RW: Read + Write>(
         con: &mut RW,
+        limits: &DecodeLimits,
         op_code: OpCode,
-        payload: impl WriteableReq,
-    ) -> IgniteResult<()> {
+        payload: &impl WriteableReq,
+    ) -> IgniteResult<Cursor<Vec<u8>>> {
         // write common message header
         Connection::write_req_header(con, payload.size(), op_code as i16)?;
 
@@ -89,20 +300,43 @@ impl Connection {
         // flush write buffer
         con.flush()?;
 
-        //read response
-        match Connection::read_resp_header(con)? {
-            Flag::Success => Ok(()),
-            Flag::Failure { err_msg } => Err(IgniteError::from(err_msg.as_str())),
+        // read the whole response, so no value could be larger than the response itself
+        let mut message = Cursor::new(read_message(con, limits)?);
+        match Connection::decode(limits, &mut message, Connection::read_resp_header)? {
+            Flag::Success => Ok(message),
+            Flag::Failure {
+                status: STATUS_AUTH_FAILED,
+                err_msg,
+            } => Err(IgniteError::Authentication(err_msg)),
+            Flag::Failure { status, err_msg } => Err(IgniteError::Server {
+                status,
+                message: err_msg,
+            }),
         }
     }
 
     fn send_and_read_safe<T: ReadableReq, RW: Read + Write>(
         buf: &mut RW,
+        limits: &DecodeLimits,
         op_code: OpCode,
-        data: impl WriteableReq,
+        data: &impl WriteableReq,
+    ) -> IgniteResult<T> {
+        let mut message = Connection::send_safe(buf, limits, op_code, data)?; //send request and read the response
+        Connection::decode(limits, &mut message, T::read) //unpack the response bytes into an actual type
+    }
+
+    /// Decodes the response which is already read as a whole. Running out of its bytes
+    /// is a malformed response rather than a connection failure, so it's a protocol error
+    fn decode<T>(
+        limits: &DecodeLimits,
+        message: &mut Cursor<Vec<u8>>,
+        read: impl FnOnce(&mut Cursor<Vec<u8>>) -> IgniteResult<T>,
     ) -> IgniteResult<T> {
-        Connection::send_safe(buf, op_code, data)?; //send request and read the response
-        T::read(buf) //unpack the input bytes into an actual type
+        let limits = limits.within_message(message.get_ref().len());
+        with_decode_limits(limits, || read(message)).map_err(|err| match err {
+            IgniteError::Io(err) => IgniteError::Protocol(format!("Malformed response: {}", err)),
+            err => err,
+        })
     }
 
     /// Returns binary repr of standard request header
@@ -117,44 +351,129 @@ impl Connection {
         Ok(())
     }
 
-    /// Reads standard response header
+    /// Reads standard response header. The length is already read
     fn read_resp_header(reader: &mut impl Read) -> IgniteResult<Flag> {
-        let _ = read_i32(reader)?;
         let _ = read_i64(reader)?;
         match read_i32(reader)? {
             0 => Ok(Success),
-            _ => {
+            status => {
                 let err_msg = String::read(reader)?;
                 Ok(Failure {
-                    err_msg: err_msg.unwrap(),
+                    status,
+                    err_msg: err_msg.unwrap_or_default(),
                 })
             }
         }
     }
+}
+
+/// Returns the stream to the connection, so the next request could use it
+struct StreamGuard<'a> {
+    conn: &'a Connection,
+    stream: Option<Stream>,
+}
 
-    #[cfg(feature = "ssl")]
-    fn wrap_tls_stream(
-        conf: &(rustls::ClientConfig, String),
-        stream: TcpStream,
-    ) -> IgniteResult<rustls::StreamOwned<rustls::ClientSession, TcpStream>> {
-        let hostname = webpki::DNSNameRef::try_from_ascii_str(&conf.1)?;
-        let tls_session = rustls::ClientSession::new(&Arc::new(conf.0.clone()), hostname);
-        let tls_stream = rustls::StreamOwned::new(tls_session, stream);
-        Ok(tls_stream)
+impl Drop for StreamGuard<'_> {
+    fn drop(&mut self) {
+        let mut slot = match self.conn.slot.lock() {
+            Ok(slot) => slot,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        // the request has panicked in the middle of a message
+        if !thread::panicking() {
+            slot.stream = self.stream.take();
+        }
+        slot.busy = false;
+        self.conn.idle.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read, Write};
+
+    use crate::api::cache_config::{CacheGetNamesReq, CacheGetNamesResp};
+    use crate::api::OpCode;
+    use crate::connection::Connection;
+    use crate::protocol::{write_i32, write_i64, write_string_type_code, DecodeLimits};
+
+    /// Stream which replies with the prepared bytes
+    struct Duplex {
+        input: Cursor<Vec<u8>>,
+        output: Vec<u8>,
     }
 
-    fn configure_tcp(stream: &TcpStream, conf: &ClientConfig) -> io::Result<()> {
-        stream.set_read_timeout(conf.tcp_read_timeout)?;
-        stream.set_write_timeout(conf.tcp_write_timeout)?;
-        if let Some(nodelay) = conf.tcp_nodelay {
-            stream.set_nodelay(nodelay)?;
+    impl Read for Duplex {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.input.read(buf)
         }
-        if let Some(nonblocking) = conf.tcp_nonblocking {
-            stream.set_nonblocking(nonblocking)?;
+    }
+
+    impl Write for Duplex {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.output.write(buf)
         }
-        if let Some(ttl) = conf.tcp_ttl {
-            stream.set_ttl(ttl)?;
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
         }
-        Ok(())
+    }
+
+    fn get_names(response: Vec<u8>, limits: &DecodeLimits) -> Result<Vec<String>, String> {
+        let mut duplex = Duplex {
+            input: Cursor::new(response),
+            output: Vec::new(),
+        };
+        Connection::send_and_read_safe::<CacheGetNamesResp, _>(
+            &mut duplex,
+            limits,
+            OpCode::CacheGetNames,
+            &CacheGetNamesReq {},
+        )
+        .map(|resp| resp.names)
+        .map_err(|err| err.to_string())
+    }
+
+    fn names_response(string_len: i32) -> Vec<u8> {
+        let mut body = Vec::new();
+        write_i64(&mut body, 0).unwrap(); // request id
+        write_i32(&mut body, 0).unwrap(); // status
+        write_i32(&mut body, 1).unwrap(); // names count
+        write_string_type_code(&mut body, "cache").unwrap();
+        let len_offset = body.len() - "cache".len() - 4;
+        body[len_offset..len_offset + 4].copy_from_slice(&string_len.to_le_bytes());
+
+        let mut response = Vec::new();
+        write_i32(&mut response, body.len() as i32).unwrap();
+        response.extend(body);
+        response
+    }
+
+    #[test]
+    fn test_message_limits() {
+        let limits = DecodeLimits {
+            message: 64,
+            ..DecodeLimits::default()
+        };
+        assert_eq!(
+            get_names(names_response(5), &limits).unwrap(),
+            vec!["cache"]
+        );
+
+        // string can't be larger than the message, whatever its limit is
+        let err = get_names(names_response(1 << 30), &limits).unwrap_err();
+        assert_eq!(
+            err,
+            "Protocol error: String of size 1073741824 exceeds the limit of 26!"
+        );
+
+        // message above the limit isn't read
+        let mut response = Vec::new();
+        write_i32(&mut response, 1 << 30).unwrap();
+        let err = get_names(response, &limits).unwrap_err();
+        assert_eq!(
+            err,
+            "Protocol error: Message of size 1073741824 exceeds the limit of 64!"
+        );
     }
 }
@@ -0,0 +1,310 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::IgniteError;
+
+/// Largest power of 10 that fits into u32. Used to convert magnitude in chunks
+const CHUNK_BASE: u32 = 1_000_000_000;
+/// Number of decimal digits in one chunk
+const CHUNK_DIGITS: usize = 9;
+/// Zeros a plain string could be padded with. Scale of the decoded decimal could be
+/// anything up to i32::MAX, so decimals which need more zeros are formatted with an exponent
+const MAX_PADDING_ZEROS: usize = 1000;
+
+/// Arbitrary-precision decimal number. Corresponds to the java.math.BigDecimal
+///
+/// The value is `(-1)^negative * magnitude * 10^(-scale)`,
+/// so "-1.50" has a magnitude of 150 and a scale of 2.
+/// Like in Java, numbers with different scales are not equal: "1.5" != "1.50"
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Decimal {
+    negative: bool,
+    magnitude: Vec<u8>, // unsigned big-endian, without leading zeros
+    scale: i32,
+}
+
+impl Decimal {
+    /// Creates a decimal from the sign, unsigned big-endian magnitude and scale
+    pub fn new(negative: bool, magnitude: Vec<u8>, scale: i32) -> Decimal {
+        let mut magnitude = magnitude;
+        strip_leading_zeros(&mut magnitude);
+        Decimal {
+            negative: negative && !magnitude.is_empty(),
+            magnitude,
+            scale,
+        }
+    }
+
+    /// Creates a decimal from the Ignite's binary representation:
+    /// big-endian magnitude with the highest bit being the sign
+    pub fn from_java_bytes(scale: i32, bytes: &[u8]) -> Decimal {
+        let mut magnitude = bytes.to_vec();
+        let negative = match magnitude.first_mut() {
+            Some(first) if *first & 0x80 != 0 => {
+                *first &= 0x7F;
+                true
+            }
+            _ => false,
+        };
+        Decimal::new(negative, magnitude, scale)
+    }
+
+    /// Returns the Ignite's binary representation of the magnitude:
+    /// big-endian magnitude with the highest bit being the sign
+    pub fn to_java_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.magnitude.len() + 1);
+        // the highest bit is reserved for sign
+        if !matches!(self.magnitude.first(), Some(first) if *first & 0x80 == 0) {
+            bytes.push(0);
+        }
+        bytes.extend_from_slice(&self.magnitude);
+        if self.negative {
+            bytes[0] |= 0x80;
+        }
+        bytes
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Unsigned big-endian magnitude of the unscaled value
+    pub fn magnitude(&self) -> &[u8] {
+        &self.magnitude
+    }
+
+    pub fn scale(&self) -> i32 {
+        self.scale
+    }
+}
+
+impl Default for Decimal {
+    fn default() -> Self {
+        Decimal::new(false, Vec::new(), 0)
+    }
+}
+
+macro_rules! from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Decimal {
+                fn from(value: $t) -> Self {
+                    Decimal::from(i128::from(value))
+                }
+            }
+        )*
+    };
+}
+
+from_integer!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+impl From<i128> for Decimal {
+    fn from(value: i128) -> Self {
+        Decimal::new(value < 0, value.unsigned_abs().to_be_bytes().to_vec(), 0)
+    }
+}
+
+impl fmt::Display for Decimal {
+    /// Formats decimal without an exponent, like Java's BigDecimal.toPlainString().
+    /// Decimals which need more than MAX_PADDING_ZEROS zeros for that are formatted
+    /// with an exponent, like BigDecimal.toString()
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut digits = magnitude_to_digits(&self.magnitude);
+        let padding = match self.scale {
+            scale if scale <= 0 => scale.unsigned_abs() as usize,
+            scale => (scale as usize + 1).saturating_sub(digits.len()),
+        };
+        if padding > MAX_PADDING_ZEROS {
+            // one digit before the point: 1234 with scale -2000 is 1.234E+2003
+            let exponent = digits.len() as i64 - 1 - i64::from(self.scale);
+            if digits.len() > 1 {
+                digits.insert(1, '.');
+            }
+            if self.negative {
+                digits.insert(0, '-');
+            }
+            return write!(f, "{}E{:+}", digits, exponent);
+        }
+        if self.scale <= 0 {
+            digits.push_str(&"0".repeat(self.scale.unsigned_abs() as usize));
+        } else {
+            let scale = self.scale as usize;
+            if digits.len() <= scale {
+                digits.insert_str(0, &"0".repeat(scale + 1 - digits.len()));
+            }
+            digits.insert(digits.len() - scale, '.');
+        }
+        if self.negative {
+            digits.insert(0, '-');
+        }
+        f.write_str(&digits)
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = IgniteError;
+
+    /// Parses a decimal like "-12.345" or "1.2E-3"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || IgniteError::InvalidValue("Invalid decimal string".to_owned());
+
+        let (negative, unsigned) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let (number, exponent) = match unsigned.find(['e', 'E']) {
+            Some(pos) => {
+                let exponent = unsigned[pos + 1..].parse::<i64>().map_err(|_| invalid())?;
+                (&unsigned[..pos], exponent)
+            }
+            None => (unsigned, 0),
+        };
+        let (int_part, frac_part) = match number.find('.') {
+            Some(pos) => (&number[..pos], &number[pos + 1..]),
+            None => (number, ""),
+        };
+
+        let digits: Vec<u8> = int_part.bytes().chain(frac_part.bytes()).collect();
+        if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+            return Err(invalid());
+        }
+        // the exponent of a formatted decimal could be out of i32, while its scale isn't
+        let scale = i64::try_from(frac_part.len())
+            .ok()
+            .and_then(|frac_len| frac_len.checked_sub(exponent))
+            .and_then(|scale| i32::try_from(scale).ok())
+            .ok_or_else(invalid)?;
+
+        let mut magnitude: Vec<u8> = Vec::new();
+        for chunk in digits.chunks(CHUNK_DIGITS) {
+            let value = chunk
+                .iter()
+                .fold(0u32, |acc, digit| acc * 10 + u32::from(digit - b'0'));
+            mul_add(&mut magnitude, 10u32.pow(chunk.len() as u32), value);
+        }
+        Ok(Decimal::new(negative, magnitude, scale))
+    }
+}
+
+/// Converts unsigned big-endian magnitude into decimal digits
+fn magnitude_to_digits(magnitude: &[u8]) -> String {
+    let mut magnitude = magnitude.to_vec();
+    let mut chunks: Vec<u32> = Vec::new(); // little-endian chunks of CHUNK_DIGITS digits
+    while !magnitude.is_empty() {
+        chunks.push(div_rem(&mut magnitude, CHUNK_BASE));
+    }
+    match chunks.split_last() {
+        None => "0".to_string(),
+        Some((most, rest)) => {
+            let mut digits = most.to_string();
+            for chunk in rest.iter().rev() {
+                digits.push_str(&format!("{:09}", chunk));
+            }
+            digits
+        }
+    }
+}
+
+/// Divides unsigned big-endian magnitude in-place. Returns remainder
+fn div_rem(magnitude: &mut Vec<u8>, divisor: u32) -> u32 {
+    let mut rem: u64 = 0;
+    for byte in magnitude.iter_mut() {
+        let acc = (rem << 8) | u64::from(*byte);
+        *byte = (acc / u64::from(divisor)) as u8;
+        rem = acc % u64::from(divisor);
+    }
+    strip_leading_zeros(magnitude);
+    rem as u32
+}
+
+/// Calculates `magnitude * mul + add` in-place for unsigned big-endian magnitude
+fn mul_add(magnitude: &mut Vec<u8>, mul: u32, add: u32) {
+    let mut carry: u64 = u64::from(add);
+    for byte in magnitude.iter_mut().rev() {
+        let acc = u64::from(*byte) * u64::from(mul) + carry;
+        *byte = acc as u8;
+        carry = acc >> 8;
+    }
+    while carry > 0 {
+        magnitude.insert(0, carry as u8);
+        carry >>= 8;
+    }
+}
+
+fn strip_leading_zeros(magnitude: &mut Vec<u8>) {
+    let zeros = magnitude.iter().take_while(|byte| **byte == 0).count();
+    magnitude.drain(..zeros);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Decimal;
+
+    #[test]
+    fn test_decimal_string_round_trip() {
+        for str in &[
+            "0",
+            "1",
+            "-1",
+            "2.0",
+            "-0.05",
+            "123456789012345678901234567890.123456789",
+            "-99999999999999999999.99",
+        ] {
+            let decimal: Decimal = str.parse().unwrap();
+            assert_eq!(decimal.to_string(), *str);
+        }
+
+        assert_eq!("1.2E-3".parse::<Decimal>().unwrap().to_string(), "0.0012");
+        assert_eq!("12e2".parse::<Decimal>().unwrap().to_string(), "1200");
+        assert_eq!("-0.00".parse::<Decimal>().unwrap().to_string(), "0.00");
+        assert!("".parse::<Decimal>().is_err());
+        assert!("1.2.3".parse::<Decimal>().is_err());
+        assert!("-".parse::<Decimal>().is_err());
+        assert!("1e".parse::<Decimal>().is_err());
+    }
+
+    #[test]
+    fn test_decimal_extreme_scale() {
+        // the scale read from the wire must not blow up the plain string
+        let decimal = Decimal::new(true, vec![0x04, 0xD2], i32::MIN);
+        assert_eq!(decimal.to_string(), "-1.234E+2147483651");
+        assert_eq!(decimal.to_string().parse::<Decimal>().unwrap(), decimal);
+        let decimal = Decimal::new(false, vec![7], i32::MAX);
+        assert_eq!(decimal.to_string(), "7E-2147483647");
+        assert_eq!(decimal.to_string().parse::<Decimal>().unwrap(), decimal);
+        assert_eq!(
+            Decimal::new(false, vec![], i32::MIN).to_string(),
+            "0E+2147483648"
+        );
+
+        // up to MAX_PADDING_ZEROS the string is plain
+        let decimal = Decimal::new(false, vec![1], -1000);
+        assert_eq!(decimal.to_string(), format!("1{}", "0".repeat(1000)));
+        let decimal = Decimal::new(false, vec![1], 1000);
+        assert_eq!(decimal.to_string(), format!("0.{}1", "0".repeat(999)));
+    }
+
+    #[test]
+    fn test_decimal_java_bytes() {
+        // java: new BigDecimal("2.0") => scale 1, unscaled 20
+        let decimal: Decimal = "2.0".parse().unwrap();
+        assert_eq!(decimal.scale(), 1);
+        assert_eq!(decimal.to_java_bytes(), vec![20]);
+
+        // the highest bit is taken by sign, so an extra byte is needed
+        let decimal: Decimal = "-128".parse().unwrap();
+        assert_eq!(decimal.to_java_bytes(), vec![0x80, 0x80]);
+        assert_eq!(Decimal::from_java_bytes(0, &[0x80, 0x80]), decimal);
+
+        let decimal: Decimal = "-1.27".parse().unwrap();
+        assert_eq!(decimal.to_java_bytes(), vec![0xFF]);
+        assert_eq!(Decimal::from_java_bytes(2, &[0xFF]), decimal);
+
+        assert_eq!(Decimal::from(0).to_java_bytes(), vec![0]);
+        assert_eq!(Decimal::from_java_bytes(0, &[]), Decimal::from(0));
+        assert_eq!(Decimal::from(-300).magnitude(), &[0x01, 0x2C]);
+    }
+}
@@ -1,56 +1,193 @@
 use std::fmt::{Display, Formatter};
+use std::io;
 use std::io::Error as IoError;
+use std::time::Duration;
 use std::{convert, error};
-#[cfg(feature = "ssl")]
-use webpki::InvalidDNSNameError;
 
 pub type IgniteResult<T> = Result<T, IgniteError>;
 
+/// Generic failure of the request
+pub const STATUS_FAILED: i32 = 1;
+/// Request's op code is not supported by the server
+pub const STATUS_INVALID_OP_CODE: i32 = 2;
+/// Cache doesn't exist
+pub const STATUS_CACHE_DOES_NOT_EXIST: i32 = 1000;
+/// Cache already exists
+pub const STATUS_CACHE_EXISTS: i32 = 1001;
+/// Too many cursors are opened
+pub const STATUS_TOO_MANY_CURSORS: i32 = 1010;
+/// Resource (like a cursor) doesn't exist
+pub const STATUS_RESOURCE_DOES_NOT_EXIST: i32 = 1011;
+/// Operation is denied by the security
+pub const STATUS_SECURITY_VIOLATION: i32 = 1012;
+/// Authentication failed
+pub const STATUS_AUTH_FAILED: i32 = 2000;
+
+/// Version of the binary client protocol
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ProtocolVersion {
+    pub major: i16,
+    pub minor: i16,
+    pub patch: i16,
+}
+
+impl Display for ProtocolVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
 #[derive(Debug)]
-pub struct IgniteError {
-    pub(crate) desc: String,
+#[non_exhaustive]
+pub enum IgniteError {
+    /// I/O error of the connection
+    Io(IoError),
+    /// Server rejected the handshake for a reason other than the credentials,
+    /// e.g. the protocol version. Contains the version proposed by the server
+    Handshake {
+        server_version: ProtocolVersion,
+        message: String,
+    },
+    /// Server rejected the credentials
+    Authentication(String),
+    /// Server failed to process the request. Status codes are listed as `STATUS_*` constants
+    Server { status: i32, message: String },
+    /// Malformed data, which can't be encoded or decoded
+    Protocol(String),
+    /// Type which is known to the protocol but is not supported by the client
+    UnsupportedType(String),
+    /// Value which doesn't fit into the target type or can't be parsed
+    InvalidValue(String),
+    /// Invalid client configuration
+    InvalidConfig(String),
+    /// Operation hasn't completed within the timeout
+    Timeout(Duration),
+    /// TLS failure
+    Tls(Box<dyn error::Error + Send + Sync>),
+}
+
+impl IgniteError {
+    /// Status code of the server's response, if the error is returned by the server
+    pub fn status(&self) -> Option<i32> {
+        match self {
+            IgniteError::Server { status, .. } => Some(*status),
+            IgniteError::Authentication(_) => Some(STATUS_AUTH_FAILED),
+            _ => None,
+        }
+    }
+
+    /// Whether the connection failed, so the operation could succeed after reconnecting.
+    /// Values which can't be encoded fail with `InvalidInput` or `InvalidData`,
+    /// so these aren't connection errors
+    pub fn is_connection_error(&self) -> bool {
+        match self {
+            IgniteError::Io(err) => !matches!(
+                err.kind(),
+                io::ErrorKind::InvalidInput | io::ErrorKind::InvalidData
+            ),
+            _ => false,
+        }
+    }
 }
 
-impl error::Error for IgniteError {}
+impl error::Error for IgniteError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            IgniteError::Io(err) => Some(err),
+            IgniteError::Tls(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
 
 impl Display for IgniteError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.desc)
+        match self {
+            IgniteError::Io(err) => write!(f, "I/O error: {}", err),
+            IgniteError::Handshake {
+                server_version,
+                message,
+            } => write!(
+                f,
+                "Handshake error: server proposed v{}: {}",
+                server_version, message
+            ),
+            IgniteError::Authentication(message) => {
+                write!(f, "Authentication failed: {}", message)
+            }
+            IgniteError::Server { status, message } => {
+                write!(f, "Server error (status {}): {}", status, message)
+            }
+            IgniteError::Protocol(message) => write!(f, "Protocol error: {}", message),
+            IgniteError::UnsupportedType(message) => write!(f, "Unsupported type: {}", message),
+            IgniteError::InvalidValue(message) => write!(f, "Invalid value: {}", message),
+            IgniteError::InvalidConfig(message) => write!(f, "Invalid config: {}", message),
+            IgniteError::Timeout(timeout) => write!(f, "Operation timed out after {:?}", timeout),
+            IgniteError::Tls(err) => write!(f, "TLS error: {}", err),
+        }
     }
 }
 
 impl convert::From<IoError> for IgniteError {
     fn from(e: IoError) -> Self {
-        IgniteError {
-            desc: e.to_string(),
+        // the TLS backends report the handshake failures and the received alerts as I/O errors
+        #[cfg(any(feature = "rustls-tls", feature = "native-tls"))]
+        if is_tls_failure(&e) {
+            let inner = e.into_inner().expect("inner error is checked");
+            return match inner.downcast::<crate::tls::TlsFailure>() {
+                Ok(failure) => IgniteError::Tls(failure.0),
+                Err(inner) => IgniteError::Tls(inner),
+            };
         }
+        IgniteError::Io(e)
     }
 }
 
-impl convert::From<&str> for IgniteError {
-    fn from(desc: &str) -> Self {
-        IgniteError {
-            desc: String::from(desc),
-        }
+#[cfg(any(feature = "rustls-tls", feature = "native-tls"))]
+fn is_tls_failure(e: &IoError) -> bool {
+    match e.get_ref() {
+        #[cfg(feature = "rustls-tls")]
+        Some(inner) if inner.is::<rustls::Error>() => true,
+        Some(inner) => inner.is::<crate::tls::TlsFailure>(),
+        None => false,
     }
 }
 
-impl convert::From<Option<String>> for IgniteError {
-    fn from(desc: Option<String>) -> Self {
-        match desc {
-            Some(desc) => IgniteError { desc },
-            None => IgniteError {
-                desc: "Ignite client error! No description provided".to_owned(),
-            },
-        }
+#[cfg(feature = "serde")]
+impl serde::ser::Error for IgniteError {
+    fn custom<T: Display>(msg: T) -> Self {
+        IgniteError::Protocol(msg.to_string())
     }
 }
 
-#[cfg(feature = "ssl")]
-impl convert::From<InvalidDNSNameError> for IgniteError {
-    fn from(err: InvalidDNSNameError) -> Self {
-        IgniteError {
-            desc: err.to_string(),
-        }
+#[cfg(feature = "serde")]
+impl serde::de::Error for IgniteError {
+    fn custom<T: Display>(msg: T) -> Self {
+        IgniteError::Protocol(msg.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+    use std::io::ErrorKind;
+
+    #[test]
+    fn test_source_chain() {
+        let err = IgniteError::from(IoError::new(ErrorKind::ConnectionReset, "reset"));
+        let source = err.source().unwrap().downcast_ref::<IoError>().unwrap();
+        assert_eq!(source.kind(), ErrorKind::ConnectionReset);
+
+        let err = IgniteError::Server {
+            status: STATUS_CACHE_DOES_NOT_EXIST,
+            message: "Cache does not exist".to_owned(),
+        };
+        assert!(err.source().is_none());
+        assert_eq!(err.status(), Some(STATUS_CACHE_DOES_NOT_EXIST));
+        assert_eq!(
+            err.to_string(),
+            "Server error (status 1000): Cache does not exist"
+        );
     }
 }
@@ -1,9 +1,10 @@
 use std::io::{Read, Write};
 
 use crate::api::OpCode;
-use crate::error::{IgniteError, IgniteResult};
+use crate::error::{IgniteError, IgniteResult, ProtocolVersion, STATUS_AUTH_FAILED};
 use crate::protocol::{
-    read_i16, read_i32, read_u8, write_i16, write_i32, write_string_type_code, write_u8,
+    read_i16, read_i32, read_message, read_u8, with_decode_limits, write_i16, write_i32,
+    write_string_type_code, write_u8,
 };
 use crate::{ClientConfig, ReadableType};
 
@@ -18,7 +19,9 @@ pub(crate) fn handshake<T: Read + Write>(conn: &mut T, conf: &ClientConfig) -> I
     let mut msg_size = MIN_HANDSHAKE_SIZE;
 
     if conf.username.is_some() != conf.password.is_some() {
-        return Err(IgniteError::from("Both username and password expected!"));
+        return Err(IgniteError::InvalidConfig(
+            "Both username and password expected!".to_owned(),
+        ));
     }
 
     if let Some(ref user) = conf.username {
@@ -47,28 +50,88 @@ pub(crate) fn handshake<T: Read + Write>(conn: &mut T, conf: &ClientConfig) -> I
     // send bytes
     conn.flush()?;
 
-    // read header
-    let _ = read_i32(conn)?;
-    match read_u8(conn)? {
-        1 => Ok(()),
-        _ => match read_handshake_err(conn) {
-            Ok(msg) => Err(IgniteError::from(msg.as_str())),
-            Err(err) => Err(err),
-        },
+    // read the whole response, so no value could be larger than the response itself
+    let limits = conf.decode_limits();
+    let message = read_message(conn, &limits)?;
+    let limits = limits.within_message(message.len());
+    with_decode_limits(limits, || {
+        let mut reader = message.as_slice();
+        match read_u8(&mut reader)? {
+            1 => Ok(()),
+            _ => Err(read_handshake_err(&mut reader)?),
+        }
+    })
+}
+
+/// Reads the reason of the rejected handshake.
+/// Newer servers follow the message with the status. Older ones don't,
+/// so the rejected credentials are recognized by the message
+fn read_handshake_err(reader: &mut &[u8]) -> IgniteResult<IgniteError> {
+    let server_version = ProtocolVersion {
+        major: read_i16(reader)?,
+        minor: read_i16(reader)?,
+        patch: read_i16(reader)?,
+    };
+    let message = String::read(reader)?.unwrap_or_default();
+    let auth_failed = if reader.is_empty() {
+        is_auth_message(&message)
+    } else {
+        read_i32(reader)? == STATUS_AUTH_FAILED
+    };
+
+    if auth_failed {
+        Ok(IgniteError::Authentication(message))
+    } else {
+        Ok(IgniteError::Handshake {
+            server_version,
+            message,
+        })
     }
 }
 
-fn read_handshake_err<T: Read + Write>(conn: &mut T) -> IgniteResult<String> {
-    let major_v = read_i16(conn)?;
-    let minor_v = read_i16(conn)?;
-    let patch_v = read_i16(conn)?;
-    let err_msg = String::read(conn)?;
-
-    Ok(format!(
-        "Handshake error: v{}.{}.{} err: {}",
-        major_v,
-        minor_v,
-        patch_v,
-        err_msg.unwrap()
-    ))
+/// Whether the message is the one of the rejected credentials,
+/// like "The user name or password is incorrect"
+fn is_auth_message(message: &str) -> bool {
+    let message = message.to_lowercase();
+    message.contains("password") || message.contains("authenticat")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::write_i32;
+
+    fn handshake_err(message: &str, status: Option<i32>) -> IgniteError {
+        let mut bytes = Vec::new();
+        write_i16(&mut bytes, V_MAJOR).unwrap();
+        write_i16(&mut bytes, V_MINOR).unwrap();
+        write_i16(&mut bytes, V_PATCH).unwrap();
+        write_string_type_code(&mut bytes, message).unwrap();
+        if let Some(status) = status {
+            write_i32(&mut bytes, status).unwrap();
+        }
+        read_handshake_err(&mut bytes.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn test_handshake_err() {
+        let err = handshake_err("The user name or password is incorrect", None);
+        assert!(matches!(err, IgniteError::Authentication(_)), "{}", err);
+        let err = handshake_err("Access denied", Some(STATUS_AUTH_FAILED));
+        assert!(matches!(err, IgniteError::Authentication(_)), "{}", err);
+
+        // other rejections at the same version are not the authentication ones
+        match handshake_err("Node is stopping", None) {
+            IgniteError::Handshake {
+                server_version,
+                message,
+            } => {
+                assert_eq!(server_version.minor, V_MINOR);
+                assert_eq!(message, "Node is stopping");
+            }
+            other => panic!("Handshake error expected: {}", other),
+        }
+        let err = handshake_err("Authentication is not available", Some(1));
+        assert!(matches!(err, IgniteError::Handshake { .. }), "{}", err);
+    }
 }
@@ -1,3 +1,4 @@
+use crate::api::binary_type::{BinaryTypeGetReq, BinaryTypeGetResp};
 use crate::api::cache_config::{
     CacheCreateWithConfigReq, CacheCreateWithNameReq, CacheDestroyReq, CacheGetConfigReq,
     CacheGetConfigResp, CacheGetNamesReq, CacheGetNamesResp, CacheGetOrCreateWithConfigReq,
@@ -5,28 +6,46 @@ use crate::api::cache_config::{
 };
 use crate::api::OpCode;
 
-use crate::cache::{Cache, CacheConfiguration};
+use crate::cache::{Cache, CacheConfiguration, QueryEntity};
 use crate::connection::Connection;
-use crate::error::IgniteResult;
-use crate::protocol::{read_wrapped_data, TypeCode};
+use crate::error::{IgniteError, IgniteResult};
+use crate::protocol::binary_type::BinaryType;
+use crate::protocol::{
+    read_wrapped_data, DecodeLimits, TypeCode, DFLT_MAX_MESSAGE_SIZE, DFLT_MAX_NESTING_DEPTH,
+};
+use crate::retry::{NeverRetry, RetryPolicy};
+use crate::transport::Transport;
 use crate::utils::string_to_java_hashcode;
 
+use std::fmt;
 use std::io;
 use std::io::{Read, Write};
+use std::str::FromStr;
 use std::sync::Arc;
 
-#[cfg(feature = "ssl")]
-use rustls;
 use std::time::Duration;
 
+pub mod affinity;
 mod api;
 pub mod cache;
+pub mod config;
 mod connection;
+mod decimal;
 pub mod error;
 mod handshake;
+#[cfg(feature = "mock")]
+pub mod mock;
 pub mod protocol;
+pub mod retry;
+#[cfg(feature = "serde")]
+pub mod serde_binary;
+#[cfg(any(feature = "rustls-tls", feature = "native-tls"))]
+pub mod tls;
+pub mod transport;
 pub mod utils;
 
+pub use crate::decimal::Decimal;
+
 /// Implementations of this trait could be serialized into Ignite byte sequence
 /// It is indented to be implemented by structs which represents requests
 pub(crate) trait WriteableReq {
@@ -55,6 +74,60 @@ pub trait ReadableType: Sized {
     }
 }
 
+/// Marks types which are written as objects, not as primitives.
+/// `Vec<T>` of such types is written as a Collection (java.util.ArrayList),
+/// while vectors of primitives like `Vec<i32>` are written as primitive arrays.
+/// Implemented by #[derive(IgniteObj)] as well
+pub trait CollectionItem: WritableType + ReadableType {}
+
+/// Types which have a dedicated array type in the binary protocol, like String (String array).
+/// `Box<[T]>` of such types is written as that array instead of an object array.
+/// Option of such type is an array item as well, representing a NULL item
+pub trait ArrayItem: WritableType + ReadableType {
+    /// Type code of the array
+    const ARRAY_TYPE_CODE: TypeCode;
+    /// Type id of items, written before the array length. Required only by the enum array
+    const ARRAY_TYPE_ID: Option<i32> = None;
+}
+
+/// Java-compatible hash code of a cache key.
+/// Ignite maps keys to partitions by their hash codes, so a key written from Rust
+/// must hash exactly like its Java counterpart to be found by Java and SQL readers.
+/// Implemented by #[derive(IgniteObj)] as well
+pub trait HashCode {
+    /// Name of the affinity key field, set by #[ignite(affinity_key)]
+    const AFFINITY_KEY_FIELD: Option<&'static str> = None;
+
+    fn hash_code(&self) -> i32;
+
+    /// Hash code used to map the key to a partition.
+    /// It's the hash code of the affinity key field if there is one, otherwise of the key itself
+    fn affinity_hash_code(&self) -> i32 {
+        self.hash_code()
+    }
+}
+
+/// Name of the Java class the type corresponds to, like "java.lang.Long" for i64.
+/// Used as a type of query entity fields. Implemented by #[derive(IgniteObj)] as well
+pub trait JavaType {
+    const JAVA_TYPE_NAME: &'static str;
+}
+
+/// Types which describe a SQL table.
+/// Intended to be implemented by #[derive(IgniteQueryEntity)]
+///
+/// use ignite_rs_derive::{IgniteObj, IgniteQueryEntity};
+/// #[derive(IgniteObj, IgniteQueryEntity)]
+/// struct Person {
+///     #[ignite(key)]
+///     id: i64,
+///     #[ignite(index, not_null)]
+///     name: String,
+/// }
+pub trait IgniteQueryEntity {
+    fn query_entity() -> QueryEntity;
+}
+
 /// Combines the WritableType and ReadableType crates.
 /// Intended to be used in the #[derive(IgniteObj)] attribute to automatically generate
 /// serialization/deserialization for the user-defined structs
@@ -69,9 +142,10 @@ pub trait IgniteObj: WritableType + ReadableType {}
 
 /// Ignite Client configuration.
 /// Allows the configuration of user's credentials, tcp configuration
-/// and SSL/TLS, if "ssl" feature is enabled
+/// and SSL/TLS, if one of the TLS features is enabled
 #[derive(Clone)]
 pub struct ClientConfig {
+    /// Address of the node. Several comma-separated addresses are tried in order
     pub addr: String,
     pub username: Option<String>,
     pub password: Option<String>,
@@ -82,12 +156,31 @@ pub struct ClientConfig {
     pub tcp_ttl: Option<u32>,
     pub tcp_read_buff_size: Option<usize>,
     pub tcp_write_buff_size: Option<usize>,
-    #[cfg(feature = "ssl")]
-    pub tls_conf: (rustls::ClientConfig, String),
+    /// Maximum size of a message read from the server in bytes. 256 MiB by default
+    pub max_message_size: Option<usize>,
+    /// Maximum size of a string in bytes. Limited only by the message size by default
+    pub max_string_size: Option<usize>,
+    /// Maximum number of items in an array, a collection or a map.
+    /// Limited only by the message size by default
+    pub max_array_size: Option<usize>,
+    /// Maximum size of a complex object in bytes. Limited only by the message size by default
+    pub max_object_size: Option<usize>,
+    /// Policy of retrying idempotent operations after connection failures. Never retries by default
+    pub retry_policy: Arc<dyn RetryPolicy>,
+    /// Default timeout of an operation including its retries. No timeout by default.
+    /// Could be overridden for a cache with `Cache::with_timeout`
+    pub operation_timeout: Option<Duration>,
+    /// TLS configuration. The certificate is verified against the host being connected
+    /// unless the server name is overridden. The connection is not encrypted if it's not set
+    #[cfg(any(feature = "rustls-tls", feature = "native-tls"))]
+    pub tls_conf: Option<tls::TlsConfig>,
+    /// Opens the streams to the nodes, e.g. through a proxy. TCP by default.
+    /// The streams are encrypted by TLS if it's configured
+    pub transport: Option<Arc<dyn Transport>>,
 }
 
 impl ClientConfig {
-    #[cfg(not(feature = "ssl"))]
+    /// Configuration with the defaults. Use `ClientConfig::builder` for the validated one
     pub fn new(addr: &str) -> ClientConfig {
         ClientConfig {
             addr: addr.into(),
@@ -100,23 +193,27 @@ impl ClientConfig {
             tcp_ttl: None,
             tcp_read_buff_size: None,
             tcp_write_buff_size: None,
+            max_message_size: None,
+            max_string_size: None,
+            max_array_size: None,
+            max_object_size: None,
+            retry_policy: Arc::new(NeverRetry),
+            operation_timeout: None,
+            #[cfg(any(feature = "rustls-tls", feature = "native-tls"))]
+            tls_conf: None,
+            transport: None,
         }
     }
 
-    #[cfg(feature = "ssl")]
-    pub fn new(addr: &str, client_conf: rustls::ClientConfig, hostname: String) -> ClientConfig {
-        ClientConfig {
-            addr: addr.into(),
-            username: None,
-            password: None,
-            tcp_nodelay: None,
-            tcp_nonblocking: None,
-            tcp_read_timeout: None,
-            tcp_write_timeout: None,
-            tcp_ttl: None,
-            tcp_read_buff_size: None,
-            tcp_write_buff_size: None,
-            tls_conf: (client_conf, hostname),
+    /// Limits of the values read from the server
+    pub(crate) fn decode_limits(&self) -> DecodeLimits {
+        let message = self.max_message_size.unwrap_or(DFLT_MAX_MESSAGE_SIZE);
+        DecodeLimits {
+            message,
+            string: self.max_string_size.unwrap_or(message),
+            array: self.max_array_size.unwrap_or(message),
+            object: self.max_object_size.unwrap_or(message),
+            depth: DFLT_MAX_NESTING_DEPTH,
         }
     }
 }
@@ -160,6 +257,9 @@ pub trait Ignite {
     fn get_cache_config(&mut self, name: &str) -> IgniteResult<CacheConfiguration>;
     /// Destroys the cache. All the data is removed.
     fn destroy_cache(&mut self, name: &str) -> IgniteResult<()>;
+    /// Returns metadata of the binary type. None if there is no such type.
+    /// Schemas of the type are registered, so its objects with compact footers become readable
+    fn get_binary_type(&mut self, type_id: i32) -> IgniteResult<Option<BinaryType>>;
 }
 
 /// Basic Ignite Client
@@ -187,9 +287,9 @@ impl Client {
 
 impl Ignite for Client {
     fn get_cache_names(&mut self) -> IgniteResult<Vec<String>> {
-        let resp: CacheGetNamesResp = self
-            .conn
-            .send_and_read(OpCode::CacheGetNames, CacheGetNamesReq {})?;
+        let resp: CacheGetNamesResp =
+            self.conn
+                .send_and_read(OpCode::CacheGetNames, CacheGetNamesReq {}, None)?;
         Ok(resp.names)
     }
 
@@ -201,6 +301,7 @@ impl Ignite for Client {
             .send(
                 OpCode::CacheCreateWithName,
                 CacheCreateWithNameReq::from(name),
+                None,
             )
             .map(|_| {
                 Cache::new(
@@ -219,6 +320,7 @@ impl Ignite for Client {
             .send(
                 OpCode::CacheGetOrCreateWithName,
                 CacheGetOrCreateWithNameReq::from(name),
+                None,
             )
             .map(|_| {
                 Cache::new(
@@ -237,6 +339,7 @@ impl Ignite for Client {
             .send(
                 OpCode::CacheCreateWithConfiguration,
                 CacheCreateWithConfigReq { config },
+                None,
             )
             .map(|_| {
                 Cache::new(
@@ -258,6 +361,7 @@ impl Ignite for Client {
             .send(
                 OpCode::CacheGetOrCreateWithConfiguration,
                 CacheGetOrCreateWithConfigReq { config },
+                None,
             )
             .map(|_| {
                 Cache::new(
@@ -269,19 +373,31 @@ impl Ignite for Client {
     }
 
     fn get_cache_config(&mut self, name: &str) -> IgniteResult<CacheConfiguration> {
-        let resp: CacheGetConfigResp = self
-            .conn
-            .send_and_read(OpCode::CacheGetConfiguration, CacheGetConfigReq::from(name))?;
+        let resp: CacheGetConfigResp = self.conn.send_and_read(
+            OpCode::CacheGetConfiguration,
+            CacheGetConfigReq::from(name),
+            None,
+        )?;
         Ok(resp.config)
     }
 
     fn destroy_cache(&mut self, name: &str) -> IgniteResult<()> {
         self.conn
-            .send(OpCode::CacheDestroy, CacheDestroyReq::from(name))
+            .send(OpCode::CacheDestroy, CacheDestroyReq::from(name), None)
+    }
+
+    fn get_binary_type(&mut self, type_id: i32) -> IgniteResult<Option<BinaryType>> {
+        let resp: BinaryTypeGetResp =
+            self.conn
+                .send_and_read(OpCode::BinaryTypeGet, BinaryTypeGetReq { type_id }, None)?;
+        if let Some(binary_type) = &resp.binary_type {
+            self.conn.schemas().register(binary_type);
+        }
+        Ok(resp.binary_type)
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[allow(dead_code)]
 ///Value of an enumerable type. For such types defined only a finite number of named values.
 pub struct Enum {
@@ -290,3 +406,116 @@ pub struct Enum {
     /// Enumeration value ordinal.
     pub ordinal: i32,
 }
+
+/// Universally unique identifier. Corresponds to the java.util.UUID
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Uuid {
+    /// Most significant 64 bits.
+    pub most_sig_bits: u64,
+    /// Least significant 64 bits.
+    pub least_sig_bits: u64,
+}
+
+impl Uuid {
+    pub fn new(most_sig_bits: u64, least_sig_bits: u64) -> Uuid {
+        Uuid {
+            most_sig_bits,
+            least_sig_bits,
+        }
+    }
+
+    /// Creates UUID from its 16 bytes in RFC 4122 (big-endian) order
+    pub fn from_bytes(bytes: [u8; 16]) -> Uuid {
+        let value = u128::from_be_bytes(bytes);
+        Uuid::new((value >> 64) as u64, value as u64)
+    }
+
+    /// Returns 16 bytes of UUID in RFC 4122 (big-endian) order
+    pub fn as_bytes(&self) -> [u8; 16] {
+        ((u128::from(self.most_sig_bits) << 64) | u128::from(self.least_sig_bits)).to_be_bytes()
+    }
+}
+
+impl fmt::Display for Uuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msb = self.most_sig_bits;
+        let lsb = self.least_sig_bits;
+        write!(
+            f,
+            "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+            msb >> 32,
+            (msb >> 16) & 0xFFFF,
+            msb & 0xFFFF,
+            lsb >> 48,
+            lsb & 0xFFFF_FFFF_FFFF
+        )
+    }
+}
+
+impl FromStr for Uuid {
+    type Err = IgniteError;
+
+    /// Parses hyphenated form, like "123e4567-e89b-12d3-a456-426614174000"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let groups: Vec<&str> = s.split('-').collect();
+        let lens: Vec<usize> = groups.iter().map(|g| g.len()).collect();
+        // from_str_radix accepts a leading sign, so the digits are checked first
+        let is_hex = groups
+            .iter()
+            .all(|g| g.chars().all(|c| c.is_ascii_hexdigit()));
+        if lens != [8, 4, 4, 4, 12] || !is_hex {
+            return Err(IgniteError::InvalidValue("Invalid UUID string".to_owned()));
+        }
+        let hex: String = groups.concat();
+        u128::from_str_radix(&hex, 16)
+            .map(|value| Uuid::new((value >> 64) as u64, value as u64))
+            .map_err(|_| IgniteError::InvalidValue("Invalid UUID string".to_owned()))
+    }
+}
+
+/// Date. Corresponds to the java.util.Date and java.sql.Date
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Date {
+    /// Number of milliseconds elapsed since 00:00:00 1 Jan 1970 UTC.
+    pub millis: i64,
+}
+
+/// Time of the day. Corresponds to the java.sql.Time
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Time {
+    /// Number of milliseconds elapsed since midnight.
+    pub millis: i64,
+}
+
+/// Timestamp with nanosecond precision. Corresponds to the java.sql.Timestamp
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Timestamp {
+    /// Number of milliseconds elapsed since 00:00:00 1 Jan 1970 UTC.
+    pub millis: i64,
+    /// Nanosecond fraction of a millisecond.
+    pub nanos: i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Uuid;
+
+    #[test]
+    fn test_uuid_string_round_trip() {
+        let str = "123e4567-e89b-12d3-a456-426614174000";
+        let uuid: Uuid = str.parse().unwrap();
+        assert_eq!(
+            uuid,
+            Uuid::new(0x123e_4567_e89b_12d3, 0xa456_4266_1417_4000)
+        );
+        assert_eq!(uuid.to_string(), str);
+        assert_eq!(Uuid::from_bytes(uuid.as_bytes()), uuid);
+        assert!("123e4567e89b12d3a456426614174000".parse::<Uuid>().is_err());
+        assert!("123e4567-e89b-12d3-a456-42661417400g"
+            .parse::<Uuid>()
+            .is_err());
+        assert!("+1234567-e89b-12d3-a456-426614174000"
+            .parse::<Uuid>()
+            .is_err());
+    }
+}
@@ -0,0 +1,169 @@
+//! Server side of the cache configuration: reads the configuration the client creates caches with
+//! and writes it the way the node answers Cache Get Configuration
+
+use std::convert::TryFrom;
+use std::io;
+use std::io::{Read, Write};
+
+use crate::cache::{
+    AtomicityMode, CacheConfiguration, CacheMode, PartitionLossPolicy, RebalanceMode,
+    WriteSynchronizationMode,
+};
+use crate::error::{IgniteError, IgniteResult};
+use crate::protocol::cache_config::ConfigPropertyCode::*;
+use crate::protocol::cache_config::{
+    read_cache_key_configs, read_query_entities, write_cache_key_configs, write_query_entities,
+    ConfigPropertyCode,
+};
+use crate::protocol::{
+    read_bool, read_i16, read_i32, read_i64, read_string_not_null, write_bool, write_i32,
+    write_i64, write_null, write_string_type_code,
+};
+use crate::ReadableType;
+
+impl TryFrom<i16> for ConfigPropertyCode {
+    type Error = IgniteError;
+
+    fn try_from(value: i16) -> Result<Self, Self::Error> {
+        let code = match value {
+            0 => Name,
+            1 => CacheMode,
+            2 => CacheAtomicityMode,
+            3 => Backups,
+            4 => WriteSynchronizationMode,
+            5 => CopyOnRead,
+            6 => ReadFromBackup,
+            100 => DataRegionName,
+            101 => IsOnheapCacheEnabled,
+            200 => QueryEntities,
+            201 => QueryParallelism,
+            202 => QueryDetailMetricsSize,
+            203 => SqlSchema,
+            204 => SqlIndexInlineMaxSize,
+            205 => SqlEscapeAll,
+            206 => MaxQueryIterators,
+            300 => RebalanceMode,
+            301 => RebalanceDelay,
+            302 => RebalanceTimeout,
+            303 => RebalanceBatchSize,
+            304 => RebalanceBatchesPrefetchCount,
+            305 => RebalanceOrder,
+            306 => RebalanceThrottle,
+            400 => GroupName,
+            401 => CacheKeyConfigurations,
+            402 => DefaultLockTimeout,
+            403 => MaxConcurrentAsyncOps,
+            404 => PartitionLossPolicy,
+            405 => EagerTtl,
+            406 => StatisticsEnabled,
+            _ => {
+                return Err(IgniteError::Protocol(format!(
+                    "Cannot read cache property {}",
+                    value
+                )))
+            }
+        };
+        Ok(code)
+    }
+}
+
+/// Reads the properties written by `get_cache_configuration_bytes`.
+/// The absent ones keep their default values
+pub(crate) fn read_cache_configuration_properties(
+    reader: &mut impl Read,
+) -> IgniteResult<CacheConfiguration> {
+    let _ = read_i32(reader)?; // length
+    let count = read_i16(reader)?;
+    let mut config = CacheConfiguration::new("");
+    for _ in 0..count {
+        match ConfigPropertyCode::try_from(read_i16(reader)?)? {
+            Name => config.name = read_string_not_null(reader)?,
+            CacheMode => config.cache_mode = CacheMode::try_from(read_i32(reader)?)?,
+            CacheAtomicityMode => {
+                config.atomicity_mode = AtomicityMode::try_from(read_i32(reader)?)?
+            }
+            Backups => config.num_backup = read_i32(reader)?,
+            WriteSynchronizationMode => {
+                config.write_synchronization_mode =
+                    WriteSynchronizationMode::try_from(read_i32(reader)?)?
+            }
+            CopyOnRead => config.copy_on_read = read_bool(reader)?,
+            ReadFromBackup => config.read_from_backup = read_bool(reader)?,
+            DataRegionName => config.data_region_name = String::read(reader)?,
+            IsOnheapCacheEnabled => config.onheap_cache_enabled = read_bool(reader)?,
+            QueryEntities => config.query_entities = Some(read_query_entities(reader)?),
+            QueryParallelism => config.query_parallelism = read_i32(reader)?,
+            QueryDetailMetricsSize => config.query_detail_metrics_size = read_i32(reader)?,
+            SqlSchema => config.sql_schema = String::read(reader)?,
+            SqlIndexInlineMaxSize => config.sql_index_max_size = read_i32(reader)?,
+            SqlEscapeAll => config.sql_escape_all = read_bool(reader)?,
+            MaxQueryIterators => config.max_query_iterators = read_i32(reader)?,
+            RebalanceMode => config.rebalance_mode = RebalanceMode::try_from(read_i32(reader)?)?,
+            RebalanceDelay => config.rebalance_delay_ms = read_i64(reader)?,
+            RebalanceTimeout => config.rebalance_timeout_ms = read_i64(reader)?,
+            RebalanceBatchSize => config.rebalance_batch_size = read_i32(reader)?,
+            RebalanceBatchesPrefetchCount => {
+                config.rebalance_batches_prefetch_count = read_i64(reader)?
+            }
+            RebalanceOrder => config.rebalance_order = read_i32(reader)?,
+            RebalanceThrottle => config.rebalance_throttle_ms = read_i64(reader)?,
+            GroupName => config.group_name = String::read(reader)?,
+            CacheKeyConfigurations => {
+                config.cache_key_configurations = Some(read_cache_key_configs(reader)?)
+            }
+            DefaultLockTimeout => config.default_lock_timeout_ms = read_i64(reader)?,
+            MaxConcurrentAsyncOps => config.max_concurrent_async_operations = read_i32(reader)?,
+            PartitionLossPolicy => {
+                config.partition_loss_policy = PartitionLossPolicy::try_from(read_i32(reader)?)?
+            }
+            EagerTtl => config.eager_ttl = read_bool(reader)?,
+            StatisticsEnabled => config.statistics_enabled = read_bool(reader)?,
+        }
+    }
+    Ok(config)
+}
+
+/// Writes the configuration the way the node answers Cache Get Configuration,
+/// so it's read by `read_cache_configuration`
+pub(crate) fn write_cache_configuration(
+    writer: &mut dyn Write,
+    config: &CacheConfiguration,
+) -> io::Result<()> {
+    let write_opt_string = |writer: &mut dyn Write, v: &Option<String>| match v {
+        Some(v) => write_string_type_code(writer, v),
+        None => write_null(writer),
+    };
+    write_i32(writer, config.atomicity_mode.clone() as i32)?;
+    write_i32(writer, config.num_backup)?;
+    write_i32(writer, config.cache_mode.clone() as i32)?;
+    write_bool(writer, config.copy_on_read)?;
+    write_opt_string(writer, &config.data_region_name)?;
+    write_bool(writer, config.eager_ttl)?;
+    write_bool(writer, config.statistics_enabled)?;
+    write_opt_string(writer, &config.group_name)?;
+    write_i64(writer, config.default_lock_timeout_ms)?;
+    write_i32(writer, config.max_concurrent_async_operations)?;
+    write_i32(writer, config.max_query_iterators)?;
+    write_string_type_code(writer, &config.name)?;
+    write_bool(writer, config.onheap_cache_enabled)?;
+    write_i32(writer, config.partition_loss_policy.clone() as i32)?;
+    write_i32(writer, config.query_detail_metrics_size)?;
+    write_i32(writer, config.query_parallelism)?;
+    write_bool(writer, config.read_from_backup)?;
+    write_i32(writer, config.rebalance_batch_size)?;
+    write_i64(writer, config.rebalance_batches_prefetch_count)?;
+    write_i64(writer, config.rebalance_delay_ms)?;
+    write_i32(writer, config.rebalance_mode.clone() as i32)?;
+    write_i32(writer, config.rebalance_order)?;
+    write_i64(writer, config.rebalance_throttle_ms)?;
+    write_i64(writer, config.rebalance_timeout_ms)?;
+    write_bool(writer, config.sql_escape_all)?;
+    write_i32(writer, config.sql_index_max_size)?;
+    write_opt_string(writer, &config.sql_schema)?;
+    write_i32(writer, config.write_synchronization_mode.clone() as i32)?;
+    write_cache_key_configs(
+        writer,
+        config.cache_key_configurations.as_deref().unwrap_or(&[]),
+    )?;
+    write_query_entities(writer, config.query_entities.as_deref().unwrap_or(&[]))
+}
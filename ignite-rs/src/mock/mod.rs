@@ -0,0 +1,522 @@
+//! In-process fake of the node for the tests, enabled by the `mock` feature.
+//!
+//! The server speaks the binary client protocol over the in-memory [`duplex`] streams.
+//! It handles the handshake, the cache configuration operations and all key-value ones,
+//! so the client is tested without Java:
+//! ```ignore
+//! let server = MockServer::new();
+//! let mut client = ignite_rs::new_client(server.config())?;
+//! let cache = client.get_or_create_cache::<i32, String>("cache")?;
+//!
+//! // the next get fails
+//! server.inject(OpCode::CacheGet, Fault::Error(STATUS_FAILED, "boom".to_owned()));
+//! assert!(cache.get(&1).is_err());
+//! ```
+//! Keys and values are stored in their binary form, so keys are equal if their bytes are.
+//! Scan queries return all the entries in a single page,
+//! binary types are never found and peek modes of the size are ignored
+
+mod cache_config;
+mod op_code;
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::convert::TryFrom;
+use std::io::{Cursor, Write};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::cache::CacheConfiguration;
+use crate::error::{
+    IgniteResult, STATUS_CACHE_DOES_NOT_EXIST, STATUS_CACHE_EXISTS, STATUS_FAILED,
+    STATUS_INVALID_OP_CODE,
+};
+use crate::mock::cache_config::{read_cache_configuration_properties, write_cache_configuration};
+use crate::protocol::complex_obj::IgniteValue;
+use crate::protocol::{
+    read_i16, read_i32, read_i64, read_message, read_string_not_null, read_u8, write_bool,
+    write_i16, write_i32, write_i64, write_null, write_string_type_code, write_u8, DecodeLimits,
+};
+use crate::transport::{duplex, DuplexStream, Transport, TransportStream};
+use crate::utils::string_to_java_hashcode;
+use crate::{ClientConfig, ReadableType};
+
+pub use crate::api::OpCode;
+
+/// Only the protocol version the client speaks is supported
+const VERSION: (i16, i16, i16) = (1, 2, 0);
+
+/// Failure of the next request of an operation
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fault {
+    /// Answers with the error status and message instead of performing the operation
+    Error(i32, String),
+    /// Performs the operation after the delay
+    Delay(Duration),
+    /// Closes the connection without answering
+    Disconnect,
+    /// Performs the operation, but answers with the header of the response only
+    Truncate,
+}
+
+/// Fake node. Clones share the caches, so the client is connected to the same node
+#[derive(Clone, Default)]
+pub struct MockServer {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    caches: BTreeMap<String, MockCache>,
+    credentials: Option<(String, String)>,
+    faults: HashMap<OpCode, VecDeque<Fault>>,
+    latency: Option<Duration>,
+    connections: usize,
+    requests: Vec<OpCode>,
+}
+
+struct MockCache {
+    config: CacheConfiguration,
+    entries: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+/// Status and message of the failed request
+type Failure = (i32, String);
+
+impl MockServer {
+    pub fn new() -> MockServer {
+        MockServer::default()
+    }
+
+    /// Rejects the handshake unless the client authenticates with the credentials
+    pub fn with_credentials(self, username: &str, password: &str) -> MockServer {
+        self.lock().credentials = Some((username.to_owned(), password.to_owned()));
+        self
+    }
+
+    /// Client configuration which connects to this server
+    pub fn config(&self) -> ClientConfig {
+        let mut conf = ClientConfig::new("mock:10800");
+        conf.transport = Some(Arc::new(self.clone()));
+        conf
+    }
+
+    /// Opens the connection to the server. Each one is served by its own thread
+    pub fn connect(&self) -> DuplexStream {
+        let (client, server) = duplex();
+        self.lock().connections += 1;
+        let me = self.clone();
+        thread::spawn(move || me.serve(server));
+        client
+    }
+
+    /// Fails the next request of the operation. Faults of the same operation are applied in order
+    pub fn inject(&self, op: OpCode, fault: Fault) {
+        let mut state = self.lock();
+        state.faults.entry(op).or_default().push_back(fault);
+    }
+
+    /// Delays every response. `None` answers right away
+    pub fn set_latency(&self, latency: Option<Duration>) {
+        self.lock().latency = latency;
+    }
+
+    /// Number of the connections opened so far
+    pub fn connections(&self) -> usize {
+        self.lock().connections
+    }
+
+    /// Operations requested so far, in order. The handshakes are not included
+    pub fn requests(&self) -> Vec<OpCode> {
+        self.lock().requests.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Serves the connection until the client closes it
+    fn serve(&self, mut stream: DuplexStream) {
+        if !matches!(self.handshake(&mut stream), Ok(true)) {
+            return;
+        }
+        let limits = DecodeLimits::default();
+        while let Ok(request) = read_message(&mut stream, &limits) {
+            let mut reader = Cursor::new(request.as_slice());
+            let (code, req_id) = match (read_i16(&mut reader), read_i64(&mut reader)) {
+                (Ok(code), Ok(req_id)) => (code, req_id),
+                _ => return,
+            };
+
+            let result = match OpCode::try_from(code) {
+                Ok(op) => {
+                    let (fault, latency) = {
+                        let mut state = self.lock();
+                        state.requests.push(op);
+                        let fault = state.faults.get_mut(&op).and_then(VecDeque::pop_front);
+                        (fault, state.latency)
+                    };
+                    if let Some(latency) = latency {
+                        thread::sleep(latency);
+                    }
+                    match fault {
+                        Some(Fault::Error(status, message)) => Err((status, message)),
+                        Some(Fault::Delay(delay)) => {
+                            thread::sleep(delay);
+                            self.handle(op, &mut reader)
+                        }
+                        Some(Fault::Disconnect) => return,
+                        Some(Fault::Truncate) => self.handle(op, &mut reader).map(|_| Vec::new()),
+                        None => self.handle(op, &mut reader),
+                    }
+                }
+                Err(_) => Err((
+                    STATUS_INVALID_OP_CODE,
+                    format!("Unsupported op code {}", code),
+                )),
+            };
+
+            if write_response(&mut stream, req_id, result).is_err() {
+                return;
+            }
+        }
+    }
+
+    /// Returns whether the client is accepted
+    fn handshake(&self, stream: &mut DuplexStream) -> IgniteResult<bool> {
+        let request = read_message(stream, &DecodeLimits::default())?;
+        let mut reader = request.as_slice();
+        let _op = read_u8(&mut reader)?;
+        let version = (
+            read_i16(&mut reader)?,
+            read_i16(&mut reader)?,
+            read_i16(&mut reader)?,
+        );
+        let _client_code = read_u8(&mut reader)?;
+        // the credentials are sent only if they are configured
+        let read_credential = |reader: &mut &[u8]| {
+            if reader.is_empty() {
+                Ok(None)
+            } else {
+                String::read(reader)
+            }
+        };
+        let username = read_credential(&mut reader)?;
+        let password = read_credential(&mut reader)?;
+
+        let error = if version != VERSION {
+            Some("Unsupported version")
+        } else {
+            match &self.lock().credentials {
+                Some((user, pass))
+                    if username.as_ref() != Some(user) || password.as_ref() != Some(pass) =>
+                {
+                    Some("The user name or password is incorrect")
+                }
+                _ => None,
+            }
+        };
+
+        let mut response = Vec::new();
+        match error {
+            None => write_u8(&mut response, 1)?,
+            Some(message) => {
+                write_u8(&mut response, 0)?;
+                write_i16(&mut response, VERSION.0)?;
+                write_i16(&mut response, VERSION.1)?;
+                write_i16(&mut response, VERSION.2)?;
+                write_string_type_code(&mut response, message)?;
+            }
+        }
+        write_i32(stream, response.len() as i32)?;
+        stream.write_all(&response)?;
+        Ok(error.is_none())
+    }
+
+    /// Performs the operation and returns the response body
+    fn handle(&self, op: OpCode, req: &mut Cursor<&[u8]>) -> Result<Vec<u8>, Failure> {
+        let mut state = self.lock();
+        let mut body = Vec::new();
+        match op {
+            OpCode::CacheGetNames => {
+                write_i32(&mut body, state.caches.len() as i32).map_err(failed)?;
+                for name in state.caches.keys() {
+                    write_string_type_code(&mut body, name).map_err(failed)?;
+                }
+            }
+            OpCode::CacheCreateWithName | OpCode::CacheGetOrCreateWithName => {
+                let name = read_string_not_null(req).map_err(failed)?;
+                let config = CacheConfiguration::new(&name);
+                state.create_cache(config, op == OpCode::CacheCreateWithName)?;
+            }
+            OpCode::CacheCreateWithConfiguration | OpCode::CacheGetOrCreateWithConfiguration => {
+                let config = read_cache_configuration_properties(req).map_err(failed)?;
+                state.create_cache(config, op == OpCode::CacheCreateWithConfiguration)?;
+            }
+            OpCode::CacheGetConfiguration => {
+                let id = read_i32(req).map_err(failed)?;
+                let mut config = Vec::new();
+                write_cache_configuration(&mut config, &state.cache(id)?.config).map_err(failed)?;
+                write_i32(&mut body, config.len() as i32).map_err(failed)?;
+                body.extend(config);
+            }
+            OpCode::CacheDestroy => {
+                let id = read_i32(req).map_err(failed)?;
+                let name = state.cache(id)?.config.name.clone();
+                state.caches.remove(&name);
+            }
+            OpCode::BinaryTypeGet => write_bool(&mut body, false).map_err(failed)?,
+            OpCode::Handshake => {
+                return Err((STATUS_FAILED, "Handshake is already performed".to_owned()))
+            }
+            _ => {
+                let id = read_i32(req).map_err(failed)?;
+                let _flags = read_u8(req).map_err(failed)?;
+                let entries = &mut state.cache_mut(id)?.entries;
+                handle_key_value(op, entries, req, &mut body).map_err(failed)?;
+            }
+        }
+        Ok(body)
+    }
+}
+
+impl State {
+    fn create_cache(
+        &mut self,
+        config: CacheConfiguration,
+        fail_if_exists: bool,
+    ) -> Result<(), Failure> {
+        if self.caches.contains_key(&config.name) {
+            if fail_if_exists {
+                return Err((
+                    STATUS_CACHE_EXISTS,
+                    format!("Cache already exists: {}", config.name),
+                ));
+            }
+            return Ok(());
+        }
+        let cache = MockCache {
+            config,
+            entries: BTreeMap::new(),
+        };
+        self.caches.insert(cache.config.name.clone(), cache);
+        Ok(())
+    }
+
+    fn cache(&self, id: i32) -> Result<&MockCache, Failure> {
+        self.caches
+            .values()
+            .find(|cache| string_to_java_hashcode(&cache.config.name) == id)
+            .ok_or_else(|| no_cache(id))
+    }
+
+    fn cache_mut(&mut self, id: i32) -> Result<&mut MockCache, Failure> {
+        self.caches
+            .values_mut()
+            .find(|cache| string_to_java_hashcode(&cache.config.name) == id)
+            .ok_or_else(|| no_cache(id))
+    }
+}
+
+impl Transport for MockServer {
+    fn connect(&self, _: &str, _: Option<Duration>) -> IgniteResult<Box<dyn TransportStream>> {
+        Ok(Box::new(MockServer::connect(self)))
+    }
+}
+
+/// Performs the key-value operation. The cache id and flags are already read
+fn handle_key_value(
+    op: OpCode,
+    entries: &mut BTreeMap<Vec<u8>, Vec<u8>>,
+    req: &mut Cursor<&[u8]>,
+    body: &mut Vec<u8>,
+) -> IgniteResult<()> {
+    match op {
+        OpCode::CacheGet => {
+            let key = read_raw(req)?;
+            write_opt_raw(body, entries.get(&key))?;
+        }
+        OpCode::CacheGetAll => {
+            let found: Vec<_> = read_raws(req)?
+                .into_iter()
+                .filter_map(|key| entries.get(&key).map(|value| (key, value)))
+                .collect();
+            write_i32(body, found.len() as i32)?;
+            for (key, value) in found {
+                body.extend(key);
+                body.extend(value);
+            }
+        }
+        OpCode::CachePut => {
+            let (key, value) = (read_raw(req)?, read_raw(req)?);
+            entries.insert(key, value);
+        }
+        OpCode::CachePutAll => {
+            let count = read_i32(req)?;
+            for _ in 0..count {
+                let (key, value) = (read_raw(req)?, read_raw(req)?);
+                entries.insert(key, value);
+            }
+        }
+        OpCode::CachePutIfAbsent => {
+            let (key, value) = (read_raw(req)?, read_raw(req)?);
+            let absent = !entries.contains_key(&key);
+            if absent {
+                entries.insert(key, value);
+            }
+            write_bool(body, absent)?;
+        }
+        OpCode::CacheGetAndPut => {
+            let (key, value) = (read_raw(req)?, read_raw(req)?);
+            write_opt_raw(body, entries.insert(key, value).as_ref())?;
+        }
+        OpCode::CacheGetAndReplace => {
+            let (key, value) = (read_raw(req)?, read_raw(req)?);
+            let old = match entries.get_mut(&key) {
+                Some(old) => Some(std::mem::replace(old, value)),
+                None => None,
+            };
+            write_opt_raw(body, old.as_ref())?;
+        }
+        OpCode::CacheGetAndRemove => {
+            let key = read_raw(req)?;
+            write_opt_raw(body, entries.remove(&key).as_ref())?;
+        }
+        OpCode::CacheGetAndPutIfAbsent => {
+            let (key, value) = (read_raw(req)?, read_raw(req)?);
+            let existing = entries.get(&key).cloned();
+            if existing.is_none() {
+                entries.insert(key, value);
+            }
+            write_opt_raw(body, existing.as_ref())?;
+        }
+        OpCode::CacheReplace => {
+            let (key, value) = (read_raw(req)?, read_raw(req)?);
+            let replaced = match entries.get_mut(&key) {
+                Some(old) => {
+                    *old = value;
+                    true
+                }
+                None => false,
+            };
+            write_bool(body, replaced)?;
+        }
+        OpCode::CacheReplaceIfEquals => {
+            let (key, old, new) = (read_raw(req)?, read_raw(req)?, read_raw(req)?);
+            let replaced = match entries.get_mut(&key) {
+                Some(value) if *value == old => {
+                    *value = new;
+                    true
+                }
+                _ => false,
+            };
+            write_bool(body, replaced)?;
+        }
+        OpCode::CacheContainsKey => {
+            let key = read_raw(req)?;
+            write_bool(body, entries.contains_key(&key))?;
+        }
+        OpCode::CacheContainsKeys => {
+            let keys = read_raws(req)?;
+            write_bool(body, keys.iter().all(|key| entries.contains_key(key)))?;
+        }
+        OpCode::CacheClear | OpCode::CacheRemoveAll => entries.clear(),
+        OpCode::CacheClearKey => {
+            entries.remove(&read_raw(req)?);
+        }
+        OpCode::CacheClearKeys | OpCode::CacheRemoveKeys => {
+            for key in read_raws(req)? {
+                entries.remove(&key);
+            }
+        }
+        OpCode::CacheRemoveKey => {
+            let key = read_raw(req)?;
+            write_bool(body, entries.remove(&key).is_some())?;
+        }
+        OpCode::CacheRemoveIfEquals => {
+            let (key, value) = (read_raw(req)?, read_raw(req)?);
+            let removed = entries.get(&key) == Some(&value);
+            if removed {
+                entries.remove(&key);
+            }
+            write_bool(body, removed)?;
+        }
+        OpCode::CacheGetSize => write_i64(body, entries.len() as i64)?,
+        OpCode::QueryScan => {
+            write_i64(body, 0)?; // cursor id
+            write_i32(body, entries.len() as i32)?;
+            for (key, value) in entries.iter() {
+                body.extend(key);
+                body.extend(value);
+            }
+            write_bool(body, false)?; // no more pages
+        }
+        // handled by the server itself
+        OpCode::Handshake
+        | OpCode::CacheGetNames
+        | OpCode::CacheCreateWithName
+        | OpCode::CacheGetOrCreateWithName
+        | OpCode::CacheCreateWithConfiguration
+        | OpCode::CacheGetOrCreateWithConfiguration
+        | OpCode::CacheGetConfiguration
+        | OpCode::CacheDestroy
+        | OpCode::BinaryTypeGet => unreachable!(),
+    }
+    Ok(())
+}
+
+/// Reads the value and returns its bytes, including the type code
+fn read_raw(req: &mut Cursor<&[u8]>) -> IgniteResult<Vec<u8>> {
+    let start = req.position() as usize;
+    IgniteValue::read(req)?;
+    let end = req.position() as usize;
+    Ok(req.get_ref()[start..end].to_vec())
+}
+
+/// Reads the length-prefixed values
+fn read_raws(req: &mut Cursor<&[u8]>) -> IgniteResult<Vec<Vec<u8>>> {
+    let count = read_i32(req)?;
+    (0..count).map(|_| read_raw(req)).collect()
+}
+
+fn write_opt_raw(body: &mut Vec<u8>, value: Option<&Vec<u8>>) -> std::io::Result<()> {
+    match value {
+        Some(value) => body.write_all(value),
+        None => write_null(body),
+    }
+}
+
+fn write_response(
+    stream: &mut impl Write,
+    req_id: i64,
+    result: Result<Vec<u8>, Failure>,
+) -> std::io::Result<()> {
+    let mut response = Vec::new();
+    write_i64(&mut response, req_id)?;
+    match result {
+        Ok(body) => {
+            write_i32(&mut response, 0)?;
+            response.extend(body);
+        }
+        Err((status, message)) => {
+            write_i32(&mut response, status)?;
+            write_string_type_code(&mut response, &message)?;
+        }
+    }
+    write_i32(stream, response.len() as i32)?;
+    stream.write_all(&response)
+}
+
+fn failed(err: impl std::fmt::Display) -> Failure {
+    (STATUS_FAILED, err.to_string())
+}
+
+fn no_cache(id: i32) -> Failure {
+    (
+        STATUS_CACHE_DOES_NOT_EXIST,
+        format!("Cache does not exist [cacheId={}]", id),
+    )
+}
@@ -0,0 +1,48 @@
+//! Op codes of the requests the server receives
+
+use std::convert::TryFrom;
+
+use crate::api::OpCode;
+use crate::error::IgniteError;
+
+impl TryFrom<i16> for OpCode {
+    type Error = IgniteError;
+
+    fn try_from(value: i16) -> Result<Self, Self::Error> {
+        let code = match value {
+            1 => OpCode::Handshake,
+            1050 => OpCode::CacheGetNames,
+            1051 => OpCode::CacheCreateWithName,
+            1052 => OpCode::CacheGetOrCreateWithName,
+            1053 => OpCode::CacheCreateWithConfiguration,
+            1054 => OpCode::CacheGetOrCreateWithConfiguration,
+            1055 => OpCode::CacheGetConfiguration,
+            1056 => OpCode::CacheDestroy,
+            1000 => OpCode::CacheGet,
+            1001 => OpCode::CachePut,
+            1002 => OpCode::CachePutIfAbsent,
+            1003 => OpCode::CacheGetAll,
+            1004 => OpCode::CachePutAll,
+            1005 => OpCode::CacheGetAndPut,
+            1006 => OpCode::CacheGetAndReplace,
+            1007 => OpCode::CacheGetAndRemove,
+            1008 => OpCode::CacheGetAndPutIfAbsent,
+            1009 => OpCode::CacheReplace,
+            1010 => OpCode::CacheReplaceIfEquals,
+            1011 => OpCode::CacheContainsKey,
+            1012 => OpCode::CacheContainsKeys,
+            1013 => OpCode::CacheClear,
+            1014 => OpCode::CacheClearKey,
+            1015 => OpCode::CacheClearKeys,
+            1016 => OpCode::CacheRemoveKey,
+            1017 => OpCode::CacheRemoveIfEquals,
+            1018 => OpCode::CacheRemoveKeys,
+            1019 => OpCode::CacheRemoveAll,
+            1020 => OpCode::CacheGetSize,
+            2000 => OpCode::QueryScan,
+            3002 => OpCode::BinaryTypeGet,
+            _ => return Err(IgniteError::Protocol(format!("Unknown op code {}", value))),
+        };
+        Ok(code)
+    }
+}
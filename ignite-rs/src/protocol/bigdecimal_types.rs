@@ -0,0 +1,104 @@
+use std::convert::TryFrom;
+use std::io;
+use std::io::{ErrorKind, Read, Write};
+
+use bigdecimal::num_bigint::{BigInt, Sign};
+use bigdecimal::BigDecimal;
+
+use crate::error::{IgniteError, IgniteResult};
+use crate::protocol::TypeCode;
+use crate::{ArrayItem, CollectionItem, Decimal, HashCode, JavaType, ReadableType, WritableType};
+
+// bigdecimal::BigDecimal <=> java.math.BigDecimal
+// Java's scale is a 32-bit integer, so writing a value with bigger scale results in error
+
+impl TryFrom<&BigDecimal> for Decimal {
+    type Error = IgniteError;
+
+    fn try_from(value: &BigDecimal) -> Result<Self, Self::Error> {
+        let (unscaled, scale) = value.as_bigint_and_exponent();
+        let scale = i32::try_from(scale).map_err(|_| {
+            IgniteError::InvalidValue("BigDecimal scale is out of range".to_owned())
+        })?;
+        let (sign, magnitude) = unscaled.to_bytes_be();
+        Ok(Decimal::new(sign == Sign::Minus, magnitude, scale))
+    }
+}
+
+impl From<Decimal> for BigDecimal {
+    fn from(value: Decimal) -> Self {
+        let sign = if value.is_negative() {
+            Sign::Minus
+        } else {
+            Sign::Plus
+        };
+        let unscaled = BigInt::from_bytes_be(sign, value.magnitude());
+        BigDecimal::new(unscaled, i64::from(value.scale()))
+    }
+}
+
+fn to_decimal(value: &BigDecimal) -> io::Result<Decimal> {
+    Decimal::try_from(value).map_err(|err| io::Error::new(ErrorKind::InvalidInput, err.to_string()))
+}
+
+impl WritableType for BigDecimal {
+    fn write(&self, writer: &mut dyn Write) -> io::Result<()> {
+        to_decimal(self)?.write(writer)
+    }
+
+    fn size(&self) -> usize {
+        to_decimal(self).map_or(0, |decimal| decimal.size())
+    }
+}
+
+impl ReadableType for BigDecimal {
+    fn read_unwrapped(type_code: TypeCode, reader: &mut impl Read) -> IgniteResult<Option<Self>> {
+        let value = Decimal::read_unwrapped(type_code, reader)?;
+        Ok(value.map(BigDecimal::from))
+    }
+}
+
+impl CollectionItem for BigDecimal {}
+
+impl JavaType for BigDecimal {
+    const JAVA_TYPE_NAME: &'static str = "java.math.BigDecimal";
+}
+
+impl HashCode for BigDecimal {
+    fn hash_code(&self) -> i32 {
+        to_decimal(self).map_or(0, |decimal| decimal.hash_code())
+    }
+}
+
+impl ArrayItem for BigDecimal {
+    const ARRAY_TYPE_CODE: TypeCode = TypeCode::ArrDecimal;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bigdecimal::BigDecimal;
+
+    use crate::{ReadableType, WritableType};
+
+    #[test]
+    fn test_big_decimal() {
+        for str in &[
+            "0",
+            "2.0",
+            "-0.05",
+            "-128",
+            "123456789012345678901234567890.123456789",
+        ] {
+            let value = BigDecimal::from_str(str).unwrap();
+
+            let mut buf: Vec<u8> = Vec::new();
+            value.write(&mut buf).unwrap();
+            assert_eq!(buf.len(), value.size());
+            let actual = BigDecimal::read(&mut buf.as_slice()).unwrap().unwrap();
+            assert_eq!(actual, value);
+            assert_eq!(actual.to_plain_string(), *str);
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io;
+use std::io::Read;
+use std::sync::{Arc, RwLock};
+
+use crate::error::{IgniteError, IgniteResult};
+use crate::protocol::{
+    check_object_size, read_i32, read_u16, read_u8, TypeCode, COMPLEX_OBJ_HEADER_LEN,
+    FLAG_COMPACT_FOOTER, FLAG_HAS_SCHEMA, FLAG_OFFSET_ONE_BYTE, FLAG_OFFSET_TWO_BYTES,
+    HAS_RAW_DATA,
+};
+use crate::ReadableType;
+
+/// Binary type metadata, registered in the cluster
+/// https://ignite.apache.org/docs/latest/binary-client-protocol/binary-type-metadata
+#[derive(Debug, Clone, PartialEq)]
+pub struct BinaryType {
+    pub type_id: i32,
+    pub type_name: String,
+    pub affinity_key_field_name: Option<String>,
+    pub fields: Vec<BinaryField>,
+    /// Names and ordinals of the enum values. None if the type is not an enum
+    pub enum_values: Option<Vec<(String, i32)>>,
+    pub schemas: Vec<BinarySchema>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BinaryField {
+    pub name: String,
+    pub type_id: i32,
+    pub field_id: i32,
+}
+
+/// Set of fields an object of the binary type could be written with
+#[derive(Debug, Clone, PartialEq)]
+pub struct BinarySchema {
+    pub schema_id: i32,
+    /// Ids of the fields in the order they are written
+    pub field_ids: Vec<i32>,
+}
+
+/// Field ids of the schemas by type id and schema id
+type Schemas = HashMap<(i32, i32), Arc<[i32]>>;
+
+/// Known schemas by type id and schema id. Used to resolve objects with compact footers.
+/// Each connection has its own registry, since ids are only unique within a cluster
+#[derive(Debug, Default)]
+pub struct SchemaRegistry {
+    schemas: RwLock<Schemas>,
+}
+
+impl SchemaRegistry {
+    /// Registers all schemas of the binary type, so objects written with compact footers are readable.
+    /// Done automatically by Ignite::get_binary_type
+    pub fn register(&self, binary_type: &BinaryType) {
+        let mut schemas = self
+            .schemas
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        for schema in &binary_type.schemas {
+            schemas.insert(
+                (binary_type.type_id, schema.schema_id),
+                schema.field_ids.as_slice().into(),
+            );
+        }
+    }
+
+    /// Returns field ids of the registered schema
+    pub fn get(&self, type_id: i32, schema_id: i32) -> Option<Arc<[i32]>> {
+        self.schemas
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(&(type_id, schema_id))
+            .cloned()
+    }
+}
+
+thread_local! {
+    // registry of the connection which response is being read
+    static SCHEMAS: RefCell<Option<Arc<SchemaRegistry>>> = const { RefCell::new(None) };
+}
+
+/// Runs the function with the registry used to resolve compact footers of the objects it reads
+pub fn with_schemas<T>(registry: &Arc<SchemaRegistry>, f: impl FnOnce() -> T) -> T {
+    // restores the previous registry even if the function panics
+    struct Restore(Option<Arc<SchemaRegistry>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SCHEMAS.with(|schemas| *schemas.borrow_mut() = self.0.take());
+        }
+    }
+
+    let _restore = Restore(SCHEMAS.with(|current| current.replace(Some(registry.clone()))));
+    f()
+}
+
+fn get_schema_field_ids(type_id: i32, schema_id: i32) -> Option<Arc<[i32]>> {
+    SCHEMAS.with(|schemas| {
+        schemas
+            .borrow()
+            .as_ref()
+            .and_then(|registry| registry.get(type_id, schema_id))
+    })
+}
+
+/// Fields of a complex object located by the object's footer.
+/// Allows reading fields in any order, no matter how the object was written
+pub struct BinaryObjectFields {
+    type_id: i32,
+    schema_id: i32,
+    data: Vec<u8>,                // object's bytes after header
+    offsets: HashMap<i32, usize>, // field id -> offset in data
+}
+
+impl BinaryObjectFields {
+    /// Reads the complex object which type code is already read.
+    /// `own_schema_id` and `own_field_ids` describe the schema of the reading type.
+    /// They are used for compact footers if schema ids are equal,
+    /// otherwise the schema must be registered in the registry set by with_schemas
+    pub fn read(
+        reader: &mut impl Read,
+        own_schema_id: i32,
+        own_field_ids: &[i32],
+    ) -> IgniteResult<BinaryObjectFields> {
+        read_u8(reader)?; // version
+        let flags = read_u16(reader)?;
+        let type_id = read_i32(reader)?;
+        read_i32(reader)?; // hash code
+        let len = read_i32(reader)?; // length including header
+        let schema_id = read_i32(reader)?;
+        let schema_offset = read_i32(reader)?;
+
+        if len < COMPLEX_OBJ_HEADER_LEN {
+            return Err(IgniteError::Protocol(
+                "Invalid complex object length!".to_owned(),
+            ));
+        }
+        let len = check_object_size(len as usize)?;
+        let data_len = len - COMPLEX_OBJ_HEADER_LEN as usize;
+        let mut data: Vec<u8> = Vec::new();
+        reader.take(data_len as u64).read_to_end(&mut data)?;
+        if data.len() != data_len {
+            return Err(IgniteError::Protocol(
+                "Complex object is truncated!".to_owned(),
+            ));
+        }
+
+        let mut offsets = HashMap::new();
+        if flags & FLAG_HAS_SCHEMA != 0 {
+            let footer_start = schema_offset.saturating_sub(COMPLEX_OBJ_HEADER_LEN);
+            let mut footer_end = data_len;
+            if flags & HAS_RAW_DATA != 0 {
+                footer_end = footer_end.saturating_sub(4); // raw data offset
+            }
+            if footer_start < 0 || footer_start as usize > footer_end {
+                return Err(IgniteError::Protocol(
+                    "Invalid complex object schema offset!".to_owned(),
+                ));
+            }
+            let footer = &data[footer_start as usize..footer_end];
+
+            let offset_size = if flags & FLAG_OFFSET_ONE_BYTE != 0 {
+                1
+            } else if flags & FLAG_OFFSET_TWO_BYTES != 0 {
+                2
+            } else {
+                4
+            };
+
+            if flags & FLAG_COMPACT_FOOTER != 0 {
+                // footer contains only offsets, field ids are taken from the schema
+                let field_ids: Arc<[i32]> = if schema_id == own_schema_id {
+                    own_field_ids.into()
+                } else {
+                    get_schema_field_ids(type_id, schema_id).ok_or_else(|| {
+                        let msg = format!("Unknown schema {} of type {}!", schema_id, type_id);
+                        IgniteError::Protocol(msg)
+                    })?
+                };
+                if footer.len() != field_ids.len() * offset_size {
+                    return Err(IgniteError::Protocol(
+                        "Complex object footer mismatches schema!".to_owned(),
+                    ));
+                }
+                for (field_id, offset) in field_ids.iter().zip(footer.chunks(offset_size)) {
+                    offsets.insert(*field_id, read_offset(offset));
+                }
+            } else {
+                let entry_size = 4 + offset_size;
+                let entries = footer.chunks_exact(entry_size);
+                if !entries.remainder().is_empty() {
+                    return Err(IgniteError::Protocol(
+                        "Invalid complex object footer!".to_owned(),
+                    ));
+                }
+                for entry in entries {
+                    let field_id = i32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]);
+                    offsets.insert(field_id, read_offset(&entry[4..]));
+                }
+            }
+        }
+
+        for offset in offsets.values_mut() {
+            // offsets are relative to the start of the object
+            if *offset < COMPLEX_OBJ_HEADER_LEN as usize || *offset >= len {
+                return Err(IgniteError::Protocol(
+                    "Invalid complex object field offset!".to_owned(),
+                ));
+            }
+            *offset -= COMPLEX_OBJ_HEADER_LEN as usize;
+        }
+
+        Ok(BinaryObjectFields {
+            type_id,
+            schema_id,
+            data,
+            offsets,
+        })
+    }
+
+    pub fn type_id(&self) -> i32 {
+        self.type_id
+    }
+
+    pub fn schema_id(&self) -> i32 {
+        self.schema_id
+    }
+
+    pub fn has_field(&self, field_id: i32) -> bool {
+        self.offsets.contains_key(&field_id)
+    }
+
+    /// Returns bytes of the object starting at the field, if the field is present
+    #[cfg(feature = "serde")]
+    pub(crate) fn field_bytes(&self, field_id: i32) -> Option<&[u8]> {
+        self.offsets
+            .get(&field_id)
+            .map(|offset| &self.data[*offset..])
+    }
+
+    /// Returns ids of the present fields in the order of their offsets
+    pub fn field_ids(&self) -> Vec<i32> {
+        let mut fields: Vec<(&i32, &usize)> = self.offsets.iter().collect();
+        fields.sort_by_key(|(_, offset)| **offset);
+        fields.into_iter().map(|(field_id, _)| *field_id).collect()
+    }
+
+    /// Reads the field by its id. A missing field is read as NULL
+    pub fn read_field<T: ReadableType>(&self, field_id: i32) -> IgniteResult<Option<T>> {
+        match self.offsets.get(&field_id) {
+            Some(offset) => T::read(&mut &self.data[*offset..]),
+            None => T::read_unwrapped(TypeCode::Null, &mut io::empty()),
+        }
+    }
+}
+
+/// Reads little-endian unsigned offset of 1, 2 or 4 bytes
+fn read_offset(bytes: &[u8]) -> usize {
+    bytes
+        .iter()
+        .rev()
+        .fold(0usize, |acc, byte| (acc << 8) | usize::from(*byte))
+}
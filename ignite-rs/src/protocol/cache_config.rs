@@ -11,11 +11,12 @@ use crate::cache::{
 use crate::error::IgniteError;
 use crate::error::IgniteResult;
 use crate::protocol::cache_config::ConfigPropertyCode::*;
+use crate::protocol::TypeCode;
 use crate::protocol::{
-    read_bool, read_i32, read_i64, read_object, read_u8, write_bool, write_i16, write_i32,
-    write_i64, write_string_type_code, write_u8,
+    read_bool, read_i32, read_i64, read_object, read_string_not_null, read_u8, write_bool,
+    write_i16, write_i32, write_i64, write_string_type_code, write_u8,
 };
-use crate::ReadableType;
+use crate::{ReadableType, WritableType};
 use std::io;
 
 const MIN_CONFIG_PARAMS: i16 = 26;
@@ -55,9 +56,9 @@ pub(crate) enum ConfigPropertyCode {
     QueryEntities = 200,
 }
 
-impl Into<i16> for ConfigPropertyCode {
-    fn into(self) -> i16 {
-        self as i16
+impl From<ConfigPropertyCode> for i16 {
+    fn from(code: ConfigPropertyCode) -> i16 {
+        code as i16
     }
 }
 
@@ -199,7 +200,8 @@ pub(crate) fn read_cache_configuration(reader: &mut impl Read) -> IgniteResult<C
         default_lock_timeout_ms: read_i64(reader)?,
         max_concurrent_async_operations: read_i32(reader)?,
         max_query_iterators: read_i32(reader)?,
-        name: String::read(reader)?.ok_or_else(|| IgniteError::from("name is required"))?,
+        name: String::read(reader)?
+            .ok_or_else(|| IgniteError::Protocol("name is required".to_owned()))?,
         onheap_cache_enabled: read_bool(reader)?,
         partition_loss_policy: PartitionLossPolicy::try_from(read_i32(reader)?)?,
         query_detail_metrics_size: read_i32(reader)?,
@@ -222,12 +224,14 @@ pub(crate) fn read_cache_configuration(reader: &mut impl Read) -> IgniteResult<C
     Ok(config)
 }
 
-fn read_cache_key_configs(reader: &mut impl Read) -> IgniteResult<Vec<CacheKeyConfiguration>> {
+pub(crate) fn read_cache_key_configs(
+    reader: &mut impl Read,
+) -> IgniteResult<Vec<CacheKeyConfiguration>> {
     let count = read_i32(reader)?;
     let mut result = Vec::<CacheKeyConfiguration>::new();
     for _ in 0..count {
-        let type_name = String::read(reader)?.unwrap();
-        let affinity_key_field_name = String::read(reader)?.unwrap();
+        let type_name = read_string_not_null(reader)?;
+        let affinity_key_field_name = read_string_not_null(reader)?;
         result.push(CacheKeyConfiguration {
             type_name,
             affinity_key_field_name,
@@ -236,7 +240,7 @@ fn read_cache_key_configs(reader: &mut impl Read) -> IgniteResult<Vec<CacheKeyCo
     Ok(result)
 }
 
-fn write_cache_key_configs(
+pub(crate) fn write_cache_key_configs(
     writer: &mut dyn Write,
     configs: &[CacheKeyConfiguration],
 ) -> io::Result<()> {
@@ -249,15 +253,15 @@ fn write_cache_key_configs(
     Ok(())
 }
 
-fn read_query_entities(reader: &mut impl Read) -> IgniteResult<Vec<QueryEntity>> {
+pub(crate) fn read_query_entities(reader: &mut impl Read) -> IgniteResult<Vec<QueryEntity>> {
     let count = read_i32(reader)?;
     let mut result = Vec::<QueryEntity>::new();
     for _ in 0..count {
-        let key_type = String::read(reader)?.unwrap();
-        let value_type = String::read(reader)?.unwrap();
-        let table = String::read(reader)?.unwrap();
-        let key_field = String::read(reader)?.unwrap_or("".to_string());
-        let value_field = String::read(reader)?.unwrap_or("".to_string());
+        let key_type = read_string_not_null(reader)?;
+        let value_type = read_string_not_null(reader)?;
+        let table = read_string_not_null(reader)?;
+        let key_field = String::read(reader)?;
+        let value_field = String::read(reader)?;
         let query_fields = read_query_fields(reader)?;
         let field_aliases = read_query_field_aliases(reader)?;
         let query_indexes = read_query_indexes(reader)?;
@@ -270,20 +274,22 @@ fn read_query_entities(reader: &mut impl Read) -> IgniteResult<Vec<QueryEntity>>
             query_fields,
             field_aliases,
             query_indexes,
-            default_value: None, //TODO
         })
     }
     Ok(result)
 }
 
-fn write_query_entities(writer: &mut dyn Write, entities: &[QueryEntity]) -> io::Result<()> {
+pub(crate) fn write_query_entities(
+    writer: &mut dyn Write,
+    entities: &[QueryEntity],
+) -> io::Result<()> {
     write_i32(writer, entities.len() as i32)?;
     for entity in entities.iter() {
         write_string_type_code(writer, entity.key_type.as_str())?;
         write_string_type_code(writer, entity.value_type.as_str())?;
         write_string_type_code(writer, entity.table.as_str())?;
-        write_string_type_code(writer, entity.key_field.as_str())?;
-        write_string_type_code(writer, entity.value_field.as_str())?;
+        entity.key_field.write(writer)?;
+        entity.value_field.write(writer)?;
         write_query_fields(writer, &entity.query_fields)?;
         write_field_aliases(writer, &entity.field_aliases)?;
         write_query_indexes(writer, &entity.query_indexes)?;
@@ -296,8 +302,8 @@ fn read_query_fields(reader: &mut impl Read) -> IgniteResult<Vec<QueryField>> {
     let count = read_i32(reader)?;
     let mut result = Vec::<QueryField>::new();
     for _ in 0..count {
-        let name = String::read(reader)?.unwrap();
-        let type_name = String::read(reader)?.unwrap();
+        let name = read_string_not_null(reader)?;
+        let type_name = read_string_not_null(reader)?;
         let key_field = read_bool(reader)?;
         let not_null_constraint = read_bool(reader)?;
         let _default_val = read_object(reader)?;
@@ -322,6 +328,9 @@ fn write_query_fields(writer: &mut dyn Write, fields: &[QueryField]) -> io::Resu
         write_string_type_code(writer, field.type_name.as_str())?;
         write_bool(writer, field.key_field)?;
         write_bool(writer, field.not_null_constraint)?;
+        write_u8(writer, TypeCode::Null as u8)?; // default value
+        write_i32(writer, field.precision)?;
+        write_i32(writer, field.scale)?;
     }
     Ok(())
 }
@@ -330,8 +339,8 @@ fn read_query_field_aliases(reader: &mut impl Read) -> IgniteResult<Vec<(String,
     let count = read_i32(reader)?;
     let mut result = Vec::<(String, String)>::new();
     for _ in 0..count {
-        let name = String::read(reader)?.unwrap();
-        let alias = String::read(reader)?.unwrap();
+        let name = read_string_not_null(reader)?;
+        let alias = read_string_not_null(reader)?;
         result.push((name, alias))
     }
     Ok(result)
@@ -350,7 +359,7 @@ fn read_query_indexes(reader: &mut impl Read) -> IgniteResult<Vec<QueryIndex>> {
     let count = read_i32(reader)?;
     let mut result = Vec::<QueryIndex>::new();
     for _ in 0..count {
-        let index_name = String::read(reader)?.unwrap();
+        let index_name = read_string_not_null(reader)?;
         let index_type = IndexType::try_from(read_u8(reader)?)?;
         let inline_size = read_i32(reader)?;
         let fields = read_query_index_fields(reader)?;
@@ -379,7 +388,7 @@ fn read_query_index_fields(reader: &mut impl Read) -> IgniteResult<Vec<(String,
     let count = read_i32(reader)?;
     let mut result = Vec::<(String, bool)>::new();
     for _ in 0..count {
-        let name = String::read(reader)?.unwrap();
+        let name = read_string_not_null(reader)?;
         let is_descending = read_bool(reader)?;
         result.push((name, is_descending))
     }
@@ -394,3 +403,61 @@ fn write_query_index_fields(writer: &mut dyn Write, fields: &[(String, bool)]) -
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_entity_round_trip() {
+        let entity = QueryEntity::builder("java.lang.Long", "com.acme.Person")
+            .key_field("id")
+            .field(
+                QueryField::builder("id", "java.lang.Long")
+                    .key_field()
+                    .build(),
+            )
+            .field(
+                QueryField::builder("salary", "java.math.BigDecimal")
+                    .not_null()
+                    .precision(10)
+                    .scale(2)
+                    .build(),
+            )
+            .alias("salary", "PAY")
+            .index(
+                QueryIndex::builder("person_salary_idx", IndexType::Sorted)
+                    .field("salary", true)
+                    .build(),
+            )
+            .build();
+
+        let mut bytes: Vec<u8> = Vec::new();
+        write_query_entities(&mut bytes, &[entity]).unwrap();
+        let entities = read_query_entities(&mut bytes.as_slice()).unwrap();
+        assert_eq!(entities.len(), 1);
+
+        let entity = &entities[0];
+        assert_eq!(entity.key_type(), "java.lang.Long");
+        assert_eq!(entity.value_type(), "com.acme.Person");
+        assert_eq!(entity.table(), "Person");
+        assert_eq!(entity.key_field(), Some("id"));
+        assert_eq!(entity.value_field(), None);
+        assert_eq!(
+            entity.field_aliases(),
+            &[("salary".to_string(), "PAY".to_string())]
+        );
+
+        let salary = &entity.fields()[1];
+        assert_eq!(salary.name(), "salary");
+        assert_eq!(salary.type_name(), "java.math.BigDecimal");
+        assert!(!salary.is_key_field());
+        assert!(salary.is_not_null());
+        assert_eq!((salary.precision(), salary.scale()), (10, 2));
+        assert!(entity.fields()[0].is_key_field());
+
+        let index = &entity.indexes()[0];
+        assert_eq!(index.name(), "person_salary_idx");
+        assert_eq!(index.fields(), &[("salary".to_string(), true)]);
+    }
+}
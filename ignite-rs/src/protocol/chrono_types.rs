@@ -0,0 +1,142 @@
+use std::convert::TryFrom;
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Timelike, Utc};
+
+use crate::error::IgniteError;
+use crate::protocol::MILLIS_PER_DAY;
+use crate::{Date, Time, Timestamp};
+
+// chrono::DateTime<Utc> <=> java.sql.Timestamp
+// chrono::NaiveDate <=> java.sql.Date (midnight UTC)
+// chrono::NaiveTime <=> java.sql.Time (millisecond precision)
+
+impl From<DateTime<Utc>> for Timestamp {
+    fn from(value: DateTime<Utc>) -> Self {
+        Timestamp {
+            millis: value.timestamp_millis(),
+            nanos: (value.timestamp_subsec_nanos() % 1_000_000) as i32,
+        }
+    }
+}
+
+impl TryFrom<Timestamp> for DateTime<Utc> {
+    type Error = IgniteError;
+
+    fn try_from(value: Timestamp) -> Result<Self, Self::Error> {
+        if !(0..1_000_000).contains(&value.nanos) {
+            return Err(IgniteError::InvalidValue(
+                "Timestamp nanos are out of range".to_owned(),
+            ));
+        }
+        let secs = value.millis.div_euclid(1000);
+        let nanos = value.millis.rem_euclid(1000) as u32 * 1_000_000 + value.nanos as u32;
+        DateTime::from_timestamp(secs, nanos)
+            .ok_or_else(|| IgniteError::InvalidValue("Timestamp is out of range".to_owned()))
+    }
+}
+
+impl From<NaiveDate> for Date {
+    fn from(value: NaiveDate) -> Self {
+        Date {
+            millis: value.and_time(NaiveTime::MIN).and_utc().timestamp_millis(),
+        }
+    }
+}
+
+impl TryFrom<Date> for NaiveDate {
+    type Error = IgniteError;
+
+    fn try_from(value: Date) -> Result<Self, Self::Error> {
+        DateTime::from_timestamp_millis(value.millis)
+            .map(|date_time| date_time.date_naive())
+            .ok_or_else(|| IgniteError::InvalidValue("Date is out of range".to_owned()))
+    }
+}
+
+impl From<NaiveTime> for Time {
+    fn from(value: NaiveTime) -> Self {
+        // leap second is represented as nanos over 1_000_000_000
+        let millis = (value.nanosecond() / 1_000_000).min(999);
+        Time {
+            millis: i64::from(value.num_seconds_from_midnight()) * 1000 + i64::from(millis),
+        }
+    }
+}
+
+impl TryFrom<Time> for NaiveTime {
+    type Error = IgniteError;
+
+    fn try_from(value: Time) -> Result<Self, Self::Error> {
+        let millis = value.millis.rem_euclid(MILLIS_PER_DAY);
+        NaiveTime::from_num_seconds_from_midnight_opt(
+            (millis / 1000) as u32,
+            (millis % 1000) as u32 * 1_000_000,
+        )
+        .ok_or_else(|| IgniteError::InvalidValue("Time is out of range".to_owned()))
+    }
+}
+
+converted_type!(DateTime<Utc>, Timestamp);
+converted_type!(NaiveDate, Date);
+converted_type!(NaiveTime, Time);
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone, Utc};
+
+    use crate::{Date, ReadableType, Time, Timestamp, WritableType};
+
+    fn round_trip<T: WritableType + ReadableType>(value: &T) -> T {
+        let mut buf: Vec<u8> = Vec::new();
+        value.write(&mut buf).unwrap();
+        assert_eq!(buf.len(), value.size());
+        T::read(&mut buf.as_slice()).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_date_time() {
+        let value = Utc.with_ymd_and_hms(2023, 6, 21, 12, 34, 56).unwrap()
+            + chrono::Duration::nanoseconds(123_456_789);
+        assert_eq!(
+            Timestamp::from(value),
+            Timestamp {
+                millis: 1_687_350_896_123,
+                nanos: 456_789
+            }
+        );
+        assert_eq!(round_trip(&value), value);
+
+        let before_epoch = Utc.with_ymd_and_hms(1969, 12, 31, 23, 59, 59).unwrap()
+            + chrono::Duration::nanoseconds(999_999_999);
+        assert_eq!(round_trip(&before_epoch), before_epoch);
+        assert!(DateTime::<Utc>::try_from(Timestamp {
+            millis: 0,
+            nanos: 1_000_000
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_naive_date() {
+        let value = NaiveDate::from_ymd_opt(2023, 6, 21).unwrap();
+        assert_eq!(
+            Date::from(value),
+            Date {
+                millis: 1_687_305_600_000
+            }
+        );
+        assert_eq!(round_trip(&value), value);
+
+        let before_epoch = NaiveDate::from_ymd_opt(1900, 1, 1).unwrap();
+        assert_eq!(round_trip(&before_epoch), before_epoch);
+    }
+
+    #[test]
+    fn test_naive_time() {
+        let value = NaiveTime::from_hms_milli_opt(12, 34, 56, 789).unwrap();
+        assert_eq!(Time::from(value), Time { millis: 45_296_789 });
+        assert_eq!(round_trip(&value), value);
+    }
+}
@@ -1,27 +1,59 @@
 use crate::cache::{QueryEntity, QueryField};
 use crate::error::{IgniteError, IgniteResult};
 use crate::protocol::{
-    read_bool, read_i16, read_i32, read_i64, read_string, read_u16, read_u8, write_i16, write_i32,
-    write_i64, write_null, write_string, write_u16, write_u8, TypeCode, COMPLEX_OBJ_HEADER_LEN,
-    FLAG_COMPACT_FOOTER, FLAG_HAS_SCHEMA, FLAG_OFFSET_ONE_BYTE, FLAG_OFFSET_TWO_BYTES,
-    FLAG_USER_TYPE, HAS_RAW_DATA,
+    check_object_size, enter_nested, prealloc_capacity, read_bool, read_decimal, read_enum,
+    read_f32, read_f64, read_i16, read_i32, read_i64, read_i8, read_len, read_primitive_arr,
+    read_string, read_u16, read_u8, read_uuid, write_bool, write_decimal, write_enum, write_f32,
+    write_f64, write_i16, write_i32, write_i64, write_i8, write_null, write_string, write_u16,
+    write_u8, write_uuid, TypeCode, COMPLEX_OBJ_HEADER_LEN, FLAG_HAS_SCHEMA, FLAG_OFFSET_ONE_BYTE,
+    FLAG_OFFSET_TWO_BYTES, FLAG_USER_TYPE, HAS_RAW_DATA,
 };
 use crate::utils::{bytes_to_java_hashcode, get_schema_id, string_to_java_hashcode};
-use crate::{ReadableType, WritableType};
+use crate::{
+    CollectionItem, Date, Decimal, Enum, ReadableType, Time, Timestamp, Uuid, WritableType,
+};
 use std::convert::TryFrom;
 use std::io::{Cursor, ErrorKind, Read, Write};
-use std::mem::size_of;
 use std::sync::Arc;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+/// Dynamically typed value of any type described in Binary Protocol
+/// https://apacheignite.readme.io/docs/binary-client-protocol-data-format
+#[derive(Debug, PartialEq, Clone)]
 pub enum IgniteValue {
     String(String),
     Long(i64),
     Int(i32),
     Short(i16),
+    Byte(u8),
+    Float(f32),
+    Double(f64),
+    Char(u16),
     Bool(bool),
+    Uuid(Uuid),
+    Date(i64),           // milliseconds since 1 Jan 1970 UTC
+    Time(i64),           // milliseconds since midnight
     Timestamp(i64, i32), // milliseconds since 1 Jan 1970 UTC, Nanosecond fraction of a millisecond.
-    Decimal(i32, Vec<u8>), // scale, big int value in bytes
+    Decimal(Decimal),
+    Enum(Enum),
+    ArrByte(Vec<u8>),
+    ArrShort(Vec<i16>),
+    ArrInt(Vec<i32>),
+    ArrLong(Vec<i64>),
+    ArrFloat(Vec<f32>),
+    ArrDouble(Vec<f64>),
+    ArrChar(Vec<u16>),
+    ArrBool(Vec<bool>),
+    ArrString(Vec<IgniteValue>),
+    ArrUuid(Vec<IgniteValue>),
+    ArrDate(Vec<IgniteValue>),
+    ArrTime(Vec<IgniteValue>),
+    ArrTimestamp(Vec<IgniteValue>),
+    ArrDecimal(Vec<IgniteValue>),
+    ArrEnum(i32, Vec<IgniteValue>),           // type id, elements
+    ArrObj(i32, Vec<IgniteValue>),            // type id, elements
+    Collection(i8, Vec<IgniteValue>),         // collection kind, elements
+    Map(i8, Vec<(IgniteValue, IgniteValue)>), // map kind, key-value pairs
+    ComplexObj(ComplexObject),
     Null,
 }
 
@@ -31,12 +63,440 @@ pub enum IgniteType {
     Long,
     Int,
     Short,
+    Byte,
+    Float,
+    Double,
+    Char,
     Bool,
+    Uuid,
+    Date,
+    Time,
     Timestamp,
     Decimal(i32, i32), // precision, scale
+    Enum,
+    ArrByte,
+    ArrShort,
+    ArrInt,
+    ArrLong,
+    ArrFloat,
+    ArrDouble,
+    ArrChar,
+    ArrBool,
+    ArrString,
+    ArrUuid,
+    ArrDate,
+    ArrTime,
+    ArrTimestamp,
+    ArrDecimal,
+    ArrEnum,
+    ArrObj,
+    Collection,
+    Map,
+    ComplexObj,
     Null,
 }
 
+impl IgniteType {
+    /// Maps the Java class name used in query entities onto the binary type.
+    /// Returns None for user-defined classes
+    pub fn from_java_type(type_name: &str, precision: i32, scale: i32) -> Option<IgniteType> {
+        let t = match type_name {
+            "java.lang.Long" | "long" => IgniteType::Long,
+            "java.lang.Integer" | "int" => IgniteType::Int,
+            "java.lang.Short" | "short" => IgniteType::Short,
+            "java.lang.Byte" | "byte" => IgniteType::Byte,
+            "java.lang.Float" | "float" => IgniteType::Float,
+            "java.lang.Double" | "double" => IgniteType::Double,
+            "java.lang.Character" | "char" => IgniteType::Char,
+            "java.lang.Boolean" | "boolean" => IgniteType::Bool,
+            "java.lang.String" => IgniteType::String,
+            "java.util.UUID" => IgniteType::Uuid,
+            "java.util.Date" | "java.sql.Date" => IgniteType::Date,
+            "java.sql.Time" => IgniteType::Time,
+            "java.sql.Timestamp" => IgniteType::Timestamp,
+            "java.math.BigDecimal" => IgniteType::Decimal(precision, scale),
+            "[B" => IgniteType::ArrByte,
+            "[S" => IgniteType::ArrShort,
+            "[I" => IgniteType::ArrInt,
+            "[J" => IgniteType::ArrLong,
+            "[F" => IgniteType::ArrFloat,
+            "[D" => IgniteType::ArrDouble,
+            "[C" => IgniteType::ArrChar,
+            "[Z" => IgniteType::ArrBool,
+            "[Ljava.lang.String;" => IgniteType::ArrString,
+            "[Ljava.util.UUID;" => IgniteType::ArrUuid,
+            "[Ljava.util.Date;" | "[Ljava.sql.Date;" => IgniteType::ArrDate,
+            "[Ljava.sql.Time;" => IgniteType::ArrTime,
+            "[Ljava.sql.Timestamp;" => IgniteType::ArrTimestamp,
+            "[Ljava.math.BigDecimal;" => IgniteType::ArrDecimal,
+            "[Ljava.lang.Object;" => IgniteType::ArrObj,
+            "java.util.Collection"
+            | "java.util.List"
+            | "java.util.Set"
+            | "java.util.ArrayList"
+            | "java.util.LinkedList"
+            | "java.util.HashSet"
+            | "java.util.LinkedHashSet" => IgniteType::Collection,
+            "java.util.Map" | "java.util.HashMap" | "java.util.LinkedHashMap" => IgniteType::Map,
+            _ => return None,
+        };
+        Some(t)
+    }
+
+    /// Type code the values of this type are serialized with
+    pub fn type_code(&self) -> TypeCode {
+        match self {
+            IgniteType::String => TypeCode::String,
+            IgniteType::Long => TypeCode::Long,
+            IgniteType::Int => TypeCode::Int,
+            IgniteType::Short => TypeCode::Short,
+            IgniteType::Byte => TypeCode::Byte,
+            IgniteType::Float => TypeCode::Float,
+            IgniteType::Double => TypeCode::Double,
+            IgniteType::Char => TypeCode::Char,
+            IgniteType::Bool => TypeCode::Bool,
+            IgniteType::Uuid => TypeCode::Uuid,
+            IgniteType::Date => TypeCode::Date,
+            IgniteType::Time => TypeCode::Time,
+            IgniteType::Timestamp => TypeCode::Timestamp,
+            IgniteType::Decimal(_, _) => TypeCode::Decimal,
+            IgniteType::Enum => TypeCode::Enum,
+            IgniteType::ArrByte => TypeCode::ArrByte,
+            IgniteType::ArrShort => TypeCode::ArrShort,
+            IgniteType::ArrInt => TypeCode::ArrInt,
+            IgniteType::ArrLong => TypeCode::ArrLong,
+            IgniteType::ArrFloat => TypeCode::ArrFloat,
+            IgniteType::ArrDouble => TypeCode::ArrDouble,
+            IgniteType::ArrChar => TypeCode::ArrChar,
+            IgniteType::ArrBool => TypeCode::ArrBool,
+            IgniteType::ArrString => TypeCode::ArrString,
+            IgniteType::ArrUuid => TypeCode::ArrUuid,
+            IgniteType::ArrDate => TypeCode::ArrDate,
+            IgniteType::ArrTime => TypeCode::ArrTime,
+            IgniteType::ArrTimestamp => TypeCode::TimestampArray,
+            IgniteType::ArrDecimal => TypeCode::ArrDecimal,
+            IgniteType::ArrEnum => TypeCode::ArrEnum,
+            IgniteType::ArrObj => TypeCode::ArrObj,
+            IgniteType::Collection => TypeCode::Collection,
+            IgniteType::Map => TypeCode::Map,
+            IgniteType::ComplexObj => TypeCode::ComplexObj,
+            IgniteType::Null => TypeCode::Null,
+        }
+    }
+}
+
+impl IgniteValue {
+    /// Reads a value which type code has already been read
+    pub fn read_value(type_code: TypeCode, reader: &mut impl Read) -> IgniteResult<IgniteValue> {
+        let _nested = enter_nested()?;
+        let val = match type_code {
+            TypeCode::String => IgniteValue::String(read_string(reader)?),
+            TypeCode::Long => IgniteValue::Long(read_i64(reader)?),
+            TypeCode::Int => IgniteValue::Int(read_i32(reader)?),
+            TypeCode::Short => IgniteValue::Short(read_i16(reader)?),
+            TypeCode::Byte => IgniteValue::Byte(read_u8(reader)?),
+            TypeCode::Float => IgniteValue::Float(read_f32(reader)?),
+            TypeCode::Double => IgniteValue::Double(read_f64(reader)?),
+            TypeCode::Char => IgniteValue::Char(read_u16(reader)?),
+            TypeCode::Bool => IgniteValue::Bool(read_bool(reader)?),
+            TypeCode::Uuid => IgniteValue::Uuid(read_uuid(reader)?),
+            TypeCode::Date => IgniteValue::Date(read_i64(reader)?),
+            TypeCode::Time => IgniteValue::Time(read_i64(reader)?),
+            TypeCode::Timestamp => {
+                let big = read_i64(reader)?;
+                let little = read_i32(reader)?;
+                IgniteValue::Timestamp(big, little)
+            }
+            TypeCode::Decimal => IgniteValue::Decimal(read_decimal(reader)?),
+            TypeCode::Enum | TypeCode::BinaryEnum => IgniteValue::Enum(read_enum(reader)?),
+            TypeCode::ArrByte => IgniteValue::ArrByte(read_primitive_arr(reader, read_u8)?),
+            TypeCode::ArrShort => IgniteValue::ArrShort(read_primitive_arr(reader, read_i16)?),
+            TypeCode::ArrInt => IgniteValue::ArrInt(read_primitive_arr(reader, read_i32)?),
+            TypeCode::ArrLong => IgniteValue::ArrLong(read_primitive_arr(reader, read_i64)?),
+            TypeCode::ArrFloat => IgniteValue::ArrFloat(read_primitive_arr(reader, read_f32)?),
+            TypeCode::ArrDouble => IgniteValue::ArrDouble(read_primitive_arr(reader, read_f64)?),
+            TypeCode::ArrChar => IgniteValue::ArrChar(read_primitive_arr(reader, read_u16)?),
+            TypeCode::ArrBool => IgniteValue::ArrBool(read_primitive_arr(reader, read_bool)?),
+            TypeCode::ArrString => IgniteValue::ArrString(Self::read_values(reader)?),
+            TypeCode::ArrUuid => IgniteValue::ArrUuid(Self::read_values(reader)?),
+            TypeCode::ArrDate => IgniteValue::ArrDate(Self::read_values(reader)?),
+            TypeCode::ArrTime => IgniteValue::ArrTime(Self::read_values(reader)?),
+            TypeCode::TimestampArray => IgniteValue::ArrTimestamp(Self::read_values(reader)?),
+            TypeCode::ArrDecimal => IgniteValue::ArrDecimal(Self::read_values(reader)?),
+            TypeCode::ArrEnum => {
+                let type_id = read_i32(reader)?;
+                IgniteValue::ArrEnum(type_id, Self::read_values(reader)?)
+            }
+            TypeCode::ArrObj => {
+                let type_id = read_i32(reader)?;
+                IgniteValue::ArrObj(type_id, Self::read_values(reader)?)
+            }
+            TypeCode::Collection => {
+                let len = read_len(reader)?;
+                let kind = read_i8(reader)?;
+                let mut items = Vec::with_capacity(prealloc_capacity(len));
+                for _ in 0..len {
+                    items.push(Self::read_any(reader)?);
+                }
+                IgniteValue::Collection(kind, items)
+            }
+            TypeCode::Map => {
+                let len = read_len(reader)?;
+                let kind = read_i8(reader)?;
+                let mut pairs = Vec::with_capacity(prealloc_capacity(len));
+                for _ in 0..len {
+                    let key = Self::read_any(reader)?;
+                    let value = Self::read_any(reader)?;
+                    pairs.push((key, value));
+                }
+                IgniteValue::Map(kind, pairs)
+            }
+            TypeCode::ComplexObj => match ComplexObject::read_unwrapped(type_code, reader)? {
+                Some(obj) => IgniteValue::ComplexObj(obj),
+                None => IgniteValue::Null,
+            },
+            TypeCode::WrappedData => {
+                read_i32(reader)?; // skip len
+                let value = Self::read_any(reader)?;
+                read_i32(reader)?; // skip offset
+                value
+            }
+            TypeCode::Null => IgniteValue::Null,
+        };
+        Ok(val)
+    }
+
+    /// Reads type code and a value
+    fn read_any(reader: &mut impl Read) -> IgniteResult<IgniteValue> {
+        let type_code = TypeCode::try_from(read_u8(reader)?)?;
+        Self::read_value(type_code, reader)
+    }
+
+    /// Reads length-prefixed sequence of values. Each one has its own type code
+    fn read_values(reader: &mut impl Read) -> IgniteResult<Vec<IgniteValue>> {
+        let len = read_len(reader)?;
+        let mut items = Vec::with_capacity(prealloc_capacity(len));
+        for _ in 0..len {
+            items.push(Self::read_any(reader)?);
+        }
+        Ok(items)
+    }
+
+    /// Type code this value is serialized with
+    pub fn type_code(&self) -> TypeCode {
+        match self {
+            IgniteValue::String(_) => TypeCode::String,
+            IgniteValue::Long(_) => TypeCode::Long,
+            IgniteValue::Int(_) => TypeCode::Int,
+            IgniteValue::Short(_) => TypeCode::Short,
+            IgniteValue::Byte(_) => TypeCode::Byte,
+            IgniteValue::Float(_) => TypeCode::Float,
+            IgniteValue::Double(_) => TypeCode::Double,
+            IgniteValue::Char(_) => TypeCode::Char,
+            IgniteValue::Bool(_) => TypeCode::Bool,
+            IgniteValue::Uuid(_) => TypeCode::Uuid,
+            IgniteValue::Date(_) => TypeCode::Date,
+            IgniteValue::Time(_) => TypeCode::Time,
+            IgniteValue::Timestamp(_, _) => TypeCode::Timestamp,
+            IgniteValue::Decimal(_) => TypeCode::Decimal,
+            IgniteValue::Enum(_) => TypeCode::Enum,
+            IgniteValue::ArrByte(_) => TypeCode::ArrByte,
+            IgniteValue::ArrShort(_) => TypeCode::ArrShort,
+            IgniteValue::ArrInt(_) => TypeCode::ArrInt,
+            IgniteValue::ArrLong(_) => TypeCode::ArrLong,
+            IgniteValue::ArrFloat(_) => TypeCode::ArrFloat,
+            IgniteValue::ArrDouble(_) => TypeCode::ArrDouble,
+            IgniteValue::ArrChar(_) => TypeCode::ArrChar,
+            IgniteValue::ArrBool(_) => TypeCode::ArrBool,
+            IgniteValue::ArrString(_) => TypeCode::ArrString,
+            IgniteValue::ArrUuid(_) => TypeCode::ArrUuid,
+            IgniteValue::ArrDate(_) => TypeCode::ArrDate,
+            IgniteValue::ArrTime(_) => TypeCode::ArrTime,
+            IgniteValue::ArrTimestamp(_) => TypeCode::TimestampArray,
+            IgniteValue::ArrDecimal(_) => TypeCode::ArrDecimal,
+            IgniteValue::ArrEnum(_, _) => TypeCode::ArrEnum,
+            IgniteValue::ArrObj(_, _) => TypeCode::ArrObj,
+            IgniteValue::Collection(_, _) => TypeCode::Collection,
+            IgniteValue::Map(_, _) => TypeCode::Map,
+            IgniteValue::ComplexObj(_) => TypeCode::ComplexObj,
+            IgniteValue::Null => TypeCode::Null,
+        }
+    }
+
+    fn write_values(writer: &mut dyn Write, items: &[IgniteValue]) -> std::io::Result<()> {
+        write_i32(writer, items.len() as i32)?;
+        for item in items {
+            item.write(writer)?;
+        }
+        Ok(())
+    }
+
+    fn values_size(items: &[IgniteValue]) -> usize {
+        items.iter().map(|item| item.size()).sum::<usize>() + 4 // items, len
+    }
+}
+
+impl WritableType for IgniteValue {
+    fn write(&self, writer: &mut dyn Write) -> std::io::Result<()> {
+        if let IgniteValue::ComplexObj(obj) = self {
+            return obj.write(writer);
+        }
+        write_u8(writer, self.type_code() as u8)?;
+        match self {
+            IgniteValue::String(val) => write_string(writer, val),
+            IgniteValue::Long(val) => write_i64(writer, *val),
+            IgniteValue::Int(val) => write_i32(writer, *val),
+            IgniteValue::Short(val) => write_i16(writer, *val),
+            IgniteValue::Byte(val) => write_u8(writer, *val),
+            IgniteValue::Float(val) => write_f32(writer, *val),
+            IgniteValue::Double(val) => write_f64(writer, *val),
+            IgniteValue::Char(val) => write_u16(writer, *val),
+            IgniteValue::Bool(val) => write_bool(writer, *val),
+            IgniteValue::Uuid(val) => write_uuid(writer, *val),
+            IgniteValue::Date(val) | IgniteValue::Time(val) => write_i64(writer, *val),
+            IgniteValue::Timestamp(big, little) => {
+                write_i64(writer, *big)?;
+                write_i32(writer, *little)
+            }
+            IgniteValue::Decimal(val) => write_decimal(writer, val),
+            IgniteValue::Enum(val) => write_enum(writer, *val),
+            IgniteValue::ArrByte(arr) => write_primitive_arr(writer, arr, |w, v| write_u8(w, *v)),
+            IgniteValue::ArrShort(arr) => write_primitive_arr(writer, arr, |w, v| write_i16(w, *v)),
+            IgniteValue::ArrInt(arr) => write_primitive_arr(writer, arr, |w, v| write_i32(w, *v)),
+            IgniteValue::ArrLong(arr) => write_primitive_arr(writer, arr, |w, v| write_i64(w, *v)),
+            IgniteValue::ArrFloat(arr) => write_primitive_arr(writer, arr, |w, v| write_f32(w, *v)),
+            IgniteValue::ArrDouble(arr) => {
+                write_primitive_arr(writer, arr, |w, v| write_f64(w, *v))
+            }
+            IgniteValue::ArrChar(arr) => write_primitive_arr(writer, arr, |w, v| write_u16(w, *v)),
+            IgniteValue::ArrBool(arr) => write_primitive_arr(writer, arr, |w, v| write_bool(w, *v)),
+            IgniteValue::ArrString(items)
+            | IgniteValue::ArrUuid(items)
+            | IgniteValue::ArrDate(items)
+            | IgniteValue::ArrTime(items)
+            | IgniteValue::ArrTimestamp(items)
+            | IgniteValue::ArrDecimal(items) => Self::write_values(writer, items),
+            IgniteValue::ArrEnum(type_id, items) | IgniteValue::ArrObj(type_id, items) => {
+                write_i32(writer, *type_id)?;
+                Self::write_values(writer, items)
+            }
+            IgniteValue::Collection(kind, items) => {
+                write_i32(writer, items.len() as i32)?;
+                write_i8(writer, *kind)?;
+                for item in items {
+                    item.write(writer)?;
+                }
+                Ok(())
+            }
+            IgniteValue::Map(kind, pairs) => {
+                write_i32(writer, pairs.len() as i32)?;
+                write_i8(writer, *kind)?;
+                for (key, value) in pairs {
+                    key.write(writer)?;
+                    value.write(writer)?;
+                }
+                Ok(())
+            }
+            IgniteValue::ComplexObj(_) | IgniteValue::Null => Ok(()),
+        }
+    }
+
+    fn size(&self) -> usize {
+        let body = match self {
+            IgniteValue::String(val) => 4 + val.len(), // len, string itself
+            IgniteValue::Long(_) | IgniteValue::Date(_) | IgniteValue::Time(_) => 8,
+            IgniteValue::Int(_) | IgniteValue::Float(_) => 4,
+            IgniteValue::Short(_) | IgniteValue::Char(_) => 2,
+            IgniteValue::Byte(_) | IgniteValue::Bool(_) => 1,
+            IgniteValue::Double(_) => 8,
+            IgniteValue::Uuid(_) => 16,
+            IgniteValue::Timestamp(_, _) => 8 + 4,
+            IgniteValue::Decimal(val) => 4 + 4 + val.to_java_bytes().len(), // scale, len, data
+            IgniteValue::Enum(_) => 4 + 4,                                  // type id, ordinal
+            IgniteValue::ArrByte(arr) => 4 + arr.len(),
+            IgniteValue::ArrShort(arr) => 4 + arr.len() * 2,
+            IgniteValue::ArrInt(arr) => 4 + arr.len() * 4,
+            IgniteValue::ArrLong(arr) => 4 + arr.len() * 8,
+            IgniteValue::ArrFloat(arr) => 4 + arr.len() * 4,
+            IgniteValue::ArrDouble(arr) => 4 + arr.len() * 8,
+            IgniteValue::ArrChar(arr) => 4 + arr.len() * 2,
+            IgniteValue::ArrBool(arr) => 4 + arr.len(),
+            IgniteValue::ArrString(items)
+            | IgniteValue::ArrUuid(items)
+            | IgniteValue::ArrDate(items)
+            | IgniteValue::ArrTime(items)
+            | IgniteValue::ArrTimestamp(items)
+            | IgniteValue::ArrDecimal(items) => Self::values_size(items),
+            IgniteValue::ArrEnum(_, items) | IgniteValue::ArrObj(_, items) => {
+                4 + Self::values_size(items) // type id, items
+            }
+            IgniteValue::Collection(_, items) => {
+                4 + 1 + items.iter().map(|item| item.size()).sum::<usize>() // len, kind, items
+            }
+            IgniteValue::Map(_, pairs) => {
+                4 + 1 // len, kind
+                    + pairs
+                        .iter()
+                        .map(|(k, v)| k.size() + v.size())
+                        .sum::<usize>()
+            }
+            IgniteValue::ComplexObj(obj) => return obj.size(),
+            IgniteValue::Null => 0,
+        };
+        body + 1 // type code
+    }
+}
+
+impl ReadableType for IgniteValue {
+    fn read_unwrapped(type_code: TypeCode, reader: &mut impl Read) -> IgniteResult<Option<Self>> {
+        match IgniteValue::read_value(type_code, reader)? {
+            IgniteValue::Null => Ok(None),
+            val => Ok(Some(val)),
+        }
+    }
+}
+
+impl From<Decimal> for IgniteValue {
+    fn from(val: Decimal) -> Self {
+        IgniteValue::Decimal(val)
+    }
+}
+
+impl From<Uuid> for IgniteValue {
+    fn from(val: Uuid) -> Self {
+        IgniteValue::Uuid(val)
+    }
+}
+
+impl From<Date> for IgniteValue {
+    fn from(val: Date) -> Self {
+        IgniteValue::Date(val.millis)
+    }
+}
+
+impl From<Time> for IgniteValue {
+    fn from(val: Time) -> Self {
+        IgniteValue::Time(val.millis)
+    }
+}
+
+impl From<Timestamp> for IgniteValue {
+    fn from(val: Timestamp) -> Self {
+        IgniteValue::Timestamp(val.millis, val.nanos)
+    }
+}
+
+fn write_primitive_arr<T, F>(writer: &mut dyn Write, arr: &[T], write_fn: F) -> std::io::Result<()>
+where
+    F: Fn(&mut dyn Write, &T) -> std::io::Result<()>,
+{
+    write_i32(writer, arr.len() as i32)?;
+    for el in arr {
+        write_fn(writer, el)?;
+    }
+    Ok(())
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct IgniteField {
     pub name: String,
@@ -51,7 +511,7 @@ pub struct ComplexObjectSchema {
 }
 
 // https://apacheignite.readme.io/docs/binary-client-protocol-data-format#complex-object
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct ComplexObject {
     pub schema: Arc<ComplexObjectSchema>,
     pub values: Vec<IgniteValue>,
@@ -68,45 +528,35 @@ impl ComplexObject {
             )?;
             write_i32(&mut schema, COMPLEX_OBJ_HEADER_LEN + values.len() as i32)?;
             match val {
-                IgniteValue::String(val) => {
-                    write_u8(&mut values, TypeCode::String as u8)?;
-                    write_string(&mut values, val)?
-                }
-                IgniteValue::Long(val) => {
-                    write_u8(&mut values, TypeCode::Long as u8)?;
-                    write_i64(&mut values, *val)?;
-                }
-                IgniteValue::Int(val) => {
-                    write_u8(&mut values, TypeCode::Int as u8)?;
-                    write_i32(&mut values, *val)?;
-                }
-                IgniteValue::Short(val) => {
-                    write_u8(&mut values, TypeCode::Short as u8)?;
-                    write_i16(&mut values, *val)?;
-                }
-                IgniteValue::Bool(val) => {
-                    write_u8(&mut values, TypeCode::Bool as u8)?;
-                    write_u8(&mut values, *val as u8)?;
-                }
-                IgniteValue::Timestamp(big, little) => {
-                    write_u8(&mut values, TypeCode::Timestamp as u8)?;
-                    write_i64(&mut values, *big)?;
-                    write_i32(&mut values, *little)?;
-                }
-                IgniteValue::Decimal(scale, data) => {
-                    write_u8(&mut values, TypeCode::Decimal as u8)?;
-                    write_i32(&mut values, *scale)?;
-                    write_i32(&mut values, data.len() as i32)?;
-                    values.write_all(data)?;
-                }
-                IgniteValue::Null => {
-                    write_null(&mut values)?;
-                }
+                IgniteValue::Null => write_null(&mut values)?,
+                _ => val.write(&mut values)?,
             }
         }
         Ok((values, schema))
     }
 
+    /// Builtin Java types (like java.lang.Long keys of SQL tables) are not wrapped
+    /// into a complex object and are serialized as a single value
+    fn is_builtin(&self) -> bool {
+        IgniteType::from_java_type(self.schema.type_name.as_str(), 0, 0).is_some()
+    }
+
+    /// The value of the builtin type. It must be of that type or NULL
+    fn builtin_value(&self) -> std::io::Result<&IgniteValue> {
+        let val = self
+            .values
+            .last()
+            .ok_or_else(|| std::io::Error::new(ErrorKind::InvalidInput, "No values"))?;
+        let expected = IgniteType::from_java_type(self.schema.type_name.as_str(), 0, 0);
+        match expected {
+            Some(ref t) if t.type_code() == val.type_code() || *val == IgniteValue::Null => Ok(val),
+            _ => Err(std::io::Error::new(
+                ErrorKind::InvalidInput,
+                format!("Mismatched types! {} expected", self.schema.type_name),
+            )),
+        }
+    }
+
     pub fn get_offset_flags(offsets: &[i32]) -> u16 {
         match offsets.last() {
             None => FLAG_OFFSET_ONE_BYTE,
@@ -133,26 +583,7 @@ impl ReadableType for ComplexObject {
             values: vec![],
         };
         match type_code {
-            TypeCode::String => {
-                let str = read_string(reader).unwrap();
-                let field = IgniteValue::String(str);
-                me.values.push(field); // TODO: set type_name to "java.lang.Long"
-            }
-            TypeCode::Long => {
-                let val = read_i64(reader).unwrap();
-                let field = IgniteValue::Long(val);
-                me.values.push(field);
-            }
-            TypeCode::Int => {
-                let val = read_i32(reader).unwrap();
-                let field = IgniteValue::Int(val);
-                me.values.push(field);
-            }
-            TypeCode::Short => {
-                let val = read_i16(reader).unwrap();
-                let field = IgniteValue::Short(val);
-                me.values.push(field);
-            }
+            TypeCode::Null => return Ok(None),
             TypeCode::ComplexObj => {
                 // read header minus type code
                 let mut partial_header = vec![0u8; COMPLEX_OBJ_HEADER_LEN as usize - 1];
@@ -166,34 +597,63 @@ impl ReadableType for ComplexObject {
                 // read values from our reconstructed header
                 let mut header = Cursor::new(&mut data);
                 let _type_code = read_u8(&mut header)?; // offset 0
-                assert_eq!(read_u8(&mut header)?, 1, "Only version 1 supported"); // version
+                let version = read_u8(&mut header)?; // offset 1
                 let flags = read_u16(&mut header)?; // offset 2
                 let _type_id = read_i32(&mut header)?; // offset 4
                 let _hash_code = read_i32(&mut header)?; // offset 8
-                let object_len = read_i32(&mut header)? as usize; // offset 12
+                let object_len = read_i32(&mut header)?; // offset 12
                 let _schema_id = read_i32(&mut header)?; // offset 16
-                let field_indexes_offset = read_i32(&mut header)? as usize; // offset 20
-
-                // compute stuff we need to read body
-                let (one, two) = (
-                    (flags & FLAG_OFFSET_ONE_BYTE) != 0,
-                    (flags & FLAG_OFFSET_TWO_BYTES) != 0,
-                );
-                assert_eq!(flags & HAS_RAW_DATA, 0, "Cannot read raw data");
-                let _compact = flags & FLAG_COMPACT_FOOTER != 0;
-                assert_ne!(flags & FLAG_HAS_SCHEMA, 0, "Schema is required");
-                assert_ne!(flags & FLAG_USER_TYPE, 0, "Only user types are supported");
-                let _offset_sz = match (one, two) {
-                    (true, false) => 1,
-                    (false, true) => 2,
-                    (false, false) => 4,
-                    (true, true) => Err(IgniteError::from("Invalid offset flags"))?,
-                };
+                let field_indexes_offset = read_i32(&mut header)?; // offset 20
+
+                if version != 1 {
+                    return Err(IgniteError::UnsupportedType(format!(
+                        "Complex object version {}",
+                        version
+                    )));
+                }
+                if flags & HAS_RAW_DATA != 0 {
+                    return Err(IgniteError::UnsupportedType(
+                        "Complex object with raw data".to_owned(),
+                    ));
+                }
+                if flags & FLAG_HAS_SCHEMA == 0 {
+                    return Err(IgniteError::UnsupportedType(
+                        "Complex object without schema".to_owned(),
+                    ));
+                }
+                if flags & FLAG_USER_TYPE == 0 {
+                    return Err(IgniteError::UnsupportedType(
+                        "Complex object of a system type".to_owned(),
+                    ));
+                }
+
+                // fields are read in order, so only the offset size flags are checked
+                if flags & FLAG_OFFSET_ONE_BYTE != 0 && flags & FLAG_OFFSET_TWO_BYTES != 0 {
+                    return Err(IgniteError::Protocol("Invalid offset flags".to_owned()));
+                }
+                let object_len = usize::try_from(object_len)
+                    .ok()
+                    .filter(|len| *len >= data.len())
+                    .ok_or_else(|| {
+                        IgniteError::Protocol("Invalid complex object length!".to_owned())
+                    })?;
+                let object_len = check_object_size(object_len)?;
+                let field_indexes_offset = usize::try_from(field_indexes_offset)
+                    .ok()
+                    .filter(|offset| *offset <= object_len)
+                    .ok_or_else(|| {
+                        IgniteError::Protocol("Invalid complex object schema offset!".to_owned())
+                    })?;
 
                 // append body
-                let mut body = vec![0u8; object_len - data.len()];
-                reader.read_exact(&mut body)?;
-                data.extend(body);
+                reader
+                    .take((object_len - data.len()) as u64)
+                    .read_to_end(&mut data)?;
+                if data.len() != object_len {
+                    return Err(IgniteError::Protocol(
+                        "Complex object is truncated!".to_owned(),
+                    ));
+                }
 
                 // for acquiring test fixture data
                 // println!("data={:02X?}", data);
@@ -203,68 +663,28 @@ impl ReadableType for ComplexObject {
                 remainder.set_position(COMPLEX_OBJ_HEADER_LEN as u64);
                 while (remainder.position() as usize) < field_indexes_offset {
                     let field_type = TypeCode::try_from(read_u8(&mut remainder)?)?;
-                    let val = match field_type {
-                        TypeCode::String => IgniteValue::String(read_string(&mut remainder)?),
-                        TypeCode::Long => IgniteValue::Long(read_i64(&mut remainder)?),
-                        TypeCode::Int => IgniteValue::Int(read_i32(&mut remainder)?),
-                        TypeCode::Short => IgniteValue::Short(read_i16(&mut remainder)?),
-                        TypeCode::Bool => IgniteValue::Bool(read_bool(&mut remainder)?),
-                        TypeCode::Timestamp => {
-                            let big = read_i64(&mut remainder)?;
-                            let little = read_i32(&mut remainder)?;
-                            IgniteValue::Timestamp(big, little)
-                        }
-                        TypeCode::Decimal => {
-                            let scale = read_i32(&mut remainder)?;
-                            let len = read_i32(&mut remainder)?;
-                            let mut buf = vec![0; len as usize];
-                            remainder.read_exact(&mut buf)?;
-                            IgniteValue::Decimal(scale, buf)
-                        }
-                        TypeCode::Null => IgniteValue::Null,
-                        _ => {
-                            let msg = format!("Unknown type: {:?}", field_type);
-                            Err(IgniteError::from(msg.as_str()))?
-                        }
-                    };
+                    let val = IgniteValue::read_value(field_type, &mut remainder)?;
                     me.values.push(val);
                 }
                 // the remainder of bytes are offsets to fields which we have already read
             }
-            _ => todo!("Unsupported type code: {:?}", type_code),
+            // builtin types are read as a single value
+            _ => {
+                let val = IgniteValue::read_value(type_code, reader)?;
+                me.values.push(val);
+            }
         }
         Ok(Some(me))
     }
 }
 
+impl CollectionItem for ComplexObject {}
+
 impl WritableType for ComplexObject {
     fn write(&self, writer: &mut dyn Write) -> std::io::Result<()> {
         // Handle primitives as ComplexObjects for simplicity
-        if self.schema.type_name == "java.lang.Long" {
-            let val = self
-                .values
-                .last()
-                .ok_or_else(|| std::io::Error::new(ErrorKind::Other, "No values"))?;
-            let val = match val {
-                IgniteValue::Long(val) => val,
-                _ => Err(std::io::Error::new(ErrorKind::Other, "Mismatched types!"))?,
-            };
-            write_u8(writer, TypeCode::Long as u8)?;
-            write_i64(writer, *val)?;
-            return Ok(());
-        }
-        if self.schema.type_name == "java.lang.String" {
-            let val = self
-                .values
-                .last()
-                .ok_or_else(|| std::io::Error::new(ErrorKind::Other, "No values"))?;
-            let val = match val {
-                IgniteValue::String(val) => val,
-                _ => Err(std::io::Error::new(ErrorKind::Other, "Mismatched types!"))?,
-            };
-            write_u8(writer, TypeCode::String as u8)?;
-            write_string(writer, val)?;
-            return Ok(());
+        if self.is_builtin() {
+            return self.builtin_value()?.write(writer);
         }
 
         // write fields to vec so we can hash
@@ -290,19 +710,62 @@ impl WritableType for ComplexObject {
     }
 
     fn size(&self) -> usize {
-        if self.schema.type_name == "java.lang.Long" {
-            return size_of::<i64>() + 1;
+        // the object which can't be written has no size
+        if self.is_builtin() {
+            return self.builtin_value().map_or(0, |val| val.size());
         }
-        if self.schema.type_name == "java.lang.String" {
-            let val = self.values.last().expect("No values!");
-            let val = match val {
-                IgniteValue::String(val) => val,
-                _ => panic!("Mismatched types!"),
+        self.get_data().map_or(0, |(values, schema)| {
+            values.len() + schema.len() + COMPLEX_OBJ_HEADER_LEN as usize
+        })
+    }
+}
+
+impl ComplexObjectSchema {
+    /// Find the key and value DynamicIgniteTypes for a table.
+    pub fn infer_schemas(
+        entity: &QueryEntity,
+    ) -> IgniteResult<(Arc<ComplexObjectSchema>, Arc<ComplexObjectSchema>)> {
+        let key_fields: Vec<_> = entity
+            .query_fields
+            .iter()
+            .filter(|f| f.key_field || entity.key_field() == Some(f.name.as_str()))
+            .collect();
+        let val_fields: Vec<_> = entity
+            .query_fields
+            .iter()
+            .filter(|f| !f.key_field && entity.key_field() != Some(f.name.as_str()))
+            .collect();
+        let key_fields = Self::convert_fields(&key_fields)?;
+        let val_fields = Self::convert_fields(&val_fields)?;
+        let k = ComplexObjectSchema {
+            type_name: entity.key_type.clone(),
+            fields: key_fields,
+        };
+        let v = ComplexObjectSchema {
+            type_name: entity.value_type.clone(),
+            fields: val_fields,
+        };
+        Ok((Arc::new(k), Arc::new(v)))
+    }
+
+    fn convert_fields(qry_fields: &[&QueryField]) -> IgniteResult<Vec<IgniteField>> {
+        let mut fields = vec![];
+        for f in qry_fields.iter() {
+            let t = IgniteType::from_java_type(f.type_name.as_str(), f.precision, f.scale)
+                .ok_or_else(|| {
+                    IgniteError::UnsupportedType(format!("Unknown field type: {}", f.type_name))
+                })?;
+            let field = IgniteField {
+                name: f.name.to_string(),
+                r#type: t,
             };
-            return size_of::<i32>() + 1 + val.len();
+            fields.push(field);
         }
-        let (values, schema) = self.get_data().expect("Can't get size!");
-        values.len() + schema.len() + COMPLEX_OBJ_HEADER_LEN as usize
+        Ok(fields)
+    }
+
+    pub fn type_name(&self) -> &str {
+        self.type_name.as_str()
     }
 }
 
@@ -410,61 +873,109 @@ mod tests {
         let actual_hex = format!("{:02X?}", actual_bytes);
         assert_eq!(actual_hex, expected_hex);
     }
-}
+    #[test]
+    fn test_value_round_trip() {
+        let values = vec![
+            IgniteValue::Byte(0xFE),
+            IgniteValue::Float(1.5),
+            IgniteValue::Double(-2.25),
+            IgniteValue::Char(0x0416),
+            IgniteValue::Uuid(Uuid::new(0x0102_0304_0506_0708, 0x090A_0B0C_0D0E_0F10)),
+            IgniteValue::Date(1687350896000),
+            IgniteValue::Time(45296000),
+            IgniteValue::Decimal("-12.345".parse().unwrap()),
+            IgniteValue::Enum(Enum {
+                type_id: 42,
+                ordinal: 3,
+            }),
+            IgniteValue::ArrLong(vec![1, -1]),
+            IgniteValue::ArrString(vec![IgniteValue::String("a".into()), IgniteValue::Null]),
+            IgniteValue::ArrObj(-1, vec![IgniteValue::Int(1), IgniteValue::Bool(false)]),
+            IgniteValue::Collection(1, vec![IgniteValue::Short(7)]),
+            IgniteValue::Map(
+                1,
+                vec![(IgniteValue::String("k".into()), IgniteValue::Long(9))],
+            ),
+        ];
+        for expected in values {
+            let mut bytes = vec![];
+            expected.write(&mut bytes).unwrap();
+            assert_eq!(bytes.len(), expected.size(), "{:?}", expected);
+            let actual = IgniteValue::read(&mut Cursor::new(bytes)).unwrap().unwrap();
+            assert_eq!(actual, expected);
+        }
+    }
 
-impl ComplexObjectSchema {
-    /// Find the key and value DynamicIgniteTypes for a table.
-    pub fn infer_schemas(
-        entity: &QueryEntity,
-    ) -> IgniteResult<(Arc<ComplexObjectSchema>, Arc<ComplexObjectSchema>)> {
-        let key_fields: Vec<_> = entity
-            .query_fields
-            .iter()
-            .filter(|f| f.key_field || entity.key_field == f.name)
-            .collect();
-        let val_fields: Vec<_> = entity
-            .query_fields
-            .iter()
-            .filter(|f| !f.key_field && entity.key_field != f.name)
-            .collect();
-        let key_fields = Self::convert_fields(&key_fields)?;
-        let val_fields = Self::convert_fields(&val_fields)?;
-        let k = ComplexObjectSchema {
-            type_name: entity.key_type.clone(),
-            fields: key_fields,
+    #[test]
+    fn test_nested_complex_object() {
+        let inner = ComplexObject {
+            schema: Arc::new(ComplexObjectSchema {
+                type_name: "Inner".to_string(),
+                fields: vec![IgniteField {
+                    name: "ID".to_string(),
+                    r#type: IgniteType::Uuid,
+                }],
+            }),
+            values: vec![IgniteValue::Uuid(Uuid::new(1, 2))],
         };
-        let v = ComplexObjectSchema {
-            type_name: entity.value_type.clone(),
-            fields: val_fields,
+        let outer = ComplexObject {
+            schema: Arc::new(ComplexObjectSchema {
+                type_name: "Outer".to_string(),
+                fields: vec![
+                    IgniteField {
+                        name: "INNER".to_string(),
+                        r#type: IgniteType::ComplexObj,
+                    },
+                    IgniteField {
+                        name: "RATE".to_string(),
+                        r#type: IgniteType::Double,
+                    },
+                ],
+            }),
+            values: vec![IgniteValue::ComplexObj(inner), IgniteValue::Double(0.5)],
         };
-        Ok((Arc::new(k), Arc::new(v)))
-    }
 
-    fn convert_fields(qry_fields: &[&QueryField]) -> IgniteResult<Vec<IgniteField>> {
-        let mut fields = vec![];
-        for f in qry_fields.iter() {
-            let t: IgniteType = match f.type_name.as_str() {
-                "java.lang.Long" => IgniteType::Long,
-                "java.lang.Short" => IgniteType::Short,
-                "java.lang.String" => IgniteType::String,
-                "java.sql.Timestamp" => IgniteType::Timestamp,
-                "java.lang.Integer" => IgniteType::Int,
-                "java.lang.Boolean" => IgniteType::Bool,
-                "java.math.BigDecimal" => IgniteType::Decimal(f.precision, f.scale),
-                _ => Err(IgniteError::from(
-                    format!("Unknown field type: {}", f.type_name).as_str(),
-                ))?,
-            };
-            let field = IgniteField {
-                name: f.name.to_string(),
-                r#type: t,
-            };
-            fields.push(field);
+        let mut bytes = vec![];
+        outer.write(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), outer.size());
+        let actual = ComplexObject::read(&mut Cursor::new(bytes))
+            .unwrap()
+            .unwrap();
+        match &actual.values[..] {
+            [IgniteValue::ComplexObj(inner), IgniteValue::Double(rate)] => {
+                assert_eq!(inner.values, vec![IgniteValue::Uuid(Uuid::new(1, 2))]);
+                assert_eq!(*rate, 0.5);
+            }
+            other => panic!("Unexpected values: {:?}", other),
         }
-        Ok(fields)
     }
 
-    pub fn type_name(&self) -> &str {
-        self.type_name.as_str()
+    #[test]
+    fn test_builtin_value() {
+        let builtin = |values: Vec<IgniteValue>| ComplexObject {
+            schema: Arc::new(ComplexObjectSchema {
+                type_name: "java.lang.Long".to_string(),
+                fields: vec![],
+            }),
+            values,
+        };
+
+        let key = builtin(vec![IgniteValue::Long(5)]);
+        let mut bytes = vec![];
+        key.write(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), key.size());
+        let actual = ComplexObject::read(&mut Cursor::new(bytes))
+            .unwrap()
+            .unwrap();
+        assert_eq!(actual.values, key.values);
+
+        // value of another type and no value at all can't be written
+        for key in [
+            builtin(vec![IgniteValue::String("5".into())]),
+            builtin(vec![]),
+        ] {
+            assert!(key.write(&mut vec![]).is_err());
+            assert_eq!(key.size(), 0);
+        }
     }
 }
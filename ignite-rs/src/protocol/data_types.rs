@@ -4,13 +4,18 @@ use crate::error::{IgniteError, IgniteResult};
 use crate::protocol::*;
 use crate::protocol::{read_u8, TypeCode};
 
-use crate::{Enum, ReadableType, WritableType};
+use crate::{
+    ArrayItem, CollectionItem, Date, Decimal, Enum, JavaType, ReadableType, Time, Timestamp, Uuid,
+    WritableType,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque};
+use std::hash::{BuildHasher, Hash};
 use std::io;
+use std::iter::FromIterator;
 
 /// Ignite's 'char' is a UTF-16 code UNIT, which means its size is 2 bytes.
 /// As Rust's 'char' is a Unicode scalar value (a.k.a UTF-32 code unit) and has 4 bytes,
 /// I don't see how the API should be properly implemented. u16 is used for now
-
 macro_rules! write_type {
     ($t:ty, $code:path, $write_fn:ident, $size:expr) => {
         impl WritableType for $t {
@@ -36,6 +41,10 @@ write_type!(f32, TypeCode::Float, write_f32, 4);
 write_type!(f64, TypeCode::Double, write_f64, 8);
 write_type!(bool, TypeCode::Bool, write_bool, 1);
 write_type!(Enum, TypeCode::Enum, write_enum, 8);
+write_type!(Uuid, TypeCode::Uuid, write_uuid, 16);
+write_type!(Date, TypeCode::Date, write_date, 8);
+write_type!(Time, TypeCode::Time, write_time, 8);
+write_type!(Timestamp, TypeCode::Timestamp, write_timestamp, 12);
 
 impl WritableType for String {
     fn write(&self, writer: &mut dyn Write) -> io::Result<()> {
@@ -49,33 +58,59 @@ impl WritableType for String {
     }
 }
 
+impl WritableType for Decimal {
+    fn write(&self, writer: &mut dyn Write) -> io::Result<()> {
+        write_u8(writer, TypeCode::Decimal as u8)?;
+        write_decimal(writer, self)?;
+        Ok(())
+    }
+
+    fn size(&self) -> usize {
+        self.to_java_bytes().len() + 1 + 4 + 4 // magnitude, type code, scale, len
+    }
+}
+
+/// Fails to read the value of another type. Its bytes would be misread
+/// or the stream would be desynced
+fn unexpected_type_code<T>(type_code: TypeCode, type_name: &str) -> IgniteResult<T> {
+    Err(IgniteError::Protocol(format!(
+        "Cannot read TypeCode {} as {}",
+        type_code as u8, type_name
+    )))
+}
+
 macro_rules! read_type {
-    ($t:ty, $read_fn:ident) => {
+    ($t:ty, $read_fn:ident, $($code:path)|+) => {
         impl ReadableType for $t {
             fn read_unwrapped(
                 type_code: TypeCode,
                 reader: &mut impl Read,
             ) -> IgniteResult<Option<Self>> {
-                let value: Option<Self> = match type_code {
-                    TypeCode::Null => None,
-                    _ => Some($read_fn(reader)?),
-                };
-                Ok(value)
+                match type_code {
+                    TypeCode::Null => Ok(None),
+                    $($code)|+ => Ok(Some($read_fn(reader)?)),
+                    _ => unexpected_type_code(type_code, stringify!($t)),
+                }
             }
         }
     };
 }
 
-read_type!(u8, read_u8);
-read_type!(u16, read_u16);
-read_type!(i16, read_i16);
-read_type!(i32, read_i32);
-read_type!(i64, read_i64);
-read_type!(f32, read_f32);
-read_type!(f64, read_f64);
-read_type!(bool, read_bool);
-read_type!(String, read_string);
-read_type!(Enum, read_enum);
+read_type!(u8, read_u8, TypeCode::Byte);
+read_type!(u16, read_u16, TypeCode::Char);
+read_type!(i16, read_i16, TypeCode::Short);
+read_type!(i32, read_i32, TypeCode::Int);
+read_type!(i64, read_i64, TypeCode::Long);
+read_type!(f32, read_f32, TypeCode::Float);
+read_type!(f64, read_f64, TypeCode::Double);
+read_type!(bool, read_bool, TypeCode::Bool);
+read_type!(String, read_string, TypeCode::String);
+read_type!(Decimal, read_decimal, TypeCode::Decimal);
+read_type!(Enum, read_enum, TypeCode::Enum | TypeCode::BinaryEnum);
+read_type!(Uuid, read_uuid, TypeCode::Uuid);
+read_type!(Date, read_date, TypeCode::Date);
+read_type!(Time, read_time, TypeCode::Time);
+read_type!(Timestamp, read_timestamp, TypeCode::Timestamp);
 
 macro_rules! write_primitive_arr {
     ($t:ty, $code:path, $write_fn:ident, $size:expr) => {
@@ -106,30 +141,30 @@ write_primitive_arr!(bool, TypeCode::ArrBool, write_bool, 1);
 write_primitive_arr!(u16, TypeCode::ArrChar, write_u16, 2);
 
 macro_rules! read_primitive_arr {
-    ($t:ty, $read_fn:ident) => {
+    ($t:ty, $code:path, $read_fn:ident) => {
         impl ReadableType for Vec<$t> {
             fn read_unwrapped(
                 type_code: TypeCode,
                 reader: &mut impl Read,
             ) -> IgniteResult<Option<Self>> {
-                let value: Option<Self> = match type_code {
-                    TypeCode::Null => None,
-                    _ => Some(read_primitive_arr(reader, $read_fn)?),
-                };
-                Ok(value)
+                match type_code {
+                    TypeCode::Null => Ok(None),
+                    $code => Ok(Some(read_primitive_arr(reader, $read_fn)?)),
+                    _ => unexpected_type_code(type_code, stringify!(Vec<$t>)),
+                }
             }
         }
     };
 }
 
-read_primitive_arr!(u8, read_u8);
-read_primitive_arr!(u16, read_u16);
-read_primitive_arr!(i16, read_i16);
-read_primitive_arr!(i32, read_i32);
-read_primitive_arr!(i64, read_i64);
-read_primitive_arr!(f32, read_f32);
-read_primitive_arr!(f64, read_f64);
-read_primitive_arr!(bool, read_bool);
+read_primitive_arr!(u8, TypeCode::ArrByte, read_u8);
+read_primitive_arr!(u16, TypeCode::ArrChar, read_u16);
+read_primitive_arr!(i16, TypeCode::ArrShort, read_i16);
+read_primitive_arr!(i32, TypeCode::ArrInt, read_i32);
+read_primitive_arr!(i64, TypeCode::ArrLong, read_i64);
+read_primitive_arr!(f32, TypeCode::ArrFloat, read_f32);
+read_primitive_arr!(f64, TypeCode::ArrDouble, read_f64);
+read_primitive_arr!(bool, TypeCode::ArrBool, read_bool);
 
 // pack all vectors as object array
 impl<T: WritableType + ReadableType> WritableType for Vec<Option<T>> {
@@ -160,30 +195,282 @@ impl<T: WritableType + ReadableType> WritableType for Vec<Option<T>> {
 
 impl<T: WritableType + ReadableType> ReadableType for Vec<Option<T>> {
     fn read_unwrapped(type_code: TypeCode, reader: &mut impl Read) -> IgniteResult<Option<Self>> {
-        match type_code {
-            TypeCode::Null => Ok(None),
-            TypeCode::ArrObj => {
+        let len = match type_code {
+            TypeCode::Null => return Ok(None),
+            TypeCode::ArrObj | TypeCode::ArrEnum => {
                 read_i32(reader)?; // ignore type id
-                let len = read_i32(reader)?;
-                let mut data: Vec<Option<T>> = Vec::with_capacity(len as usize);
-                for _ in 0..len {
-                    let item = T::read(reader)?;
-                    data.push(item);
-                }
-                Ok(Some(data))
+                read_len(reader)?
             }
             TypeCode::Collection => {
-                let len = read_i32(reader)?;
+                let len = read_len(reader)?;
                 read_i8(reader)?; // ignore collection type
-                let mut data: Vec<Option<T>> = Vec::with_capacity(len as usize);
-                for _ in 0..len {
-                    let item = T::read(reader)?;
-                    data.push(item);
-                }
-                Ok(Some(data))
+                len
             }
-            _ => Err(IgniteError::from("Expected Array or Collection!")),
+            TypeCode::ArrString
+            | TypeCode::ArrUuid
+            | TypeCode::ArrDate
+            | TypeCode::ArrTime
+            | TypeCode::TimestampArray
+            | TypeCode::ArrDecimal => read_len(reader)?,
+            _ => {
+                return Err(IgniteError::Protocol(
+                    "Expected Array or Collection!".to_owned(),
+                ))
+            }
+        };
+        let mut data: Vec<Option<T>> = Vec::with_capacity(prealloc_capacity(len));
+        for _ in 0..len {
+            let item = T::read(reader)?;
+            data.push(item);
+        }
+        Ok(Some(data))
+    }
+}
+
+fn write_collection<'a, T, I>(
+    writer: &mut dyn Write,
+    kind: i8,
+    len: usize,
+    items: I,
+) -> io::Result<()>
+where
+    T: WritableType + 'a,
+    I: Iterator<Item = &'a T>,
+{
+    write_u8(writer, TypeCode::Collection as u8)?;
+    write_i32(writer, len as i32)?; // number of items
+    write_i8(writer, kind)?;
+    for item in items {
+        item.write(writer)?;
+    }
+    Ok(())
+}
+
+fn collection_size<'a, T, I>(items: I) -> usize
+where
+    T: WritableType + 'a,
+    I: Iterator<Item = &'a T>,
+{
+    let items_size: usize = items.map(|item| item.size()).sum();
+    items_size + 1 + 4 + 1 // items, type code, len, kind
+}
+
+/// Reads items of the ArrObj or Collection. NULL items are not allowed
+fn read_collection<T, C>(type_code: TypeCode, reader: &mut impl Read) -> IgniteResult<Option<C>>
+where
+    T: WritableType + ReadableType,
+    C: FromIterator<T>,
+{
+    match Vec::<Option<T>>::read_unwrapped(type_code, reader)? {
+        None => Ok(None),
+        Some(items) => items
+            .into_iter()
+            .map(|item| {
+                item.ok_or_else(|| IgniteError::Protocol("NULL item is not expected".to_owned()))
+            })
+            .collect::<IgniteResult<C>>()
+            .map(Some),
+    }
+}
+
+macro_rules! collection_type {
+    ($t:ident, $kind:path $(, $bound:path)*) => {
+        impl<T: WritableType $(+ $bound)*> WritableType for $t<T> {
+            fn write(&self, writer: &mut dyn Write) -> io::Result<()> {
+                write_collection(writer, $kind, self.len(), self.iter())
+            }
+
+            fn size(&self) -> usize {
+                collection_size(self.iter())
+            }
+        }
+
+        impl<T: WritableType + ReadableType $(+ $bound)*> ReadableType for $t<T> {
+            fn read_unwrapped(
+                type_code: TypeCode,
+                reader: &mut impl Read,
+            ) -> IgniteResult<Option<Self>> {
+                read_collection(type_code, reader)
+            }
+        }
+    };
+}
+
+// Vec of objects is written as java.util.ArrayList. Vec of primitives is written as an array
+collection_type!(Vec, COLLECTION_KIND_ARRAY_LIST, CollectionItem);
+collection_type!(VecDeque, COLLECTION_KIND_ARRAY_LIST);
+collection_type!(LinkedList, COLLECTION_KIND_LINKED_LIST);
+collection_type!(BTreeSet, COLLECTION_KIND_LINKED_HASH_SET, Ord);
+
+// HashSet is written as java.util.HashSet
+impl<T: WritableType, S> WritableType for HashSet<T, S> {
+    fn write(&self, writer: &mut dyn Write) -> io::Result<()> {
+        write_collection(writer, COLLECTION_KIND_HASH_SET, self.len(), self.iter())
+    }
+
+    fn size(&self) -> usize {
+        collection_size(self.iter())
+    }
+}
+
+impl<T, S> ReadableType for HashSet<T, S>
+where
+    T: WritableType + ReadableType + Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn read_unwrapped(type_code: TypeCode, reader: &mut impl Read) -> IgniteResult<Option<Self>> {
+        read_collection(type_code, reader)
+    }
+}
+
+impl CollectionItem for String {}
+impl CollectionItem for Enum {}
+impl CollectionItem for Uuid {}
+impl CollectionItem for Date {}
+impl CollectionItem for Time {}
+impl CollectionItem for Timestamp {}
+impl CollectionItem for Decimal {}
+impl CollectionItem for Vec<u8> {}
+impl CollectionItem for Vec<u16> {}
+impl CollectionItem for Vec<i16> {}
+impl CollectionItem for Vec<i32> {}
+impl CollectionItem for Vec<i64> {}
+impl CollectionItem for Vec<f32> {}
+impl CollectionItem for Vec<f64> {}
+impl CollectionItem for Vec<bool> {}
+impl<T: WritableType + ReadableType> CollectionItem for Vec<Option<T>> {}
+impl<T: CollectionItem> CollectionItem for Vec<T> {}
+impl<T: ArrayItem> CollectionItem for Box<[T]> {}
+impl<T: WritableType + ReadableType> CollectionItem for VecDeque<T> {}
+impl<T: WritableType + ReadableType> CollectionItem for LinkedList<T> {}
+impl<T: WritableType + ReadableType + Ord> CollectionItem for BTreeSet<T> {}
+impl<T, S> CollectionItem for HashSet<T, S>
+where
+    T: WritableType + ReadableType + Eq + Hash,
+    S: BuildHasher + Default,
+{
+}
+impl<K, V, S> CollectionItem for HashMap<K, V, S>
+where
+    K: WritableType + ReadableType + Eq + Hash,
+    V: WritableType + ReadableType,
+    S: BuildHasher + Default,
+{
+}
+impl<K, V> CollectionItem for BTreeMap<K, V>
+where
+    K: WritableType + ReadableType + Ord,
+    V: WritableType + ReadableType,
+{
+}
+
+macro_rules! array_item {
+    ($t:ty, $code:path) => {
+        impl ArrayItem for $t {
+            const ARRAY_TYPE_CODE: TypeCode = $code;
+        }
+    };
+}
+
+array_item!(String, TypeCode::ArrString);
+array_item!(Uuid, TypeCode::ArrUuid);
+array_item!(Date, TypeCode::ArrDate);
+array_item!(Time, TypeCode::ArrTime);
+array_item!(Timestamp, TypeCode::TimestampArray);
+array_item!(Decimal, TypeCode::ArrDecimal);
+
+impl<T: ArrayItem> ArrayItem for Option<T> {
+    const ARRAY_TYPE_CODE: TypeCode = T::ARRAY_TYPE_CODE;
+    const ARRAY_TYPE_ID: Option<i32> = T::ARRAY_TYPE_ID;
+}
+
+macro_rules! java_type {
+    ($t:ty, $name:expr) => {
+        impl JavaType for $t {
+            const JAVA_TYPE_NAME: &'static str = $name;
+        }
+    };
+}
+
+java_type!(u8, "java.lang.Byte");
+java_type!(u16, "java.lang.Character");
+java_type!(i16, "java.lang.Short");
+java_type!(i32, "java.lang.Integer");
+java_type!(i64, "java.lang.Long");
+java_type!(f32, "java.lang.Float");
+java_type!(f64, "java.lang.Double");
+java_type!(bool, "java.lang.Boolean");
+java_type!(String, "java.lang.String");
+java_type!(Uuid, "java.util.UUID");
+java_type!(Date, "java.util.Date");
+java_type!(Time, "java.sql.Time");
+java_type!(Timestamp, "java.sql.Timestamp");
+java_type!(Decimal, "java.math.BigDecimal");
+java_type!(Vec<u8>, "[B");
+java_type!(Vec<u16>, "[C");
+java_type!(Vec<i16>, "[S");
+java_type!(Vec<i32>, "[I");
+java_type!(Vec<i64>, "[J");
+java_type!(Vec<f32>, "[F");
+java_type!(Vec<f64>, "[D");
+java_type!(Vec<bool>, "[Z");
+
+impl<T: CollectionItem> JavaType for Vec<T> {
+    const JAVA_TYPE_NAME: &'static str = "java.util.ArrayList";
+}
+
+impl<T> JavaType for VecDeque<T> {
+    const JAVA_TYPE_NAME: &'static str = "java.util.ArrayList";
+}
+
+impl<T> JavaType for LinkedList<T> {
+    const JAVA_TYPE_NAME: &'static str = "java.util.LinkedList";
+}
+
+impl<T, S> JavaType for HashSet<T, S> {
+    const JAVA_TYPE_NAME: &'static str = "java.util.HashSet";
+}
+
+impl<T> JavaType for BTreeSet<T> {
+    const JAVA_TYPE_NAME: &'static str = "java.util.LinkedHashSet";
+}
+
+impl<K, V, S> JavaType for HashMap<K, V, S> {
+    const JAVA_TYPE_NAME: &'static str = "java.util.HashMap";
+}
+
+impl<K, V> JavaType for BTreeMap<K, V> {
+    const JAVA_TYPE_NAME: &'static str = "java.util.LinkedHashMap";
+}
+
+impl<T: JavaType> JavaType for Option<T> {
+    const JAVA_TYPE_NAME: &'static str = T::JAVA_TYPE_NAME;
+}
+
+// boxed slices are written as dedicated arrays, like String[] or UUID[]
+impl<T: ArrayItem> WritableType for Box<[T]> {
+    fn write(&self, writer: &mut dyn Write) -> io::Result<()> {
+        write_u8(writer, T::ARRAY_TYPE_CODE as u8)?;
+        if let Some(type_id) = T::ARRAY_TYPE_ID {
+            write_i32(writer, type_id)?;
+        }
+        write_i32(writer, self.len() as i32)?; // length of array
+        for item in self.iter() {
+            item.write(writer)?;
         }
+        Ok(())
+    }
+
+    fn size(&self) -> usize {
+        let items_size: usize = self.iter().map(|item| item.size()).sum();
+        let type_id_size = T::ARRAY_TYPE_ID.map_or(0, |_| 4);
+        items_size + type_id_size + 1 + 4 // items, type id, type code, len
+    }
+}
+
+impl<T: ArrayItem> ReadableType for Box<[T]> {
+    fn read_unwrapped(type_code: TypeCode, reader: &mut impl Read) -> IgniteResult<Option<Self>> {
+        read_collection::<T, Vec<T>>(type_code, reader).map(|arr| arr.map(Vec::into_boxed_slice))
     }
 }
 
@@ -203,12 +490,402 @@ impl<T: WritableType> WritableType for Option<T> {
     }
 }
 
+// NULL is a valid value of Option, so it's read as Some(None)
 impl<T: ReadableType> ReadableType for Option<T> {
     fn read_unwrapped(type_code: TypeCode, reader: &mut impl Read) -> IgniteResult<Option<Self>> {
         let inner_op = T::read_unwrapped(type_code, reader)?;
-        match inner_op {
-            None => Ok(None),
-            Some(inner) => Ok(Some(Some(inner))),
+        Ok(Some(inner_op))
+    }
+}
+
+fn write_map<'a, K, V, I>(
+    writer: &mut dyn Write,
+    kind: i8,
+    len: usize,
+    entries: I,
+) -> io::Result<()>
+where
+    K: WritableType + 'a,
+    V: WritableType + 'a,
+    I: Iterator<Item = (&'a K, &'a V)>,
+{
+    write_u8(writer, TypeCode::Map as u8)?;
+    write_i32(writer, len as i32)?; // number of entries
+    write_i8(writer, kind)?;
+    for (key, value) in entries {
+        key.write(writer)?;
+        value.write(writer)?;
+    }
+    Ok(())
+}
+
+fn map_size<'a, K, V, I>(entries: I) -> usize
+where
+    K: WritableType + 'a,
+    V: WritableType + 'a,
+    I: Iterator<Item = (&'a K, &'a V)>,
+{
+    let entries_size: usize = entries.map(|(key, value)| key.size() + value.size()).sum();
+    entries_size + 1 + 4 + 1 // entries, type code, len, kind
+}
+
+/// Reads entries of the Map (25). Map kind is ignored
+fn read_map<K, V, M>(type_code: TypeCode, reader: &mut impl Read) -> IgniteResult<Option<M>>
+where
+    K: ReadableType,
+    V: ReadableType,
+    M: Extend<(K, V)> + Default,
+{
+    match type_code {
+        TypeCode::Null => Ok(None),
+        TypeCode::Map => {
+            let len = read_len(reader)?;
+            read_i8(reader)?; // ignore map kind
+            let mut map = M::default();
+            for _ in 0..len {
+                let key = K::read(reader)?.ok_or_else(|| {
+                    IgniteError::Protocol("NULL map key is not expected".to_owned())
+                })?;
+                let value = V::read(reader)?.ok_or_else(|| {
+                    IgniteError::Protocol("NULL map value is not expected".to_owned())
+                })?;
+                map.extend(Some((key, value)));
+            }
+            Ok(Some(map))
+        }
+        _ => Err(IgniteError::Protocol("Expected Map!".to_owned())),
+    }
+}
+
+// HashMap is written as java.util.HashMap
+impl<K, V, S> WritableType for HashMap<K, V, S>
+where
+    K: WritableType,
+    V: WritableType,
+{
+    fn write(&self, writer: &mut dyn Write) -> io::Result<()> {
+        write_map(writer, MAP_KIND_HASH_MAP, self.len(), self.iter())
+    }
+
+    fn size(&self) -> usize {
+        map_size(self.iter())
+    }
+}
+
+impl<K, V, S> ReadableType for HashMap<K, V, S>
+where
+    K: ReadableType + Eq + Hash,
+    V: ReadableType,
+    S: BuildHasher + Default,
+{
+    fn read_unwrapped(type_code: TypeCode, reader: &mut impl Read) -> IgniteResult<Option<Self>> {
+        read_map(type_code, reader)
+    }
+}
+
+// BTreeMap is written as java.util.LinkedHashMap to preserve the order of keys
+impl<K: WritableType, V: WritableType> WritableType for BTreeMap<K, V> {
+    fn write(&self, writer: &mut dyn Write) -> io::Result<()> {
+        write_map(writer, MAP_KIND_LINKED_HASH_MAP, self.len(), self.iter())
+    }
+
+    fn size(&self) -> usize {
+        map_size(self.iter())
+    }
+}
+
+impl<K: ReadableType + Ord, V: ReadableType> ReadableType for BTreeMap<K, V> {
+    fn read_unwrapped(type_code: TypeCode, reader: &mut impl Read) -> IgniteResult<Option<Self>> {
+        read_map(type_code, reader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque};
+    use std::io::Cursor;
+
+    use crate::error::IgniteError;
+    use crate::protocol::complex_obj::{ComplexObject, IgniteValue};
+    use crate::protocol::{
+        with_decode_limits, write_i32, write_u16, write_u8, DecodeLimits, TypeCode,
+        COLLECTION_KIND_ARRAY_LIST, COLLECTION_KIND_HASH_SET, COLLECTION_KIND_LINKED_HASH_SET,
+        COLLECTION_KIND_LINKED_LIST, MAP_KIND_HASH_MAP, MAP_KIND_LINKED_HASH_MAP,
+    };
+    use crate::{Date, Decimal, ReadableType, Time, Timestamp, Uuid, WritableType};
+
+    fn to_bytes(value: &impl WritableType) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::new();
+        value.write(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), value.size());
+        bytes
+    }
+
+    #[test]
+    fn test_hash_map() {
+        let mut map: HashMap<String, i64> = HashMap::new();
+        map.insert("one".to_string(), 1);
+        map.insert("two".to_string(), 2);
+
+        let bytes = to_bytes(&map);
+        let actual = HashMap::<String, i64>::read(&mut Cursor::new(&bytes)).unwrap();
+        assert_eq!(actual, Some(map));
+
+        match IgniteValue::read(&mut Cursor::new(&bytes)).unwrap() {
+            Some(IgniteValue::Map(kind, entries)) => {
+                assert_eq!(kind, MAP_KIND_HASH_MAP);
+                assert_eq!(entries.len(), 2);
+            }
+            other => panic!("Map expected: {:?}", other),
         }
     }
+
+    #[test]
+    fn test_btree_map() {
+        let mut map: BTreeMap<i32, Option<String>> = BTreeMap::new();
+        map.insert(2, Some("two".to_string()));
+        map.insert(1, None);
+
+        let bytes = to_bytes(&map);
+        let expected = IgniteValue::Map(
+            MAP_KIND_LINKED_HASH_MAP,
+            vec![
+                (IgniteValue::Int(1), IgniteValue::Null),
+                (IgniteValue::Int(2), IgniteValue::String("two".to_string())),
+            ],
+        );
+        assert_eq!(
+            IgniteValue::read(&mut Cursor::new(&bytes)).unwrap(),
+            Some(expected)
+        );
+
+        let actual = BTreeMap::<i32, Option<String>>::read(&mut Cursor::new(&bytes)).unwrap();
+        assert_eq!(actual, Some(map));
+
+        // NULL values are allowed only for Option
+        let actual = BTreeMap::<i32, String>::read(&mut Cursor::new(&bytes));
+        assert!(actual.is_err());
+    }
+
+    fn collection_kind(bytes: &[u8]) -> i8 {
+        match IgniteValue::read(&mut Cursor::new(bytes)).unwrap() {
+            Some(IgniteValue::Collection(kind, _)) => kind,
+            other => panic!("Collection expected: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_collections() {
+        let vec = vec!["a".to_string(), "b".to_string()];
+        let bytes = to_bytes(&vec);
+        assert_eq!(collection_kind(&bytes), COLLECTION_KIND_ARRAY_LIST);
+        assert_eq!(
+            Vec::<String>::read(&mut Cursor::new(&bytes)).unwrap(),
+            Some(vec.clone())
+        );
+
+        let deque: VecDeque<String> = vec.iter().cloned().collect();
+        let bytes = to_bytes(&deque);
+        assert_eq!(collection_kind(&bytes), COLLECTION_KIND_ARRAY_LIST);
+        assert_eq!(
+            VecDeque::<String>::read(&mut Cursor::new(&bytes)).unwrap(),
+            Some(deque)
+        );
+
+        let list: LinkedList<i32> = vec![1, 2, 3].into_iter().collect();
+        let bytes = to_bytes(&list);
+        assert_eq!(collection_kind(&bytes), COLLECTION_KIND_LINKED_LIST);
+        assert_eq!(
+            LinkedList::<i32>::read(&mut Cursor::new(&bytes)).unwrap(),
+            Some(list)
+        );
+
+        let set: HashSet<i64> = vec![1, 2, 3].into_iter().collect();
+        let bytes = to_bytes(&set);
+        assert_eq!(collection_kind(&bytes), COLLECTION_KIND_HASH_SET);
+        assert_eq!(
+            HashSet::<i64>::read(&mut Cursor::new(&bytes)).unwrap(),
+            Some(set)
+        );
+
+        let set: BTreeSet<String> = vec.iter().cloned().collect();
+        let bytes = to_bytes(&set);
+        assert_eq!(collection_kind(&bytes), COLLECTION_KIND_LINKED_HASH_SET);
+        assert_eq!(
+            BTreeSet::<String>::read(&mut Cursor::new(&bytes)).unwrap(),
+            Some(set)
+        );
+
+        // object array could be read as any collection, but NULL items are not allowed
+        let arr = vec![Some("a".to_string()), None];
+        let bytes = to_bytes(&arr);
+        assert!(Vec::<String>::read(&mut Cursor::new(&bytes)).is_err());
+        let arr = vec![Some("a".to_string())];
+        let bytes = to_bytes(&arr);
+        assert_eq!(
+            HashSet::<String>::read(&mut Cursor::new(&bytes)).unwrap(),
+            Some(vec!["a".to_string()].into_iter().collect())
+        );
+    }
+
+    #[test]
+    fn test_arrays() {
+        let strings: Box<[Option<String>]> = vec![Some("a".to_string()), None].into_boxed_slice();
+        let bytes = to_bytes(&strings);
+        let expected = IgniteValue::ArrString(vec![
+            IgniteValue::String("a".to_string()),
+            IgniteValue::Null,
+        ]);
+        assert_eq!(
+            IgniteValue::read(&mut Cursor::new(&bytes)).unwrap(),
+            Some(expected)
+        );
+        assert_eq!(
+            Box::<[Option<String>]>::read(&mut Cursor::new(&bytes)).unwrap(),
+            Some(strings)
+        );
+        assert!(Box::<[String]>::read(&mut Cursor::new(&bytes)).is_err());
+
+        let uuids: Box<[Uuid]> = vec![Uuid::new(1, 2), Uuid::new(3, 4)].into_boxed_slice();
+        let bytes = to_bytes(&uuids);
+        let expected = IgniteValue::ArrUuid(vec![
+            IgniteValue::Uuid(Uuid::new(1, 2)),
+            IgniteValue::Uuid(Uuid::new(3, 4)),
+        ]);
+        assert_eq!(
+            IgniteValue::read(&mut Cursor::new(&bytes)).unwrap(),
+            Some(expected)
+        );
+        assert_eq!(
+            Box::<[Uuid]>::read(&mut Cursor::new(&bytes)).unwrap(),
+            Some(uuids.clone())
+        );
+        // typed arrays are readable as vectors as well
+        assert_eq!(
+            Vec::<Uuid>::read(&mut Cursor::new(&bytes)).unwrap(),
+            Some(uuids.into_vec())
+        );
+
+        let decimals: Box<[Decimal]> = vec!["1.5".parse().unwrap()].into_boxed_slice();
+        let bytes = to_bytes(&decimals);
+        assert_eq!(
+            Box::<[Decimal]>::read(&mut Cursor::new(&bytes)).unwrap(),
+            Some(decimals)
+        );
+
+        let timestamps: Box<[Timestamp]> = vec![Timestamp {
+            millis: 1,
+            nanos: 2,
+        }]
+        .into_boxed_slice();
+        let bytes = to_bytes(&timestamps);
+        let expected = IgniteValue::ArrTimestamp(vec![IgniteValue::Timestamp(1, 2)]);
+        assert_eq!(
+            IgniteValue::read(&mut Cursor::new(&bytes)).unwrap(),
+            Some(expected)
+        );
+        assert_eq!(
+            Box::<[Timestamp]>::read(&mut Cursor::new(&bytes)).unwrap(),
+            Some(timestamps)
+        );
+    }
+
+    #[test]
+    fn test_type_code_mismatch() {
+        let timestamp = to_bytes(&Timestamp {
+            millis: 1,
+            nanos: 2,
+        });
+        match Date::read(&mut Cursor::new(&timestamp)) {
+            Err(IgniteError::Protocol(msg)) => {
+                assert_eq!(msg, "Cannot read TypeCode 33 as Date")
+            }
+            other => panic!("Protocol error expected: {:?}", other),
+        }
+        assert!(Time::read(&mut Cursor::new(&timestamp)).is_err());
+        assert!(Uuid::read(&mut Cursor::new(&timestamp)).is_err());
+
+        let date = to_bytes(&Date { millis: 1 });
+        assert!(Timestamp::read(&mut Cursor::new(&date)).is_err());
+        assert_eq!(
+            Date::read(&mut Cursor::new(&date)).unwrap(),
+            Some(Date { millis: 1 })
+        );
+        assert_eq!(Time::read(&mut Cursor::new(&[101u8])).unwrap(), None);
+
+        let double = to_bytes(&1.5f64);
+        assert!(Decimal::read(&mut Cursor::new(&double)).is_err());
+        let decimal: Decimal = "1.5".parse().unwrap();
+        let bytes = to_bytes(&decimal);
+        assert_eq!(
+            Decimal::read(&mut Cursor::new(&bytes)).unwrap(),
+            Some(decimal)
+        );
+
+        // Java's long field isn't read into an int, nor an int into a string
+        let long = to_bytes(&(1i64 << 40));
+        match i32::read(&mut Cursor::new(&long)) {
+            Err(IgniteError::Protocol(msg)) => assert_eq!(msg, "Cannot read TypeCode 4 as i32"),
+            other => panic!("Protocol error expected: {:?}", other),
+        }
+        assert!(String::read(&mut Cursor::new(to_bytes(&1i32))).is_err());
+        assert!(bool::read(&mut Cursor::new(to_bytes(&1u8))).is_err());
+        assert!(Vec::<i32>::read(&mut Cursor::new(to_bytes(&vec![1i64]))).is_err());
+        assert_eq!(i64::read(&mut Cursor::new(&long)).unwrap(), Some(1 << 40));
+    }
+
+    #[test]
+    fn test_decode_limits() {
+        let limits = DecodeLimits {
+            message: 1024,
+            string: 3,
+            array: 2,
+            object: 64,
+            depth: 2,
+        };
+
+        let bytes = to_bytes(&"abcd".to_owned());
+        let err = with_decode_limits(limits, || String::read(&mut Cursor::new(&bytes)));
+        match err {
+            Err(IgniteError::Protocol(msg)) => {
+                assert_eq!(msg, "String of size 4 exceeds the limit of 3!")
+            }
+            other => panic!("Protocol error expected: {:?}", other),
+        }
+        let bytes = to_bytes(&"abc".to_owned());
+        let read = with_decode_limits(limits, || String::read(&mut Cursor::new(&bytes)));
+        assert_eq!(read.unwrap(), Some("abc".to_owned()));
+
+        let bytes = to_bytes(&vec![1i32, 2, 3]);
+        assert!(with_decode_limits(limits, || Vec::<i32>::read(&mut Cursor::new(&bytes))).is_err());
+        let bytes = to_bytes(&vec!["a".to_owned(), "b".to_owned(), "c".to_owned()]);
+        assert!(
+            with_decode_limits(limits, || Vec::<String>::read(&mut Cursor::new(&bytes))).is_err()
+        );
+
+        // header of a 1 GiB object. Fails before the body is read
+        let mut bytes = Vec::new();
+        write_u8(&mut bytes, TypeCode::ComplexObj as u8).unwrap();
+        write_u8(&mut bytes, 1).unwrap();
+        write_u16(&mut bytes, 0x0003).unwrap();
+        for value in &[1, 0, 1 << 30, 0, 24] {
+            write_i32(&mut bytes, *value).unwrap();
+        }
+        let err = with_decode_limits(limits, || ComplexObject::read(&mut Cursor::new(&bytes)));
+        assert!(err
+            .unwrap_err()
+            .to_string()
+            .contains("exceeds the limit of 64"));
+
+        // collection in a collection exceeds the depth of 2
+        let bytes = to_bytes(&vec![vec![vec![1i32]]]);
+        let err = with_decode_limits(limits, || IgniteValue::read(&mut Cursor::new(&bytes)));
+        assert!(err.unwrap_err().to_string().contains("Nesting depth"));
+
+        // limits are restored
+        assert_eq!(
+            Vec::<i32>::read(&mut Cursor::new(to_bytes(&vec![1i32, 2, 3]))).unwrap(),
+            Some(vec![1, 2, 3])
+        );
+    }
 }
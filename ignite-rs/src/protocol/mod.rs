@@ -1,14 +1,77 @@
+use std::cell::Cell;
 use std::io;
 use std::io::{ErrorKind, Read, Write};
 
 use crate::error::{IgniteError, IgniteResult};
 
-use crate::{Enum, ReadableType};
+use crate::utils::string_to_java_hashcode;
+use crate::{Date, Decimal, Enum, ReadableType, Time, Timestamp, Uuid};
 use std::convert::TryFrom;
 
+/// Implements WritableType and ReadableType for a third-party type
+/// by converting it from/into one of the crate's own types
+#[cfg(any(feature = "chrono", feature = "time", feature = "rust_decimal"))]
+macro_rules! converted_type {
+    ($t:ty, $ignite_t:ty) => {
+        impl crate::WritableType for $t {
+            fn write(&self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
+                crate::WritableType::write(&<$ignite_t>::from(*self), writer)
+            }
+
+            fn size(&self) -> usize {
+                crate::WritableType::size(&<$ignite_t>::from(*self))
+            }
+        }
+
+        impl crate::CollectionItem for $t {}
+
+        impl crate::JavaType for $t {
+            const JAVA_TYPE_NAME: &'static str = <$ignite_t as crate::JavaType>::JAVA_TYPE_NAME;
+        }
+
+        impl crate::HashCode for $t {
+            fn hash_code(&self) -> i32 {
+                crate::HashCode::hash_code(&<$ignite_t>::from(*self))
+            }
+        }
+
+        impl crate::ArrayItem for $t {
+            const ARRAY_TYPE_CODE: crate::protocol::TypeCode =
+                <$ignite_t as crate::ArrayItem>::ARRAY_TYPE_CODE;
+        }
+
+        impl crate::ReadableType for $t {
+            fn read_unwrapped(
+                type_code: crate::protocol::TypeCode,
+                reader: &mut impl std::io::Read,
+            ) -> crate::error::IgniteResult<Option<Self>> {
+                match <$ignite_t as crate::ReadableType>::read_unwrapped(type_code, reader)? {
+                    None => Ok(None),
+                    Some(value) => {
+                        <$t as std::convert::TryFrom<$ignite_t>>::try_from(value).map(Some)
+                    }
+                }
+            }
+        }
+    };
+}
+
+#[cfg(feature = "bigdecimal")]
+mod bigdecimal_types;
+pub mod binary_type;
 pub(crate) mod cache_config;
+#[cfg(feature = "chrono")]
+mod chrono_types;
 pub mod complex_obj;
 pub(crate) mod data_types;
+#[cfg(feature = "rust_decimal")]
+mod rust_decimal_types;
+#[cfg(feature = "time")]
+mod time_types;
+
+/// Number of milliseconds in one day
+#[cfg(any(feature = "chrono", feature = "time"))]
+const MILLIS_PER_DAY: i64 = 86_400_000;
 
 pub const FLAG_USER_TYPE: u16 = 0x0001;
 pub const FLAG_HAS_SCHEMA: u16 = 0x0002;
@@ -19,9 +82,151 @@ pub const FLAG_OFFSET_TWO_BYTES: u16 = 0x0010;
 
 pub const COMPLEX_OBJ_HEADER_LEN: i32 = 24;
 
+/// Type id of the types which are not registered in the cluster
+pub const UNREGISTERED_TYPE_ID: i32 = 0;
+
+/// Upper bound of items pre-allocated for a length read from the stream
+const MAX_PREALLOCATED_ITEMS: usize = 1024;
+
+/// Default upper bound of a message read from the server
+pub const DFLT_MAX_MESSAGE_SIZE: usize = 256 * 1024 * 1024;
+
+/// Default upper bound of nested collections, maps and objects read from the server
+pub const DFLT_MAX_NESTING_DEPTH: usize = 128;
+
+/// Upper bounds of the sizes read from the wire.
+/// Sizes above the limits fail the decoding before anything is allocated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct DecodeLimits {
+    /// Bytes in the whole message
+    pub message: usize,
+    /// Bytes in a string
+    pub string: usize,
+    /// Items in an array, a collection or a map
+    pub array: usize,
+    /// Bytes in a complex object including its header
+    pub object: usize,
+    /// Levels of values nested in one another
+    pub depth: usize,
+}
+
+impl DecodeLimits {
+    /// Limits for the values of a message which has `len` bytes.
+    /// No value could be larger than the message itself
+    pub(crate) fn within_message(&self, len: usize) -> DecodeLimits {
+        DecodeLimits {
+            message: len,
+            string: self.string.min(len),
+            array: self.array.min(len),
+            object: self.object.min(len),
+            depth: self.depth,
+        }
+    }
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        DecodeLimits {
+            message: DFLT_MAX_MESSAGE_SIZE,
+            string: DFLT_MAX_MESSAGE_SIZE,
+            array: DFLT_MAX_MESSAGE_SIZE,
+            object: DFLT_MAX_MESSAGE_SIZE,
+            depth: DFLT_MAX_NESTING_DEPTH,
+        }
+    }
+}
+
+thread_local! {
+    static DECODE_LIMITS: Cell<DecodeLimits> = Cell::new(DecodeLimits::default());
+    // levels of the values being read
+    static DECODE_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Runs the decoding function with the limits applied to all the values it reads
+pub(crate) fn with_decode_limits<T>(limits: DecodeLimits, f: impl FnOnce() -> T) -> T {
+    // restores the previous limits even if the function panics
+    struct Restore(DecodeLimits);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            DECODE_LIMITS.with(|limits| limits.set(self.0));
+        }
+    }
+
+    let _restore = Restore(DECODE_LIMITS.with(|current| current.replace(limits)));
+    f()
+}
+
+fn decode_limits() -> DecodeLimits {
+    DECODE_LIMITS.with(|limits| limits.get())
+}
+
+/// Fails if the size read from the wire exceeds the limit
+fn check_size(what: &str, size: usize, limit: usize) -> IgniteResult<usize> {
+    if size > limit {
+        return Err(IgniteError::Protocol(format!(
+            "{} of size {} exceeds the limit of {}!",
+            what, size, limit
+        )));
+    }
+    Ok(size)
+}
+
+/// Nesting level of the value being read. Leaves the level on drop
+pub(crate) struct Nested(());
+
+impl Drop for Nested {
+    fn drop(&mut self) {
+        DECODE_DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+/// Enters the next nesting level. Fails if it exceeds the limit,
+/// so deeply nested values can't overflow the stack
+pub(crate) fn enter_nested() -> IgniteResult<Nested> {
+    let level = DECODE_DEPTH.with(|depth| depth.get()) + 1;
+    let limit = decode_limits().depth;
+    if level > limit {
+        return Err(IgniteError::Protocol(format!(
+            "Nesting depth exceeds the limit of {}!",
+            limit
+        )));
+    }
+    DECODE_DEPTH.with(|depth| depth.set(level));
+    Ok(Nested(()))
+}
+
+/// Fails if the complex object's length exceeds the limit
+pub(crate) fn check_object_size(len: usize) -> IgniteResult<usize> {
+    check_size("Complex object", len, decode_limits().object)
+}
+
+/// Reads the length-prefixed message. The length is checked against the limit
+/// before the message is read
+pub(crate) fn read_message(reader: &mut impl Read, limits: &DecodeLimits) -> IgniteResult<Vec<u8>> {
+    let len = read_non_negative(reader)?;
+    let len = check_size("Message", len, limits.message)?;
+    // the connection is closed in the middle of the message
+    read_bytes(reader, len)?
+        .ok_or_else(|| IgniteError::from(io::Error::from(ErrorKind::UnexpectedEof)))
+}
+
+/// Map kinds. Java's map implementation the Map type (25) is deserialized into
+pub const MAP_KIND_HASH_MAP: i8 = 1;
+pub const MAP_KIND_LINKED_HASH_MAP: i8 = 2;
+
+/// Collection kinds. Java's collection implementation the Collection type (24) is deserialized into
+pub const COLLECTION_KIND_USER_SET: i8 = -1;
+pub const COLLECTION_KIND_USER_COL: i8 = 0;
+pub const COLLECTION_KIND_ARRAY_LIST: i8 = 1;
+pub const COLLECTION_KIND_LINKED_LIST: i8 = 2;
+pub const COLLECTION_KIND_HASH_SET: i8 = 3;
+pub const COLLECTION_KIND_LINKED_HASH_SET: i8 = 4;
+pub const COLLECTION_KIND_SINGLETON_LIST: i8 = 5;
+
 /// All Data types described in Binary Protocol
 /// https://apacheignite.readme.io/docs/binary-client-protocol-data-format
-#[derive(PartialOrd, PartialEq, Debug)]
+#[derive(PartialOrd, PartialEq, Debug, Clone, Copy)]
 pub enum TypeCode {
     Byte = 1,
     Short = 2,
@@ -32,6 +237,8 @@ pub enum TypeCode {
     Char = 7,
     Bool = 8,
     String = 9,
+    Uuid = 10,
+    Date = 11,
     ArrByte = 12,
     ArrShort = 13,
     ArrInt = 14,
@@ -40,13 +247,22 @@ pub enum TypeCode {
     ArrDouble = 17,
     ArrChar = 18,
     ArrBool = 19,
+    ArrString = 20,
+    ArrUuid = 21,
+    ArrDate = 22,
     ArrObj = 23,
     Collection = 24,
+    Map = 25,
+    ArrEnum = 29,
     Decimal = 30,
+    ArrDecimal = 31,
     Timestamp = 33,
     WrappedData = 27,
     Enum = 28,
     TimestampArray = 34,
+    Time = 36,
+    ArrTime = 37,
+    BinaryEnum = 38,
     Null = 101,
     ComplexObj = 103,
 }
@@ -65,7 +281,11 @@ impl TryFrom<u8> for TypeCode {
             7 => Ok(TypeCode::Char),
             8 => Ok(TypeCode::Bool),
             9 => Ok(TypeCode::String),
+            10 => Ok(TypeCode::Uuid),
+            11 => Ok(TypeCode::Date),
             28 => Ok(TypeCode::Enum),
+            29 => Ok(TypeCode::ArrEnum),
+            38 => Ok(TypeCode::BinaryEnum),
             12 => Ok(TypeCode::ArrByte),
             13 => Ok(TypeCode::ArrShort),
             14 => Ok(TypeCode::ArrInt),
@@ -74,16 +294,25 @@ impl TryFrom<u8> for TypeCode {
             17 => Ok(TypeCode::ArrDouble),
             18 => Ok(TypeCode::ArrChar),
             19 => Ok(TypeCode::ArrBool),
+            20 => Ok(TypeCode::ArrString),
+            21 => Ok(TypeCode::ArrUuid),
+            22 => Ok(TypeCode::ArrDate),
             23 => Ok(TypeCode::ArrObj),
             30 => Ok(TypeCode::Decimal),
+            31 => Ok(TypeCode::ArrDecimal),
             33 => Ok(TypeCode::Timestamp),
+            34 => Ok(TypeCode::TimestampArray),
+            36 => Ok(TypeCode::Time),
+            37 => Ok(TypeCode::ArrTime),
             24 => Ok(TypeCode::Collection),
+            25 => Ok(TypeCode::Map),
             27 => Ok(TypeCode::WrappedData),
             103 => Ok(TypeCode::ComplexObj),
             101 => Ok(TypeCode::Null),
-            _ => Err(IgniteError::from(
-                format!("Cannot read TypeCode {}", value).as_str(),
-            )),
+            _ => Err(IgniteError::UnsupportedType(format!(
+                "Cannot read TypeCode {}",
+                value
+            ))),
         }
     }
 }
@@ -91,7 +320,7 @@ impl TryFrom<u8> for TypeCode {
 /// Flag of general Response header
 pub(crate) enum Flag {
     Success,
-    Failure { err_msg: String },
+    Failure { status: i32, err_msg: String },
 }
 
 fn read_object(reader: &mut impl Read) -> IgniteResult<Option<()>> {
@@ -100,9 +329,10 @@ fn read_object(reader: &mut impl Read) -> IgniteResult<Option<()>> {
     let code = code?;
     match code {
         TypeCode::Null => Ok(Some(())),
-        _ => Err(IgniteError::from(
-            format!("Cannot read TypeCode {}", flag).as_str(),
-        )),
+        _ => Err(IgniteError::Protocol(format!(
+            "Cannot read TypeCode {}",
+            flag
+        ))),
     }
 }
 
@@ -131,7 +361,7 @@ pub(crate) fn write_string_type_code(writer: &mut dyn Write, value: &str) -> io:
     Ok(())
 }
 
-//// Read functions. No TypeCode, no NULL checking
+// Read functions. No TypeCode, no NULL checking
 
 pub fn write_string(writer: &mut dyn Write, value: &str) -> io::Result<()> {
     let value_bytes = value.as_bytes();
@@ -140,17 +370,15 @@ pub fn write_string(writer: &mut dyn Write, value: &str) -> io::Result<()> {
     Ok(())
 }
 
-pub fn read_string(reader: &mut impl Read) -> io::Result<String> {
-    let str_len = read_i32(reader)?;
+pub fn read_string(reader: &mut impl Read) -> IgniteResult<String> {
+    let str_len = read_string_len(reader)?;
+    let bytes = read_value_bytes(reader, str_len)?;
+    String::from_utf8(bytes).map_err(|err| IgniteError::Protocol(err.to_string()))
+}
 
-    let mut new_alloc = vec![0u8; str_len as usize];
-    match reader.read_exact(new_alloc.as_mut_slice()) {
-        Ok(_) => match String::from_utf8(new_alloc) {
-            Ok(s) => Ok(s),
-            Err(err) => Err(io::Error::new(ErrorKind::InvalidData, err)),
-        },
-        Err(err) => Err(err),
-    }
+/// Reads String (with its type code), which must not be NULL
+pub(crate) fn read_string_not_null(reader: &mut impl Read) -> IgniteResult<String> {
+    String::read(reader)?.ok_or_else(|| IgniteError::Protocol("NULL is not expected".to_owned()))
 }
 
 pub fn read_bool(reader: &mut impl Read) -> io::Result<bool> {
@@ -299,21 +527,64 @@ pub fn write_f64(writer: &mut dyn Write, v: f64) -> io::Result<()> {
     Ok(())
 }
 
-pub fn read_primitive_arr<T, R, F>(reader: &mut R, read_fn: F) -> io::Result<Vec<T>>
+/// Reads length of an array, a collection or a map.
+/// Negative length or length above the decoding limit is a protocol error
+pub fn read_len(reader: &mut impl Read) -> IgniteResult<usize> {
+    let len = read_non_negative(reader)?;
+    check_size("Array", len, decode_limits().array)
+}
+
+/// Reads length of a string. Length above the decoding limit is a protocol error
+pub(crate) fn read_string_len(reader: &mut impl Read) -> IgniteResult<usize> {
+    let len = read_non_negative(reader)?;
+    check_size("String", len, decode_limits().string)
+}
+
+fn read_non_negative(reader: &mut impl Read) -> IgniteResult<usize> {
+    let len = read_i32(reader)?;
+    usize::try_from(len).map_err(|_| IgniteError::Protocol(format!("Negative length {}!", len)))
+}
+
+/// Capacity to pre-allocate for the items count read from the stream.
+/// Corrupted count doesn't allocate more than MAX_PREALLOCATED_ITEMS,
+/// the rest is allocated as the items are actually read
+pub(crate) fn prealloc_capacity(len: usize) -> usize {
+    len.min(MAX_PREALLOCATED_ITEMS)
+}
+
+/// Reads exactly `len` bytes. None if the reader ends before that
+fn read_bytes(reader: &mut impl Read, len: usize) -> io::Result<Option<Vec<u8>>> {
+    let mut bytes = Vec::with_capacity(prealloc_capacity(len));
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+    Ok(Some(bytes).filter(|bytes| bytes.len() == len))
+}
+
+/// Reads exactly `len` bytes of a value. Value cut short is a protocol error
+fn read_value_bytes(reader: &mut impl Read, len: usize) -> IgniteResult<Vec<u8>> {
+    read_bytes(reader, len)?
+        .ok_or_else(|| IgniteError::Protocol(format!("Value of {} bytes is truncated!", len)))
+}
+
+pub fn read_primitive_arr<T, R, F>(reader: &mut R, read_fn: F) -> IgniteResult<Vec<T>>
 where
     R: Read,
     F: Fn(&mut R) -> io::Result<T>,
 {
-    let len = read_i32(reader)?;
-    let mut payload: Vec<T> = Vec::with_capacity(len as usize);
+    let len = read_len(reader)?;
+    let mut payload: Vec<T> = Vec::with_capacity(prealloc_capacity(len));
     for _ in 0..len {
         payload.push(read_fn(reader)?);
     }
     Ok(payload)
 }
 
-pub fn read_enum(reader: &mut impl Read) -> io::Result<Enum> {
-    let type_id = read_i32(reader)?;
+pub fn read_enum(reader: &mut impl Read) -> IgniteResult<Enum> {
+    let mut type_id = read_i32(reader)?;
+    if type_id == UNREGISTERED_TYPE_ID {
+        // type is not registered, so its class name is written instead
+        let class_name = read_string_not_null(reader)?;
+        type_id = string_to_java_hashcode(&class_name.to_lowercase());
+    }
     let ordinal = read_i32(reader)?;
     Ok(Enum { type_id, ordinal })
 }
@@ -324,6 +595,63 @@ pub fn write_enum(writer: &mut dyn Write, val: Enum) -> io::Result<()> {
     Ok(())
 }
 
+pub fn read_uuid(reader: &mut impl Read) -> io::Result<Uuid> {
+    let most_sig_bits = read_u64(reader)?;
+    let least_sig_bits = read_u64(reader)?;
+    Ok(Uuid::new(most_sig_bits, least_sig_bits))
+}
+
+pub fn write_uuid(writer: &mut dyn Write, val: Uuid) -> io::Result<()> {
+    write_u64(writer, val.most_sig_bits)?;
+    write_u64(writer, val.least_sig_bits)?;
+    Ok(())
+}
+
+pub fn read_date(reader: &mut impl Read) -> io::Result<Date> {
+    let millis = read_i64(reader)?;
+    Ok(Date { millis })
+}
+
+pub fn write_date(writer: &mut dyn Write, val: Date) -> io::Result<()> {
+    write_i64(writer, val.millis)
+}
+
+pub fn read_time(reader: &mut impl Read) -> io::Result<Time> {
+    let millis = read_i64(reader)?;
+    Ok(Time { millis })
+}
+
+pub fn write_time(writer: &mut dyn Write, val: Time) -> io::Result<()> {
+    write_i64(writer, val.millis)
+}
+
+pub fn read_timestamp(reader: &mut impl Read) -> io::Result<Timestamp> {
+    let millis = read_i64(reader)?;
+    let nanos = read_i32(reader)?;
+    Ok(Timestamp { millis, nanos })
+}
+
+pub fn write_timestamp(writer: &mut dyn Write, val: Timestamp) -> io::Result<()> {
+    write_i64(writer, val.millis)?;
+    write_i32(writer, val.nanos)?;
+    Ok(())
+}
+
+pub fn read_decimal(reader: &mut impl Read) -> IgniteResult<Decimal> {
+    let scale = read_i32(reader)?;
+    let len = read_len(reader)?;
+    let bytes = read_value_bytes(reader, len)?;
+    Ok(Decimal::from_java_bytes(scale, &bytes))
+}
+
+pub fn write_decimal(writer: &mut dyn Write, val: &Decimal) -> io::Result<()> {
+    let bytes = val.to_java_bytes();
+    write_i32(writer, val.scale())?;
+    write_i32(writer, bytes.len() as i32)?;
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
 pub fn write_null(writer: &mut dyn Write) -> io::Result<()> {
     write_u8(writer, TypeCode::Null as u8)?;
     Ok(())
@@ -0,0 +1,99 @@
+use std::convert::TryFrom;
+
+use crate::error::IgniteError;
+use crate::Decimal;
+
+// rust_decimal::Decimal <=> java.math.BigDecimal
+// rust_decimal supports up to 96 bits of magnitude and scale up to 28,
+// so reading a bigger value results in error
+
+impl From<rust_decimal::Decimal> for Decimal {
+    fn from(value: rust_decimal::Decimal) -> Self {
+        let mantissa = value.mantissa();
+        Decimal::new(
+            mantissa < 0,
+            mantissa.unsigned_abs().to_be_bytes().to_vec(),
+            value.scale() as i32,
+        )
+    }
+}
+
+impl TryFrom<Decimal> for rust_decimal::Decimal {
+    type Error = IgniteError;
+
+    fn try_from(value: Decimal) -> Result<Self, Self::Error> {
+        let out_of_range =
+            || IgniteError::InvalidValue("Decimal is out of range of rust_decimal".to_owned());
+
+        let magnitude = value.magnitude();
+        if magnitude.len() > 16 {
+            return Err(out_of_range());
+        }
+        let mut bytes = [0u8; 16];
+        bytes[16 - magnitude.len()..].copy_from_slice(magnitude);
+        let mut mantissa =
+            i128::try_from(u128::from_be_bytes(bytes)).map_err(|_| out_of_range())?;
+        if value.is_negative() {
+            mantissa = -mantissa;
+        }
+
+        // zero has no digits to shift, while the scale read from the wire could be i32::MIN
+        let mut scale = if mantissa == 0 {
+            value.scale().max(0)
+        } else {
+            value.scale()
+        };
+        while scale < 0 {
+            mantissa = mantissa.checked_mul(10).ok_or_else(out_of_range)?;
+            scale += 1;
+        }
+        rust_decimal::Decimal::try_from_i128_with_scale(mantissa, scale as u32)
+            .map_err(|_| out_of_range())
+    }
+}
+
+converted_type!(rust_decimal::Decimal, Decimal);
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+    use std::str::FromStr;
+
+    use crate::{Decimal, ReadableType, WritableType};
+
+    #[test]
+    fn test_rust_decimal() {
+        for str in &[
+            "0",
+            "2.0",
+            "-0.05",
+            "79228162514264337593543950335",
+            "-1.2345",
+        ] {
+            let value = rust_decimal::Decimal::from_str(str).unwrap();
+            assert_eq!(Decimal::from(value).to_string(), *str);
+
+            let mut buf: Vec<u8> = Vec::new();
+            value.write(&mut buf).unwrap();
+            assert_eq!(buf.len(), value.size());
+            let actual = rust_decimal::Decimal::read(&mut buf.as_slice()).unwrap();
+            assert_eq!(actual.unwrap().to_string(), *str);
+        }
+
+        let negative_scale: Decimal = "12E3".parse().unwrap();
+        assert_eq!(
+            rust_decimal::Decimal::try_from(negative_scale).unwrap(),
+            rust_decimal::Decimal::from(12000)
+        );
+
+        let too_big: Decimal = "79228162514264337593543950336".parse().unwrap();
+        assert!(rust_decimal::Decimal::try_from(too_big).is_err());
+        let huge_exponent = Decimal::new(true, vec![1], i32::MIN);
+        assert!(rust_decimal::Decimal::try_from(huge_exponent).is_err());
+        let zero = Decimal::new(false, vec![], i32::MIN);
+        assert_eq!(
+            rust_decimal::Decimal::try_from(zero).unwrap(),
+            rust_decimal::Decimal::ZERO
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use std::convert::TryFrom;
+
+use time::{OffsetDateTime, UtcOffset};
+
+use crate::error::IgniteError;
+use crate::protocol::MILLIS_PER_DAY;
+use crate::{Date, Time, Timestamp};
+
+// time::OffsetDateTime <=> java.sql.Timestamp (read as UTC)
+// time::Date <=> java.sql.Date (midnight UTC)
+// time::Time <=> java.sql.Time (millisecond precision)
+
+/// Julian day of 1 Jan 1970
+const UNIX_EPOCH_JULIAN_DAY: i64 = 2_440_588;
+
+impl From<OffsetDateTime> for Timestamp {
+    fn from(value: OffsetDateTime) -> Self {
+        let nanos = value.unix_timestamp_nanos();
+        Timestamp {
+            millis: nanos.div_euclid(1_000_000) as i64,
+            nanos: nanos.rem_euclid(1_000_000) as i32,
+        }
+    }
+}
+
+impl TryFrom<Timestamp> for OffsetDateTime {
+    type Error = IgniteError;
+
+    fn try_from(value: Timestamp) -> Result<Self, Self::Error> {
+        if !(0..1_000_000).contains(&value.nanos) {
+            return Err(IgniteError::InvalidValue(
+                "Timestamp nanos are out of range".to_owned(),
+            ));
+        }
+        let nanos = i128::from(value.millis) * 1_000_000 + i128::from(value.nanos);
+        OffsetDateTime::from_unix_timestamp_nanos(nanos)
+            .map(|date_time| date_time.to_offset(UtcOffset::UTC))
+            .map_err(|_| IgniteError::InvalidValue("Timestamp is out of range".to_owned()))
+    }
+}
+
+impl From<time::Date> for Date {
+    fn from(value: time::Date) -> Self {
+        let days = i64::from(value.to_julian_day()) - UNIX_EPOCH_JULIAN_DAY;
+        Date {
+            millis: days * MILLIS_PER_DAY,
+        }
+    }
+}
+
+impl TryFrom<Date> for time::Date {
+    type Error = IgniteError;
+
+    fn try_from(value: Date) -> Result<Self, Self::Error> {
+        let julian_day = value.millis.div_euclid(MILLIS_PER_DAY) + UNIX_EPOCH_JULIAN_DAY;
+        i32::try_from(julian_day)
+            .ok()
+            .and_then(|julian_day| time::Date::from_julian_day(julian_day).ok())
+            .ok_or_else(|| IgniteError::InvalidValue("Date is out of range".to_owned()))
+    }
+}
+
+impl From<time::Time> for Time {
+    fn from(value: time::Time) -> Self {
+        let (hour, minute, second, milli) = value.as_hms_milli();
+        let secs = i64::from(hour) * 3600 + i64::from(minute) * 60 + i64::from(second);
+        Time {
+            millis: secs * 1000 + i64::from(milli),
+        }
+    }
+}
+
+impl TryFrom<Time> for time::Time {
+    type Error = IgniteError;
+
+    fn try_from(value: Time) -> Result<Self, Self::Error> {
+        let millis = value.millis.rem_euclid(MILLIS_PER_DAY);
+        let secs = millis / 1000;
+        time::Time::from_hms_milli(
+            (secs / 3600) as u8,
+            (secs / 60 % 60) as u8,
+            (secs % 60) as u8,
+            (millis % 1000) as u16,
+        )
+        .map_err(|_| IgniteError::InvalidValue("Time is out of range".to_owned()))
+    }
+}
+
+converted_type!(OffsetDateTime, Timestamp);
+converted_type!(time::Date, Date);
+converted_type!(time::Time, Time);
+
+#[cfg(test)]
+mod tests {
+    use time::{Month, OffsetDateTime};
+
+    use crate::{Date, ReadableType, Time, Timestamp, WritableType};
+
+    fn round_trip<T: WritableType + ReadableType>(value: &T) -> T {
+        let mut buf: Vec<u8> = Vec::new();
+        value.write(&mut buf).unwrap();
+        assert_eq!(buf.len(), value.size());
+        T::read(&mut buf.as_slice()).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_offset_date_time() {
+        let value = OffsetDateTime::from_unix_timestamp_nanos(1_687_350_896_123_456_789).unwrap();
+        assert_eq!(
+            Timestamp::from(value),
+            Timestamp {
+                millis: 1_687_350_896_123,
+                nanos: 456_789
+            }
+        );
+        assert_eq!(round_trip(&value), value);
+
+        let before_epoch = OffsetDateTime::from_unix_timestamp_nanos(-1).unwrap();
+        assert_eq!(round_trip(&before_epoch), before_epoch);
+    }
+
+    #[test]
+    fn test_date() {
+        let value = time::Date::from_calendar_date(2023, Month::June, 21).unwrap();
+        assert_eq!(
+            Date::from(value),
+            Date {
+                millis: 1_687_305_600_000
+            }
+        );
+        assert_eq!(round_trip(&value), value);
+
+        let before_epoch = time::Date::from_calendar_date(1900, Month::January, 1).unwrap();
+        assert_eq!(round_trip(&before_epoch), before_epoch);
+    }
+
+    #[test]
+    fn test_time() {
+        let value = time::Time::from_hms_milli(12, 34, 56, 789).unwrap();
+        assert_eq!(Time::from(value), Time { millis: 45_296_789 });
+        assert_eq!(round_trip(&value), value);
+    }
+}
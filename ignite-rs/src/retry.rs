@@ -0,0 +1,120 @@
+//! Policies of retrying the operations failed because of the connection.
+//! Only idempotent operations are retried, so the retry can't apply a change twice.
+//! The connection is re-established before the next attempt
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use crate::error::IgniteError;
+
+/// Decides whether the failed operation should be attempted again
+pub trait RetryPolicy: Send + Sync {
+    /// Returns the delay before the next attempt or None if the operation should fail.
+    /// `attempt` is the number of the failed attempts, starting from 1
+    fn retry_delay(&self, attempt: u32, err: &IgniteError) -> Option<Duration>;
+}
+
+/// Never retries. The default policy
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NeverRetry;
+
+impl RetryPolicy for NeverRetry {
+    fn retry_delay(&self, _: u32, _: &IgniteError) -> Option<Duration> {
+        None
+    }
+}
+
+/// Retries up to `retries` times with the same delay
+#[derive(Debug, Clone, Copy)]
+pub struct FixedRetry {
+    pub retries: u32,
+    pub delay: Duration,
+}
+
+impl FixedRetry {
+    pub fn new(retries: u32, delay: Duration) -> FixedRetry {
+        FixedRetry { retries, delay }
+    }
+}
+
+impl RetryPolicy for FixedRetry {
+    fn retry_delay(&self, attempt: u32, _: &IgniteError) -> Option<Duration> {
+        if attempt > self.retries {
+            return None;
+        }
+        Some(self.delay)
+    }
+}
+
+/// Retries up to `retries` times. The delay is doubled after each attempt
+/// until it reaches `max_delay`. The actual delay is random between zero and the computed one,
+/// so the clients don't reconnect all at once
+#[derive(Debug, Clone, Copy)]
+pub struct ExponentialBackoff {
+    pub retries: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl ExponentialBackoff {
+    pub fn new(retries: u32, initial_delay: Duration, max_delay: Duration) -> ExponentialBackoff {
+        ExponentialBackoff {
+            retries,
+            initial_delay,
+            max_delay,
+        }
+    }
+
+    /// Delay before the attempt without jitter
+    fn max_delay_of(&self, attempt: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempt - 1).unwrap_or(u32::MAX);
+        self.initial_delay
+            .checked_mul(factor)
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay))
+    }
+}
+
+impl RetryPolicy for ExponentialBackoff {
+    fn retry_delay(&self, attempt: u32, _: &IgniteError) -> Option<Duration> {
+        if attempt == 0 || attempt > self.retries {
+            return None;
+        }
+        let max_delay = self.max_delay_of(attempt).as_nanos() as u64;
+        Some(Duration::from_nanos(random() % max_delay.saturating_add(1)))
+    }
+}
+
+/// Random number for the jitter. Every RandomState is seeded with random keys
+fn random() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Error, ErrorKind};
+
+    #[test]
+    fn test_policies() {
+        let err = IgniteError::from(Error::from(ErrorKind::ConnectionReset));
+        assert_eq!(NeverRetry.retry_delay(1, &err), None);
+
+        let fixed = FixedRetry::new(2, Duration::from_millis(10));
+        assert_eq!(fixed.retry_delay(1, &err), Some(Duration::from_millis(10)));
+        assert_eq!(fixed.retry_delay(2, &err), Some(Duration::from_millis(10)));
+        assert_eq!(fixed.retry_delay(3, &err), None);
+
+        let backoff =
+            ExponentialBackoff::new(40, Duration::from_millis(10), Duration::from_millis(100));
+        assert_eq!(backoff.max_delay_of(1), Duration::from_millis(10));
+        assert_eq!(backoff.max_delay_of(3), Duration::from_millis(40));
+        assert_eq!(backoff.max_delay_of(5), Duration::from_millis(100));
+        assert_eq!(backoff.max_delay_of(40), Duration::from_millis(100));
+        for attempt in 1..=40 {
+            let delay = backoff.retry_delay(attempt, &err).unwrap();
+            assert!(delay <= backoff.max_delay_of(attempt));
+        }
+        assert_eq!(backoff.retry_delay(41, &err), None);
+    }
+}
@@ -0,0 +1,546 @@
+use std::convert::TryFrom;
+use std::io::Read;
+
+use serde::de::value::SeqDeserializer;
+use serde::de::{self, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+
+use crate::error::{IgniteError, IgniteResult};
+use crate::protocol::binary_type::BinaryObjectFields;
+use crate::protocol::{
+    enter_nested, read_bool, read_date, read_decimal, read_enum, read_f32, read_f64, read_i16,
+    read_i32, read_i64, read_i8, read_len, read_primitive_arr, read_string, read_time,
+    read_timestamp, read_u16, read_u8, read_uuid, TypeCode,
+};
+use crate::serde_binary::{
+    field_id, DATE_NAME, DECIMAL_NAME, TIMESTAMP_NAME, TIME_NAME, UUID_NAME,
+};
+use crate::utils::{get_schema_id_by_field_ids, string_to_java_hashcode};
+
+/// Deserializes values from the Ignite binary format.
+///
+/// Accepts everything the `Serializer` writes. Besides that, arrays of any kind are read
+/// as sequences, Enum and String values are read as unit variants,
+/// and UUID, Decimal, Date, Time and Timestamp could be read into strings and integers.
+/// Fields of complex objects are located by the footer, so their order doesn't matter
+pub struct Deserializer<R> {
+    reader: R,
+    type_code: Option<TypeCode>, // already read type code
+}
+
+impl<R: Read> Deserializer<R> {
+    pub fn new(reader: R) -> Deserializer<R> {
+        Deserializer {
+            reader,
+            type_code: None,
+        }
+    }
+
+    /// Creates deserializer of the value which type code is already read
+    pub fn with_type_code(reader: R, type_code: TypeCode) -> Deserializer<R> {
+        Deserializer {
+            reader,
+            type_code: Some(type_code),
+        }
+    }
+
+    fn type_code(&mut self) -> IgniteResult<TypeCode> {
+        match self.type_code.take() {
+            Some(type_code) => Ok(type_code),
+            None => TypeCode::try_from(read_u8(&mut self.reader)?),
+        }
+    }
+
+    fn read_len(&mut self) -> IgniteResult<usize> {
+        read_len(&mut self.reader)
+    }
+
+    /// Visits items of an array or a collection which type code is read
+    fn visit_items<'de, V: Visitor<'de>>(
+        &mut self,
+        item_code: Option<TypeCode>,
+        visitor: V,
+    ) -> IgniteResult<V::Value> {
+        let len = self.read_len()?;
+        let mut items = Items {
+            de: self,
+            remaining: len,
+            item_code,
+        };
+        let value = visitor.visit_seq(&mut items)?;
+        items.end()?;
+        Ok(value)
+    }
+
+    /// Visits fields of the complex object which type code is read.
+    /// Type id is checked only if the type name is given
+    fn visit_object<'de, V: Visitor<'de>>(
+        &mut self,
+        name: Option<&str>,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> IgniteResult<V::Value> {
+        let field_ids: Vec<i32> = fields.iter().map(|name| field_id(name)).collect();
+        let obj = BinaryObjectFields::read(
+            &mut self.reader,
+            get_schema_id_by_field_ids(&field_ids),
+            &field_ids,
+        )?;
+        if let Some(name) = name {
+            let exp_type_id = string_to_java_hashcode(&name.to_lowercase());
+            if obj.type_id() != exp_type_id {
+                return Err(IgniteError::Protocol(format!(
+                    "Unknown type id! {} expected!",
+                    exp_type_id
+                )));
+            }
+        }
+        visitor.visit_map(ObjectFields {
+            obj: &obj,
+            fields: fields
+                .iter()
+                .copied()
+                .zip(field_ids)
+                .collect::<Vec<_>>()
+                .into_iter(),
+            value: None,
+        })
+    }
+}
+
+impl<'de, R: Read> de::Deserializer<'de> for &mut Deserializer<R> {
+    type Error = IgniteError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> IgniteResult<V::Value> {
+        let _nested = enter_nested()?;
+        let type_code = self.type_code()?;
+        let reader = &mut self.reader;
+        match type_code {
+            TypeCode::Byte => visitor.visit_u8(read_u8(reader)?),
+            TypeCode::Short => visitor.visit_i16(read_i16(reader)?),
+            TypeCode::Int => visitor.visit_i32(read_i32(reader)?),
+            TypeCode::Long => visitor.visit_i64(read_i64(reader)?),
+            TypeCode::Float => visitor.visit_f32(read_f32(reader)?),
+            TypeCode::Double => visitor.visit_f64(read_f64(reader)?),
+            TypeCode::Char => {
+                let unit = read_u16(reader)?;
+                match char::from_u32(u32::from(unit)) {
+                    Some(ch) => visitor.visit_char(ch),
+                    None => visitor.visit_u16(unit), // lone surrogate
+                }
+            }
+            TypeCode::Bool => visitor.visit_bool(read_bool(reader)?),
+            TypeCode::String => visitor.visit_string(read_string(reader)?),
+            TypeCode::Uuid => visitor.visit_string(read_uuid(reader)?.to_string()),
+            TypeCode::Decimal => visitor.visit_string(read_decimal(reader)?.to_string()),
+            TypeCode::Date => visitor.visit_i64(read_date(reader)?.millis),
+            TypeCode::Time => visitor.visit_i64(read_time(reader)?.millis),
+            TypeCode::Timestamp => {
+                let timestamp = read_timestamp(reader)?;
+                let parts = vec![timestamp.millis, i64::from(timestamp.nanos)];
+                let mut seq: SeqDeserializer<_, IgniteError> =
+                    SeqDeserializer::new(parts.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            TypeCode::Enum | TypeCode::BinaryEnum => visitor.visit_i32(read_enum(reader)?.ordinal),
+            TypeCode::ArrByte => self.visit_items(Some(TypeCode::Byte), visitor),
+            TypeCode::ArrShort => self.visit_items(Some(TypeCode::Short), visitor),
+            TypeCode::ArrInt => self.visit_items(Some(TypeCode::Int), visitor),
+            TypeCode::ArrLong => self.visit_items(Some(TypeCode::Long), visitor),
+            TypeCode::ArrFloat => self.visit_items(Some(TypeCode::Float), visitor),
+            TypeCode::ArrDouble => self.visit_items(Some(TypeCode::Double), visitor),
+            TypeCode::ArrChar => self.visit_items(Some(TypeCode::Char), visitor),
+            TypeCode::ArrBool => self.visit_items(Some(TypeCode::Bool), visitor),
+            TypeCode::ArrString
+            | TypeCode::ArrUuid
+            | TypeCode::ArrDate
+            | TypeCode::ArrTime
+            | TypeCode::TimestampArray
+            | TypeCode::ArrDecimal => self.visit_items(None, visitor),
+            TypeCode::ArrObj | TypeCode::ArrEnum => {
+                read_i32(reader)?; // type id of items
+                self.visit_items(None, visitor)
+            }
+            TypeCode::Collection => {
+                let len = self.read_len()?;
+                read_i8(&mut self.reader)?; // kind
+                let mut items = Items {
+                    de: self,
+                    remaining: len,
+                    item_code: None,
+                };
+                let value = visitor.visit_seq(&mut items)?;
+                items.end()?;
+                Ok(value)
+            }
+            TypeCode::Map => {
+                let len = self.read_len()?;
+                read_i8(&mut self.reader)?; // kind
+                let mut entries = Items {
+                    de: self,
+                    remaining: len,
+                    item_code: None,
+                };
+                let value = visitor.visit_map(&mut entries)?;
+                entries.end()?;
+                Ok(value)
+            }
+            TypeCode::Null => visitor.visit_unit(),
+            // names of fields are unknown, so they are visited by their ids
+            TypeCode::ComplexObj => {
+                let obj = BinaryObjectFields::read(reader, 0, &[])?;
+                let field_ids = obj.field_ids();
+                visitor.visit_map(ObjectFields {
+                    obj: &obj,
+                    fields: field_ids
+                        .into_iter()
+                        .map(|id| (id, id))
+                        .collect::<Vec<_>>()
+                        .into_iter(),
+                    value: None,
+                })
+            }
+            TypeCode::WrappedData => Err(IgniteError::UnsupportedType(
+                "Wrapped data can't be read as a nested value!".to_owned(),
+            )),
+        }
+    }
+
+    // Byte is signed in Java, while the crate reads it as u8 by default
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> IgniteResult<V::Value> {
+        match self.type_code()? {
+            TypeCode::Byte => visitor.visit_i8(read_i8(&mut self.reader)?),
+            type_code => {
+                self.type_code = Some(type_code);
+                self.deserialize_any(visitor)
+            }
+        }
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> IgniteResult<V::Value> {
+        match self.type_code()? {
+            TypeCode::Char => visitor.visit_u16(read_u16(&mut self.reader)?),
+            type_code => {
+                self.type_code = Some(type_code);
+                self.deserialize_any(visitor)
+            }
+        }
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> IgniteResult<V::Value> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> IgniteResult<V::Value> {
+        match self.type_code()? {
+            TypeCode::ArrByte => {
+                visitor.visit_byte_buf(read_primitive_arr(&mut self.reader, read_u8)?)
+            }
+            type_code => {
+                self.type_code = Some(type_code);
+                self.deserialize_any(visitor)
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> IgniteResult<V::Value> {
+        match self.type_code()? {
+            TypeCode::Null => visitor.visit_none(),
+            type_code => {
+                self.type_code = Some(type_code);
+                visitor.visit_some(self)
+            }
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> IgniteResult<V::Value> {
+        match self.type_code()? {
+            TypeCode::ComplexObj => {
+                // fields are ignored, but the whole object is read anyway
+                self.visit_object(Some(name), &[], de::IgnoredAny)?;
+                visitor.visit_unit()
+            }
+            type_code => {
+                self.type_code = Some(type_code);
+                self.deserialize_any(visitor)
+            }
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> IgniteResult<V::Value> {
+        match name {
+            UUID_NAME | DATE_NAME | TIME_NAME | TIMESTAMP_NAME | DECIMAL_NAME => {
+                self.deserialize_any(visitor)
+            }
+            _ => visitor.visit_newtype_struct(self),
+        }
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> IgniteResult<V::Value> {
+        match self.type_code()? {
+            TypeCode::ComplexObj => {
+                let field_ids: Vec<i32> = (0..len).map(|i| field_id(&i.to_string())).collect();
+                let obj = BinaryObjectFields::read(
+                    &mut self.reader,
+                    get_schema_id_by_field_ids(&field_ids),
+                    &field_ids,
+                )?;
+                let exp_type_id = string_to_java_hashcode(&name.to_lowercase());
+                if obj.type_id() != exp_type_id {
+                    return Err(IgniteError::Protocol(format!(
+                        "Unknown type id! {} expected!",
+                        exp_type_id
+                    )));
+                }
+                let mut seq = PositionalFields {
+                    obj: &obj,
+                    field_ids: field_ids.into_iter(),
+                };
+                let value = visitor.visit_seq(&mut seq)?;
+                match seq.field_ids.next() {
+                    None => Ok(value),
+                    Some(_) => Err(IgniteError::Protocol(
+                        "Not all fields of the tuple are read!".to_owned(),
+                    )),
+                }
+            }
+            type_code => {
+                self.type_code = Some(type_code);
+                self.deserialize_any(visitor)
+            }
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> IgniteResult<V::Value> {
+        match self.type_code()? {
+            TypeCode::ComplexObj => self.visit_object(Some(name), fields, visitor),
+            type_code => {
+                self.type_code = Some(type_code);
+                self.deserialize_any(visitor)
+            }
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> IgniteResult<V::Value> {
+        match self.type_code()? {
+            TypeCode::Enum | TypeCode::BinaryEnum => {
+                let ordinal = read_enum(&mut self.reader)?.ordinal;
+                let index = u32::try_from(ordinal)
+                    .map_err(|_| IgniteError::Protocol("Negative enum ordinal!".to_owned()))?;
+                visitor.visit_enum(IntoDeserializer::<IgniteError>::into_deserializer(index))
+            }
+            TypeCode::String => {
+                let variant = read_string(&mut self.reader)?;
+                visitor.visit_enum(IntoDeserializer::<IgniteError>::into_deserializer(variant))
+            }
+            // variant with data is a single entry map
+            TypeCode::Map => {
+                let len = self.read_len()?;
+                read_i8(&mut self.reader)?; // kind
+                if len != 1 {
+                    return Err(IgniteError::Protocol(
+                        "Enum variant with data must be a map with a single entry!".to_owned(),
+                    ));
+                }
+                visitor.visit_enum(self)
+            }
+            _ => Err(IgniteError::Protocol(
+                "Expected Enum, String or Map!".to_owned(),
+            )),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> IgniteResult<V::Value> {
+        self.deserialize_any(de::IgnoredAny)?;
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i16 i32 i64 i128 u8 u32 u64 u128 f32 f64 char str string
+        unit seq tuple map identifier
+    }
+}
+
+impl<'de, R: Read> de::EnumAccess<'de> for &mut Deserializer<R> {
+    type Error = IgniteError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> IgniteResult<(V::Value, Self)> {
+        let variant = seed.deserialize(&mut *self)?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, R: Read> de::VariantAccess<'de> for &mut Deserializer<R> {
+    type Error = IgniteError;
+
+    fn unit_variant(self) -> IgniteResult<()> {
+        de::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> IgniteResult<T::Value> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> IgniteResult<V::Value> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    // the object of a struct variant is named after the variant, so type id isn't checked
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> IgniteResult<V::Value> {
+        match self.type_code()? {
+            TypeCode::ComplexObj => self.visit_object(None, fields, visitor),
+            type_code => {
+                self.type_code = Some(type_code);
+                de::Deserializer::deserialize_any(self, visitor)
+            }
+        }
+    }
+}
+
+/// Items of arrays and collections, and entries of maps
+struct Items<'a, R> {
+    de: &'a mut Deserializer<R>,
+    remaining: usize,
+    item_code: Option<TypeCode>, // items of primitive arrays have no type codes
+}
+
+impl<R> Items<'_, R> {
+    fn end(&self) -> IgniteResult<()> {
+        match self.remaining {
+            0 => Ok(()),
+            _ => Err(IgniteError::Protocol("Not all items are read!".to_owned())),
+        }
+    }
+}
+
+impl<'de, R: Read> de::SeqAccess<'de> for Items<'_, R> {
+    type Error = IgniteError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> IgniteResult<Option<T::Value>> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        self.de.type_code = self.item_code;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de, R: Read> de::MapAccess<'de> for Items<'_, R> {
+    type Error = IgniteError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> IgniteResult<Option<K::Value>> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> IgniteResult<V::Value> {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+/// Present fields of a complex object. Keys are either names or ids of the fields
+struct ObjectFields<'a, K> {
+    obj: &'a BinaryObjectFields,
+    fields: std::vec::IntoIter<(K, i32)>,
+    value: Option<&'a [u8]>, // bytes of the field which key is visited
+}
+
+impl<'de, K> de::MapAccess<'de> for ObjectFields<'_, K>
+where
+    K: IntoDeserializer<'de, IgniteError>,
+{
+    type Error = IgniteError;
+
+    fn next_key_seed<S: DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> IgniteResult<Option<S::Value>> {
+        for (key, field_id) in self.fields.by_ref() {
+            if let Some(bytes) = self.obj.field_bytes(field_id) {
+                self.value = Some(bytes);
+                return seed.deserialize(key.into_deserializer()).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    fn next_value_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> IgniteResult<S::Value> {
+        let bytes = self.value.take().ok_or_else(|| {
+            IgniteError::Protocol("Field value is requested before its key!".to_owned())
+        })?;
+        seed.deserialize(&mut Deserializer::new(bytes))
+    }
+}
+
+/// Fields of a complex object written from a tuple struct
+struct PositionalFields<'a> {
+    obj: &'a BinaryObjectFields,
+    field_ids: std::vec::IntoIter<i32>,
+}
+
+impl<'de> de::SeqAccess<'de> for PositionalFields<'_> {
+    type Error = IgniteError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> IgniteResult<Option<T::Value>> {
+        match self.field_ids.next() {
+            None => Ok(None),
+            Some(field_id) => {
+                let bytes = self.obj.field_bytes(field_id).ok_or_else(|| {
+                    IgniteError::Protocol("Field of the tuple is missing!".to_owned())
+                })?;
+                seed.deserialize(&mut Deserializer::new(bytes)).map(Some)
+            }
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! Serde support for the binary format. Enabled by the `serde` feature.
+//!
+//! Any type implementing `Serialize` and `Deserialize` could be used as a cache key or value
+//! by wrapping it into [`Serde`]:
+//! ```ignore
+//! let cache = ignite.get_or_create_cache::<Serde<String>, Serde<Person>>("people")?;
+//! cache.put(&Serde("id".to_owned()), &Serde(person))?;
+//! ```
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::io;
+use std::io::{Read, Write};
+
+use serde::de::{DeserializeOwned, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize};
+
+use crate::error::IgniteResult;
+use crate::protocol::TypeCode;
+use crate::utils::string_to_java_hashcode;
+use crate::{CollectionItem, Date, Decimal, ReadableType, Time, Timestamp, Uuid, WritableType};
+
+mod de;
+mod ser;
+
+pub use self::de::Deserializer;
+pub use self::ser::Serializer;
+
+// The crate's types are serialized as newtypes with these names,
+// so the serializer writes them as the corresponding Ignite types
+const UUID_NAME: &str = "$ignite_rs::Uuid";
+const DATE_NAME: &str = "$ignite_rs::Date";
+const TIME_NAME: &str = "$ignite_rs::Time";
+const TIMESTAMP_NAME: &str = "$ignite_rs::Timestamp";
+const DECIMAL_NAME: &str = "$ignite_rs::Decimal";
+
+/// Serializes the value into the binary format
+pub fn to_bytes<T: ?Sized + Serialize>(value: &T) -> IgniteResult<Vec<u8>> {
+    let mut serializer = Serializer::new();
+    value.serialize(&mut serializer)?;
+    Ok(serializer.into_inner())
+}
+
+/// Deserializes a value of the binary format
+pub fn from_reader<T: DeserializeOwned>(reader: &mut impl Read) -> IgniteResult<T> {
+    T::deserialize(&mut Deserializer::new(reader))
+}
+
+/// Id of the complex object's field
+fn field_id(name: &str) -> i32 {
+    string_to_java_hashcode(&name.to_lowercase())
+}
+
+/// Wrapper which makes any serde type a cache key or value.
+/// The value is written with the [`Serializer`] and read with the [`Deserializer`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Serde<T>(pub T);
+
+impl<T: Serialize> WritableType for Serde<T> {
+    fn write(&self, writer: &mut dyn Write) -> io::Result<()> {
+        let bytes = to_bytes(&self.0)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err.to_string()))?;
+        writer.write_all(&bytes)
+    }
+
+    fn size(&self) -> usize {
+        to_bytes(&self.0).map_or(0, |bytes| bytes.len())
+    }
+}
+
+impl<T: DeserializeOwned> ReadableType for Serde<T> {
+    fn read_unwrapped(type_code: TypeCode, reader: &mut impl Read) -> IgniteResult<Option<Self>> {
+        match type_code {
+            // NULL is a value only for types like Option or ()
+            TypeCode::Null => Ok(T::deserialize(&mut Deserializer::with_type_code(
+                io::empty(),
+                type_code,
+            ))
+            .ok()
+            .map(Serde)),
+            _ => T::deserialize(&mut Deserializer::with_type_code(reader, type_code))
+                .map(|value| Some(Serde(value))),
+        }
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> CollectionItem for Serde<T> {}
+
+macro_rules! string_type {
+    ($t:ty, $name:expr, $visitor:ident, $expecting:expr) => {
+        impl Serialize for $t {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_newtype_struct($name, &self.to_string())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $t {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_newtype_struct($name, $visitor)
+            }
+        }
+
+        struct $visitor;
+
+        impl<'de> Visitor<'de> for $visitor {
+            type Value = $t;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str($expecting)
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<$t, E> {
+                v.parse().map_err(E::custom)
+            }
+
+            fn visit_newtype_struct<D: serde::Deserializer<'de>>(
+                self,
+                deserializer: D,
+            ) -> Result<$t, D::Error> {
+                let value = String::deserialize(deserializer)?;
+                value.parse().map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+string_type!(Uuid, UUID_NAME, UuidVisitor, "UUID string");
+string_type!(Decimal, DECIMAL_NAME, DecimalVisitor, "decimal string");
+
+macro_rules! millis_type {
+    ($t:ident, $name:expr, $visitor:ident, $expecting:expr) => {
+        impl Serialize for $t {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_newtype_struct($name, &self.millis)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $t {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_newtype_struct($name, $visitor)
+            }
+        }
+
+        struct $visitor;
+
+        impl<'de> Visitor<'de> for $visitor {
+            type Value = $t;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str($expecting)
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, millis: i64) -> Result<$t, E> {
+                Ok($t { millis })
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, millis: u64) -> Result<$t, E> {
+                i64::try_from(millis)
+                    .map(|millis| $t { millis })
+                    .map_err(E::custom)
+            }
+
+            fn visit_newtype_struct<D: serde::Deserializer<'de>>(
+                self,
+                deserializer: D,
+            ) -> Result<$t, D::Error> {
+                let millis = i64::deserialize(deserializer)?;
+                Ok($t { millis })
+            }
+        }
+    };
+}
+
+millis_type!(Date, DATE_NAME, DateVisitor, "milliseconds since epoch");
+millis_type!(Time, TIME_NAME, TimeVisitor, "milliseconds since midnight");
+
+// Timestamp is a pair of milliseconds and nanoseconds
+impl Serialize for Timestamp {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(TIMESTAMP_NAME, &(self.millis, self.nanos))
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_newtype_struct(TIMESTAMP_NAME, TimestampVisitor)
+    }
+}
+
+struct TimestampVisitor;
+
+impl<'de> Visitor<'de> for TimestampVisitor {
+    type Value = Timestamp;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("milliseconds since epoch and nanoseconds")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Timestamp, A::Error> {
+        let millis = seq
+            .next_element()?
+            .ok_or_else(|| serde::de::Error::invalid_length(0, &self))?;
+        let nanos = seq
+            .next_element()?
+            .ok_or_else(|| serde::de::Error::invalid_length(1, &self))?;
+        Ok(Timestamp { millis, nanos })
+    }
+
+    fn visit_newtype_struct<D: serde::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Timestamp, D::Error> {
+        deserializer.deserialize_tuple(2, self)
+    }
+}
@@ -0,0 +1,456 @@
+use std::convert::TryFrom;
+
+use serde::ser::{self, Serialize};
+
+use crate::error::{IgniteError, IgniteResult};
+use crate::protocol::{
+    write_i32, write_i8, write_string_type_code, write_u16, write_u8, TypeCode,
+    COLLECTION_KIND_ARRAY_LIST, COMPLEX_OBJ_HEADER_LEN, FLAG_HAS_SCHEMA, FLAG_USER_TYPE,
+    MAP_KIND_HASH_MAP,
+};
+use crate::serde_binary::{DATE_NAME, DECIMAL_NAME, TIMESTAMP_NAME, TIME_NAME, UUID_NAME};
+use crate::utils::{bytes_to_java_hashcode, get_schema_id_by_field_ids, string_to_java_hashcode};
+use crate::{Date, Decimal, Enum, Time, Timestamp, Uuid, WritableType};
+
+/// Serializes values into the Ignite binary format.
+///
+/// * structs, tuple structs and unit structs are written as complex objects.
+///   Type id and field ids are hash codes of the lowercased names, like #[derive(IgniteObj)] does
+/// * sequences and tuples are written as Collection (java.util.ArrayList)
+/// * maps are written as Map (java.util.HashMap)
+/// * `None` and `()` are written as Null
+/// * unit variants are written as Enum, other variants as a Map with a single entry
+///   from the variant name to the value
+pub struct Serializer {
+    output: Vec<u8>,
+}
+
+impl Serializer {
+    pub fn new() -> Serializer {
+        Serializer { output: Vec::new() }
+    }
+
+    /// Returns the serialized bytes
+    pub fn into_inner(self) -> Vec<u8> {
+        self.output
+    }
+
+    fn write<T: WritableType>(&mut self, value: &T) -> IgniteResult<()> {
+        value.write(&mut self.output)?;
+        Ok(())
+    }
+
+    /// Writes type code and a placeholder of the length, which is set by `end_sequence`
+    fn start_sequence(&mut self, type_code: TypeCode, kind: i8) -> IgniteResult<Sequence<'_>> {
+        write_u8(&mut self.output, type_code as u8)?;
+        let len_pos = self.output.len();
+        write_i32(&mut self.output, 0)?;
+        write_i8(&mut self.output, kind)?;
+        Ok(Sequence {
+            ser: self,
+            len_pos,
+            len: 0,
+        })
+    }
+
+    /// Writes a single entry Map header and the variant name as its key
+    fn start_variant(&mut self, variant: &str) -> IgniteResult<()> {
+        write_u8(&mut self.output, TypeCode::Map as u8)?;
+        write_i32(&mut self.output, 1)?;
+        write_i8(&mut self.output, MAP_KIND_HASH_MAP)?;
+        write_string_type_code(&mut self.output, variant)?;
+        Ok(())
+    }
+
+    /// Writes the header placeholder, which is set by `Object::end`
+    fn start_object(&mut self, name: &str) -> IgniteResult<Object<'_>> {
+        let start = self.output.len();
+        self.output
+            .resize(start + COMPLEX_OBJ_HEADER_LEN as usize, 0);
+        Ok(Object {
+            ser: self,
+            start,
+            type_id: string_to_java_hashcode(&name.to_lowercase()),
+            schema: Vec::new(),
+        })
+    }
+
+    /// Serializes the value on its own and reads it back as the given type.
+    /// Used for the crate's types serialized as newtypes with magic names
+    fn reparse<T, U>(value: &T) -> IgniteResult<U>
+    where
+        T: ?Sized + Serialize,
+        U: serde::de::DeserializeOwned,
+    {
+        let bytes = super::to_bytes(value)?;
+        super::from_reader(&mut bytes.as_slice())
+    }
+}
+
+impl Default for Serializer {
+    fn default() -> Self {
+        Serializer::new()
+    }
+}
+
+impl<'a> ser::Serializer for &'a mut Serializer {
+    type Ok = ();
+    type Error = IgniteError;
+    type SerializeSeq = Sequence<'a>;
+    type SerializeTuple = Sequence<'a>;
+    type SerializeTupleStruct = Object<'a>;
+    type SerializeTupleVariant = Sequence<'a>;
+    type SerializeMap = Sequence<'a>;
+    type SerializeStruct = Object<'a>;
+    type SerializeStructVariant = Object<'a>;
+
+    fn serialize_bool(self, v: bool) -> IgniteResult<()> {
+        self.write(&v)
+    }
+
+    fn serialize_i8(self, v: i8) -> IgniteResult<()> {
+        self.write(&(v as u8))
+    }
+
+    fn serialize_i16(self, v: i16) -> IgniteResult<()> {
+        self.write(&v)
+    }
+
+    fn serialize_i32(self, v: i32) -> IgniteResult<()> {
+        self.write(&v)
+    }
+
+    fn serialize_i64(self, v: i64) -> IgniteResult<()> {
+        self.write(&v)
+    }
+
+    fn serialize_u8(self, v: u8) -> IgniteResult<()> {
+        self.write(&v)
+    }
+
+    // u16 is written as Char, like the crate does everywhere else
+    fn serialize_u16(self, v: u16) -> IgniteResult<()> {
+        self.write(&v)
+    }
+
+    fn serialize_u32(self, v: u32) -> IgniteResult<()> {
+        self.write(&i64::from(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> IgniteResult<()> {
+        match i64::try_from(v) {
+            Ok(v) => self.write(&v),
+            Err(_) => Err(IgniteError::InvalidValue(
+                "u64 value is out of Long range!".to_owned(),
+            )),
+        }
+    }
+
+    fn serialize_f32(self, v: f32) -> IgniteResult<()> {
+        self.write(&v)
+    }
+
+    fn serialize_f64(self, v: f64) -> IgniteResult<()> {
+        self.write(&v)
+    }
+
+    fn serialize_char(self, v: char) -> IgniteResult<()> {
+        let mut units = [0u16; 2];
+        match v.encode_utf16(&mut units) {
+            [unit] => self.write(unit),
+            _ => Err(IgniteError::InvalidValue(
+                "Char out of the Basic Multilingual Plane can't be written!".to_owned(),
+            )),
+        }
+    }
+
+    fn serialize_str(self, v: &str) -> IgniteResult<()> {
+        write_string_type_code(&mut self.output, v)?;
+        Ok(())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> IgniteResult<()> {
+        write_u8(&mut self.output, TypeCode::ArrByte as u8)?;
+        write_i32(&mut self.output, v.len() as i32)?;
+        self.output.extend_from_slice(v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> IgniteResult<()> {
+        self.serialize_unit()
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> IgniteResult<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> IgniteResult<()> {
+        write_u8(&mut self.output, TypeCode::Null as u8)?;
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> IgniteResult<()> {
+        ser::SerializeStruct::end(self.start_object(name)?)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> IgniteResult<()> {
+        self.write(&Enum {
+            type_id: string_to_java_hashcode(&name.to_lowercase()),
+            ordinal: variant_index as i32,
+        })
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> IgniteResult<()> {
+        match name {
+            UUID_NAME => {
+                let uuid: String = Serializer::reparse(value)?;
+                self.write(&uuid.parse::<Uuid>()?)
+            }
+            DATE_NAME => {
+                let millis = Serializer::reparse(value)?;
+                self.write(&Date { millis })
+            }
+            TIME_NAME => {
+                let millis = Serializer::reparse(value)?;
+                self.write(&Time { millis })
+            }
+            TIMESTAMP_NAME => {
+                let (millis, nanos) = Serializer::reparse(value)?;
+                self.write(&Timestamp { millis, nanos })
+            }
+            DECIMAL_NAME => {
+                let decimal: String = Serializer::reparse(value)?;
+                self.write(&decimal.parse::<Decimal>()?)
+            }
+            _ => value.serialize(self),
+        }
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> IgniteResult<()> {
+        self.start_variant(variant)?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> IgniteResult<Sequence<'a>> {
+        self.start_sequence(TypeCode::Collection, COLLECTION_KIND_ARRAY_LIST)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> IgniteResult<Sequence<'a>> {
+        self.start_sequence(TypeCode::Collection, COLLECTION_KIND_ARRAY_LIST)
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, _len: usize) -> IgniteResult<Object<'a>> {
+        self.start_object(name)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> IgniteResult<Sequence<'a>> {
+        self.start_variant(variant)?;
+        self.start_sequence(TypeCode::Collection, COLLECTION_KIND_ARRAY_LIST)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> IgniteResult<Sequence<'a>> {
+        self.start_sequence(TypeCode::Map, MAP_KIND_HASH_MAP)
+    }
+
+    fn serialize_struct(self, name: &'static str, _len: usize) -> IgniteResult<Object<'a>> {
+        self.start_object(name)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> IgniteResult<Object<'a>> {
+        self.start_variant(variant)?;
+        self.start_object(variant)
+    }
+}
+
+/// Collection or Map which length is set when all items are written
+pub struct Sequence<'a> {
+    ser: &'a mut Serializer,
+    len_pos: usize,
+    len: i32,
+}
+
+impl Sequence<'_> {
+    fn item<T: ?Sized + Serialize>(&mut self, value: &T) -> IgniteResult<()> {
+        value.serialize(&mut *self.ser)?;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn end_sequence(self) -> IgniteResult<()> {
+        self.ser.output[self.len_pos..self.len_pos + 4].copy_from_slice(&self.len.to_le_bytes());
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for Sequence<'_> {
+    type Ok = ();
+    type Error = IgniteError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> IgniteResult<()> {
+        self.item(value)
+    }
+
+    fn end(self) -> IgniteResult<()> {
+        self.end_sequence()
+    }
+}
+
+impl ser::SerializeTuple for Sequence<'_> {
+    type Ok = ();
+    type Error = IgniteError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> IgniteResult<()> {
+        self.item(value)
+    }
+
+    fn end(self) -> IgniteResult<()> {
+        self.end_sequence()
+    }
+}
+
+impl ser::SerializeTupleVariant for Sequence<'_> {
+    type Ok = ();
+    type Error = IgniteError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> IgniteResult<()> {
+        self.item(value)
+    }
+
+    fn end(self) -> IgniteResult<()> {
+        self.end_sequence()
+    }
+}
+
+impl ser::SerializeMap for Sequence<'_> {
+    type Ok = ();
+    type Error = IgniteError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> IgniteResult<()> {
+        key.serialize(&mut *self.ser)
+    }
+
+    // entry is counted once its value is written
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> IgniteResult<()> {
+        self.item(value)
+    }
+
+    fn end(self) -> IgniteResult<()> {
+        self.end_sequence()
+    }
+}
+
+/// Complex object which header and footer are written when all fields are written
+pub struct Object<'a> {
+    ser: &'a mut Serializer,
+    start: usize,
+    type_id: i32,
+    schema: Vec<(i32, i32)>, // field id, offset from the object start
+}
+
+impl Object<'_> {
+    fn field<T: ?Sized + Serialize>(&mut self, name: &str, value: &T) -> IgniteResult<()> {
+        let offset = (self.ser.output.len() - self.start) as i32;
+        self.schema
+            .push((string_to_java_hashcode(&name.to_lowercase()), offset));
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end_object(self) -> IgniteResult<()> {
+        let output = &mut self.ser.output;
+        let fields_end = output.len();
+        let field_ids: Vec<i32> = self.schema.iter().map(|(id, _)| *id).collect();
+        for (field_id, offset) in &self.schema {
+            write_i32(output, *field_id)?;
+            write_i32(output, *offset)?;
+        }
+
+        let mut header: Vec<u8> = Vec::with_capacity(COMPLEX_OBJ_HEADER_LEN as usize);
+        write_u8(&mut header, TypeCode::ComplexObj as u8)?;
+        write_u8(&mut header, 1)?; // version
+        write_u16(&mut header, FLAG_USER_TYPE | FLAG_HAS_SCHEMA)?;
+        write_i32(&mut header, self.type_id)?;
+        let fields = &output[self.start + COMPLEX_OBJ_HEADER_LEN as usize..fields_end];
+        write_i32(&mut header, bytes_to_java_hashcode(fields))?;
+        write_i32(&mut header, (output.len() - self.start) as i32)?; // length including header
+        write_i32(&mut header, get_schema_id_by_field_ids(&field_ids))?;
+        write_i32(&mut header, (fields_end - self.start) as i32)?; // schema offset
+        output[self.start..self.start + header.len()].copy_from_slice(&header);
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for Object<'_> {
+    type Ok = ();
+    type Error = IgniteError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> IgniteResult<()> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> IgniteResult<()> {
+        self.end_object()
+    }
+}
+
+impl ser::SerializeStructVariant for Object<'_> {
+    type Ok = ();
+    type Error = IgniteError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> IgniteResult<()> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> IgniteResult<()> {
+        self.end_object()
+    }
+}
+
+// fields of tuple structs are named by their positions: "0", "1" and so on
+impl ser::SerializeTupleStruct for Object<'_> {
+    type Ok = ();
+    type Error = IgniteError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> IgniteResult<()> {
+        let name = self.schema.len().to_string();
+        self.field(&name, value)
+    }
+
+    fn end(self) -> IgniteResult<()> {
+        self.end_object()
+    }
+}
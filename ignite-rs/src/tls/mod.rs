@@ -0,0 +1,217 @@
+//! TLS configuration of the connection. Enabled by one of the backends:
+//! the `rustls-tls` feature (or its alias `ssl`) or the `native-tls` one.
+//!
+//! Trusted CAs and the client certificate for the mutual TLS are loaded from PEM files:
+//! ```ignore
+//! let tls = TlsConfig::builder()
+//!     .ca_file("ca.pem")
+//!     .client_cert_files("client.pem", "client.key")
+//!     .build()?;
+//! ```
+
+use std::path::Path;
+use std::sync::Arc;
+use std::{error, fmt, fs};
+
+use crate::error::{IgniteError, IgniteResult};
+use crate::transport::TlsConnector;
+
+#[cfg(feature = "native-tls")]
+mod native_backend;
+#[cfg(feature = "rustls-tls")]
+mod rustls_backend;
+
+/// Library which implements TLS. rustls is the default one if its feature is enabled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TlsBackend {
+    /// [rustls](https://github.com/rustls/rustls). Enabled by the `rustls-tls` feature
+    #[cfg(feature = "rustls-tls")]
+    #[default]
+    Rustls,
+    /// OpenSSL, SChannel or Secure Transport depending on the platform.
+    /// Enabled by the `native-tls` feature
+    #[cfg(feature = "native-tls")]
+    #[cfg_attr(not(feature = "rustls-tls"), default)]
+    NativeTls,
+}
+
+/// TLS configuration of the connection
+#[derive(Clone)]
+pub struct TlsConfig {
+    connector: Arc<dyn TlsConnector>,
+    server_name: Option<String>,
+}
+
+impl TlsConfig {
+    pub fn builder() -> TlsConfigBuilder {
+        TlsConfigBuilder {
+            backend: TlsBackend::default(),
+            ca_pems: Vec::new(),
+            client_cert_pem: None,
+            server_name: None,
+            accept_invalid_certs: false,
+        }
+    }
+
+    /// Uses the prepared rustls configuration as is
+    #[cfg(feature = "rustls-tls")]
+    pub fn from_rustls(client_conf: impl Into<Arc<rustls::ClientConfig>>) -> TlsConfig {
+        TlsConfig {
+            connector: rustls_backend::connector_of(client_conf.into()),
+            server_name: None,
+        }
+    }
+
+    /// Uses the prepared native-tls connector as is
+    #[cfg(feature = "native-tls")]
+    pub fn from_native_tls(connector: native_tls::TlsConnector) -> TlsConfig {
+        TlsConfig {
+            connector: native_backend::connector_of(connector),
+            server_name: None,
+        }
+    }
+
+    /// Name sent as SNI and verified against the certificate instead of the host being connected
+    pub fn with_server_name(mut self, server_name: &str) -> TlsConfig {
+        self.server_name = Some(server_name.to_owned());
+        self
+    }
+
+    pub(crate) fn connector(&self) -> &dyn TlsConnector {
+        self.connector.as_ref()
+    }
+
+    /// Name the certificate is verified against when connecting to the host
+    pub(crate) fn server_name<'a>(&'a self, host: &'a str) -> &'a str {
+        self.server_name.as_deref().unwrap_or(host)
+    }
+}
+
+/// Builder of the [`TlsConfig`]. Files are read and validated by [`TlsConfigBuilder::build`]
+pub struct TlsConfigBuilder {
+    backend: TlsBackend,
+    ca_pems: Vec<Pem>,
+    client_cert_pem: Option<(Pem, Pem)>,
+    server_name: Option<String>,
+    accept_invalid_certs: bool,
+}
+
+/// PEM contents or the file to read it from
+enum Pem {
+    File(String),
+    Bytes(Vec<u8>),
+}
+
+impl Pem {
+    fn read(&self) -> IgniteResult<Vec<u8>> {
+        match self {
+            Pem::File(path) => fs::read(path)
+                .map_err(|err| IgniteError::InvalidConfig(format!("Can't read {}: {}", path, err))),
+            Pem::Bytes(bytes) => Ok(bytes.clone()),
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            Pem::File(path) => path,
+            Pem::Bytes(_) => "PEM",
+        }
+    }
+}
+
+impl TlsConfigBuilder {
+    /// Library which implements TLS. rustls by default if its feature is enabled
+    pub fn backend(mut self, backend: TlsBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Trusts the CA certificates of the PEM file. Could be called several times
+    pub fn ca_file(mut self, path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_string_lossy().into_owned();
+        self.ca_pems.push(Pem::File(path));
+        self
+    }
+
+    /// Trusts the PEM-encoded CA certificates
+    pub fn ca_pem(mut self, pem: &[u8]) -> Self {
+        self.ca_pems.push(Pem::Bytes(pem.to_vec()));
+        self
+    }
+
+    /// Certificate chain and its private key presented to the server.
+    /// The native-tls backend requires the PKCS#8 key, rustls accepts the RSA and SEC1 ones too
+    pub fn client_cert_files(mut self, cert: impl AsRef<Path>, key: impl AsRef<Path>) -> Self {
+        let cert = Pem::File(cert.as_ref().to_string_lossy().into_owned());
+        let key = Pem::File(key.as_ref().to_string_lossy().into_owned());
+        self.client_cert_pem = Some((cert, key));
+        self
+    }
+
+    /// PEM-encoded certificate chain and its private key presented to the server
+    pub fn client_cert_pem(mut self, cert: &[u8], key: &[u8]) -> Self {
+        self.client_cert_pem = Some((Pem::Bytes(cert.to_vec()), Pem::Bytes(key.to_vec())));
+        self
+    }
+
+    /// Name sent as SNI and verified against the certificate instead of the host being connected.
+    /// Useful when the nodes are connected by their IP addresses
+    pub fn server_name(mut self, server_name: &str) -> Self {
+        self.server_name = Some(server_name.to_owned());
+        self
+    }
+
+    /// Accepts any server certificate, so the connection is open to man-in-the-middle attacks.
+    /// Intended only for the local test clusters with self-signed certificates
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.accept_invalid_certs = accept;
+        self
+    }
+
+    pub fn build(self) -> IgniteResult<TlsConfig> {
+        if self.ca_pems.is_empty() && !self.accept_invalid_certs {
+            return Err(IgniteError::InvalidConfig(
+                "No CA certificate is trusted".to_owned(),
+            ));
+        }
+        let connector = match self.backend {
+            #[cfg(feature = "rustls-tls")]
+            TlsBackend::Rustls => rustls_backend::connector(&self)?,
+            #[cfg(feature = "native-tls")]
+            TlsBackend::NativeTls => native_backend::connector(&self)?,
+        };
+        Ok(TlsConfig {
+            connector,
+            server_name: self.server_name,
+        })
+    }
+}
+
+/// Failure of the TLS itself reported by the backend's stream as an I/O error,
+/// so it's converted to `IgniteError::Tls` rather than `IgniteError::Io`
+#[derive(Debug)]
+pub(crate) struct TlsFailure(pub(crate) Box<dyn error::Error + Send + Sync>);
+
+impl fmt::Display for TlsFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl error::Error for TlsFailure {}
+
+fn no_ca_cert(pem: &Pem) -> IgniteError {
+    IgniteError::InvalidConfig(format!("No valid CA certificate in {}", pem.name()))
+}
+
+fn no_cert(pem: &Pem) -> IgniteError {
+    IgniteError::InvalidConfig(format!("No certificate in {}", pem.name()))
+}
+
+fn no_key(pem: &Pem) -> IgniteError {
+    IgniteError::InvalidConfig(format!("No private key in {}", pem.name()))
+}
+
+fn invalid_client_cert(err: impl std::fmt::Display) -> IgniteError {
+    IgniteError::InvalidConfig(format!("Invalid client certificate: {}", err))
+}
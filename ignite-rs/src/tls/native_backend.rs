@@ -0,0 +1,172 @@
+//! TLS by the platform's library: OpenSSL, SChannel or Secure Transport
+
+use std::io;
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use native_tls::{Certificate, HandshakeError, Identity};
+
+use super::{invalid_client_cert, no_ca_cert, no_cert, no_key, Pem, TlsConfigBuilder, TlsFailure};
+use crate::error::{IgniteError, IgniteResult};
+use crate::transport::{TlsConnector, TransportStream};
+
+struct NativeConnector(native_tls::TlsConnector);
+
+pub(super) fn connector_of(connector: native_tls::TlsConnector) -> Arc<dyn TlsConnector> {
+    Arc::new(NativeConnector(connector))
+}
+
+pub(super) fn connector(builder: &TlsConfigBuilder) -> IgniteResult<Arc<dyn TlsConnector>> {
+    let mut native = native_tls::TlsConnector::builder();
+    // only the configured CAs are trusted, as with rustls
+    native.disable_built_in_roots(true);
+    native.danger_accept_invalid_certs(builder.accept_invalid_certs);
+
+    for pem in &builder.ca_pems {
+        let certs = read_certs(pem).map_err(|_| no_ca_cert(pem))?;
+        for cert in certs {
+            native.add_root_certificate(cert);
+        }
+    }
+
+    if let Some((cert_pem, key_pem)) = &builder.client_cert_pem {
+        let cert = cert_pem.read()?;
+        let key = key_pem.read()?;
+        read_certs(cert_pem)?;
+        if !String::from_utf8_lossy(&key).contains("PRIVATE KEY-----") {
+            return Err(no_key(key_pem));
+        }
+        native.identity(Identity::from_pkcs8(&cert, &key).map_err(invalid_client_cert)?);
+    }
+
+    let connector = native
+        .build()
+        .map_err(|err| IgniteError::Tls(Box::new(err)))?;
+    Ok(connector_of(connector))
+}
+
+fn read_certs(pem: &Pem) -> IgniteResult<Vec<Certificate>> {
+    match Certificate::stack_from_pem(&pem.read()?) {
+        Ok(certs) if !certs.is_empty() => Ok(certs),
+        _ => Err(no_cert(pem)),
+    }
+}
+
+impl TlsConnector for NativeConnector {
+    fn connect(
+        &self,
+        server_name: &str,
+        stream: Box<dyn TransportStream>,
+    ) -> IgniteResult<Box<dyn TransportStream>> {
+        let transport_failed = Arc::new(AtomicBool::new(false));
+        let stream = TrackedStream {
+            inner: stream,
+            failed: transport_failed.clone(),
+        };
+        // unlike rustls, the handshake is performed right away
+        match self.0.connect(server_name, stream) {
+            Ok(tls) => Ok(Box::new(NativeStream {
+                tls,
+                transport_failed,
+            })),
+            // the connection failed rather than the TLS
+            Err(HandshakeError::Failure(err)) if transport_failed.load(Ordering::Relaxed) => {
+                Err(IgniteError::from(io::Error::other(err)))
+            }
+            Err(HandshakeError::Failure(err)) => Err(IgniteError::Tls(Box::new(err))),
+            // the stream's timeout has expired
+            Err(HandshakeError::WouldBlock(_)) => Err(IgniteError::from(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "TLS handshake timed out",
+            ))),
+        }
+    }
+}
+
+/// Transport's stream which remembers its failures,
+/// so they are told from the ones of the TLS, like the alerts of the server
+struct TrackedStream {
+    inner: Box<dyn TransportStream>,
+    failed: Arc<AtomicBool>,
+}
+
+impl TrackedStream {
+    fn track<T>(&self, result: io::Result<T>) -> io::Result<T> {
+        if result.is_err() {
+            self.failed.store(true, Ordering::Relaxed);
+        }
+        result
+    }
+}
+
+impl Read for TrackedStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let result = self.inner.read(buf);
+        self.track(result)
+    }
+}
+
+impl Write for TrackedStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let result = self.inner.write(buf);
+        self.track(result)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let result = self.inner.flush();
+        self.track(result)
+    }
+}
+
+struct NativeStream {
+    tls: native_tls::TlsStream<TrackedStream>,
+    transport_failed: Arc<AtomicBool>,
+}
+
+impl NativeStream {
+    /// Errors not caused by the transport are the TLS ones
+    fn check<T>(&self, result: io::Result<T>) -> io::Result<T> {
+        let transport_failed = self.transport_failed.swap(false, Ordering::Relaxed);
+        match result {
+            Err(err) if !transport_failed => Err(io::Error::new(
+                err.kind(),
+                TlsFailure(err.into_inner().unwrap_or_else(|| "TLS failure".into())),
+            )),
+            result => result,
+        }
+    }
+}
+
+impl Read for NativeStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let result = self.tls.read(buf);
+        self.check(result)
+    }
+}
+
+impl Write for NativeStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let result = self.tls.write(buf);
+        self.check(result)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let result = self.tls.flush();
+        self.check(result)
+    }
+}
+
+impl TransportStream for NativeStream {
+    fn set_timeouts(
+        &mut self,
+        read_timeout: Option<Duration>,
+        write_timeout: Option<Duration>,
+    ) -> io::Result<()> {
+        self.tls
+            .get_mut()
+            .inner
+            .set_timeouts(read_timeout, write_timeout)
+    }
+}
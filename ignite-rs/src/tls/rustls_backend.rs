@@ -0,0 +1,150 @@
+//! TLS by rustls with the ring crypto provider
+
+use std::convert::TryFrom;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{ring, verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use rustls::{
+    ClientConfig, ClientConnection, DigitallySignedStruct, RootCertStore, SignatureScheme,
+    StreamOwned,
+};
+
+use super::{invalid_client_cert, no_ca_cert, no_cert, no_key, Pem, TlsConfigBuilder};
+use crate::error::{IgniteError, IgniteResult};
+use crate::transport::{TlsConnector, TransportStream};
+
+struct RustlsConnector(Arc<ClientConfig>);
+
+pub(super) fn connector_of(client_conf: Arc<ClientConfig>) -> Arc<dyn TlsConnector> {
+    Arc::new(RustlsConnector(client_conf))
+}
+
+pub(super) fn connector(builder: &TlsConfigBuilder) -> IgniteResult<Arc<dyn TlsConnector>> {
+    let provider = Arc::new(ring::default_provider());
+    let conf = ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(tls_error)?;
+
+    let conf = if builder.accept_invalid_certs {
+        conf.dangerous()
+            .with_custom_certificate_verifier(Arc::new(AcceptAnyCert(provider)))
+    } else {
+        let mut roots = RootCertStore::empty();
+        for pem in &builder.ca_pems {
+            let certs = read_certs(pem).map_err(|_| no_ca_cert(pem))?;
+            if roots.add_parsable_certificates(certs).0 == 0 {
+                return Err(no_ca_cert(pem));
+            }
+        }
+        conf.with_root_certificates(roots)
+    };
+
+    let conf = match &builder.client_cert_pem {
+        Some((cert_pem, key_pem)) => {
+            let certs = read_certs(cert_pem)?;
+            let key =
+                PrivateKeyDer::from_pem_slice(&key_pem.read()?).map_err(|_| no_key(key_pem))?;
+            conf.with_client_auth_cert(certs, key)
+                .map_err(invalid_client_cert)?
+        }
+        None => conf.with_no_client_auth(),
+    };
+    Ok(connector_of(Arc::new(conf)))
+}
+
+fn read_certs(pem: &Pem) -> IgniteResult<Vec<CertificateDer<'static>>> {
+    let certs = CertificateDer::pem_slice_iter(&pem.read()?)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| no_cert(pem))?;
+    if certs.is_empty() {
+        return Err(no_cert(pem));
+    }
+    Ok(certs)
+}
+
+fn tls_error(err: rustls::Error) -> IgniteError {
+    IgniteError::Tls(Box::new(err))
+}
+
+impl TlsConnector for RustlsConnector {
+    fn connect(
+        &self,
+        server_name: &str,
+        stream: Box<dyn TransportStream>,
+    ) -> IgniteResult<Box<dyn TransportStream>> {
+        let server_name = ServerName::try_from(server_name.to_owned())
+            .map_err(|err| IgniteError::Tls(Box::new(err)))?;
+        let mut session = ClientConnection::new(self.0.clone(), server_name).map_err(tls_error)?;
+        let mut stream = stream;
+        // rustls performs the handshake on the first read or write unless it's driven here,
+        // so the certificate failures would be reported by the first request
+        while session.is_handshaking() {
+            session.complete_io(&mut stream)?;
+        }
+        Ok(Box::new(StreamOwned::new(session, stream)))
+    }
+}
+
+impl TransportStream for StreamOwned<ClientConnection, Box<dyn TransportStream>> {
+    fn set_timeouts(
+        &mut self,
+        read_timeout: Option<Duration>,
+        write_timeout: Option<Duration>,
+    ) -> io::Result<()> {
+        self.get_mut().set_timeouts(read_timeout, write_timeout)
+    }
+}
+
+/// Verifier which accepts any certificate. Handshake signatures are still checked
+#[derive(Debug)]
+struct AcceptAnyCert(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AcceptAnyCert {
+    fn verify_server_cert(
+        &self,
+        _: &CertificateDer<'_>,
+        _: &[CertificateDer<'_>],
+        _: &ServerName<'_>,
+        _: &[u8],
+        _: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
@@ -0,0 +1,338 @@
+//! Streams the connection is established over.
+//!
+//! The client connects over TCP by default, encrypted if TLS is configured.
+//! Custom [`Transport`] set as [`ClientConfig::transport`] could tunnel the connection
+//! through a proxy or connect to an in-process server over the in-memory [`duplex`]:
+//! ```ignore
+//! conf.transport = Some(Arc::new(|addr: &str, timeout: Option<Duration>| {
+//!     let stream = my_proxy::connect(addr, timeout)?;
+//!     Ok(Box::new(stream) as Box<dyn TransportStream>)
+//! }));
+//! ```
+
+use std::collections::VecDeque;
+use std::io;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::error::{IgniteError, IgniteResult};
+#[cfg(any(feature = "rustls-tls", feature = "native-tls"))]
+use crate::tls::TlsConfig;
+use crate::ClientConfig;
+
+/// Bidirectional byte stream to the node
+pub trait TransportStream: Read + Write + Send {
+    /// Limits the time a read or a write blocks. `None` blocks indefinitely.
+    /// Expired timeout fails the operation with `WouldBlock` or `TimedOut`.
+    /// Set before every request, so the operation timeouts work
+    fn set_timeouts(
+        &mut self,
+        read_timeout: Option<Duration>,
+        write_timeout: Option<Duration>,
+    ) -> io::Result<()>;
+}
+
+/// Opens streams to the nodes
+pub trait Transport: Send + Sync {
+    /// Connects to the `host:port` address. `timeout` limits the time of connecting, if set
+    fn connect(
+        &self,
+        addr: &str,
+        timeout: Option<Duration>,
+    ) -> IgniteResult<Box<dyn TransportStream>>;
+}
+
+impl<F> Transport for F
+where
+    F: Fn(&str, Option<Duration>) -> IgniteResult<Box<dyn TransportStream>> + Send + Sync,
+{
+    fn connect(
+        &self,
+        addr: &str,
+        timeout: Option<Duration>,
+    ) -> IgniteResult<Box<dyn TransportStream>> {
+        self(addr, timeout)
+    }
+}
+
+impl TransportStream for TcpStream {
+    fn set_timeouts(
+        &mut self,
+        read_timeout: Option<Duration>,
+        write_timeout: Option<Duration>,
+    ) -> io::Result<()> {
+        self.set_read_timeout(read_timeout)?;
+        self.set_write_timeout(write_timeout)
+    }
+}
+
+/// Plain TCP connection. The default transport
+#[derive(Debug, Clone, Default)]
+pub struct TcpTransport {
+    pub read_timeout: Option<Duration>,
+    pub write_timeout: Option<Duration>,
+    pub nodelay: Option<bool>,
+    pub nonblocking: Option<bool>,
+    pub ttl: Option<u32>,
+}
+
+impl TcpTransport {
+    /// Transport with the `tcp_*` options of the configuration
+    pub fn from_config(conf: &ClientConfig) -> TcpTransport {
+        TcpTransport {
+            read_timeout: conf.tcp_read_timeout,
+            write_timeout: conf.tcp_write_timeout,
+            nodelay: conf.tcp_nodelay,
+            nonblocking: conf.tcp_nonblocking,
+            ttl: conf.tcp_ttl,
+        }
+    }
+
+    fn configure(&self, stream: &TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(self.read_timeout)?;
+        stream.set_write_timeout(self.write_timeout)?;
+        if let Some(nodelay) = self.nodelay {
+            stream.set_nodelay(nodelay)?;
+        }
+        if let Some(nonblocking) = self.nonblocking {
+            stream.set_nonblocking(nonblocking)?;
+        }
+        if let Some(ttl) = self.ttl {
+            stream.set_ttl(ttl)?;
+        }
+        Ok(())
+    }
+}
+
+impl Transport for TcpTransport {
+    /// Tries every address the host is resolved to
+    fn connect(
+        &self,
+        addr: &str,
+        timeout: Option<Duration>,
+    ) -> IgniteResult<Box<dyn TransportStream>> {
+        let mut last_err = io::Error::new(io::ErrorKind::InvalidInput, "No address resolved");
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        for addr in addr.to_socket_addrs()? {
+            let result = match deadline {
+                Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                    Some(remaining) if remaining > Duration::from_millis(0) => {
+                        TcpStream::connect_timeout(&addr, remaining)
+                    }
+                    _ => Err(io::Error::from(io::ErrorKind::TimedOut)),
+                },
+                None => TcpStream::connect(addr),
+            };
+            match result {
+                Ok(stream) => {
+                    self.configure(&stream)?;
+                    return Ok(Box::new(stream));
+                }
+                Err(err) => last_err = err,
+            }
+        }
+        Err(IgniteError::from(last_err))
+    }
+}
+
+/// Encrypts the streams of the inner transport
+#[cfg(any(feature = "rustls-tls", feature = "native-tls"))]
+pub struct TlsTransport {
+    inner: Arc<dyn Transport>,
+    tls_conf: TlsConfig,
+}
+
+#[cfg(any(feature = "rustls-tls", feature = "native-tls"))]
+impl TlsTransport {
+    pub fn new(inner: Arc<dyn Transport>, tls_conf: TlsConfig) -> TlsTransport {
+        TlsTransport { inner, tls_conf }
+    }
+}
+
+#[cfg(any(feature = "rustls-tls", feature = "native-tls"))]
+impl Transport for TlsTransport {
+    /// The certificate is verified against the host of the address unless it's overridden
+    fn connect(
+        &self,
+        addr: &str,
+        timeout: Option<Duration>,
+    ) -> IgniteResult<Box<dyn TransportStream>> {
+        let mut stream = self.inner.connect(addr, timeout)?;
+        // some backends perform the handshake right away
+        if timeout.is_some() {
+            stream.set_timeouts(timeout, timeout)?;
+        }
+        let server_name = self.tls_conf.server_name(host_of(addr));
+        self.tls_conf.connector().connect(server_name, stream)
+    }
+}
+
+/// TLS backend, which encrypts the established stream
+#[cfg(any(feature = "rustls-tls", feature = "native-tls"))]
+pub(crate) trait TlsConnector: Send + Sync {
+    /// Wraps the stream. The certificate is verified against the server name
+    fn connect(
+        &self,
+        server_name: &str,
+        stream: Box<dyn TransportStream>,
+    ) -> IgniteResult<Box<dyn TransportStream>>;
+}
+
+/// Host of the `host:port` address without the IPv6 brackets
+#[cfg(any(feature = "rustls-tls", feature = "native-tls"))]
+fn host_of(addr: &str) -> &str {
+    let host = match addr.rfind(':') {
+        Some(pos) if !addr[pos..].contains(']') => &addr[..pos],
+        _ => addr,
+    };
+    host.trim_start_matches('[').trim_end_matches(']')
+}
+
+/// Pair of connected in-memory streams: what is written to one is read from the other.
+/// Dropping a stream closes the pair, so the other one reads EOF
+pub fn duplex() -> (DuplexStream, DuplexStream) {
+    let first = Arc::new(Pipe::default());
+    let second = Arc::new(Pipe::default());
+    (
+        DuplexStream {
+            input: first.clone(),
+            output: second.clone(),
+            read_timeout: None,
+        },
+        DuplexStream {
+            input: second,
+            output: first,
+            read_timeout: None,
+        },
+    )
+}
+
+/// One end of the [`duplex`]. Writes never block
+pub struct DuplexStream {
+    input: Arc<Pipe>,
+    output: Arc<Pipe>,
+    read_timeout: Option<Duration>,
+}
+
+/// Bytes written to one stream and not yet read by the other
+#[derive(Default)]
+struct Pipe {
+    state: Mutex<PipeState>,
+    readable: Condvar,
+}
+
+#[derive(Default)]
+struct PipeState {
+    bytes: VecDeque<u8>,
+    closed: bool,
+}
+
+impl Pipe {
+    fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.readable.notify_all();
+    }
+}
+
+impl Read for DuplexStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let deadline = self.read_timeout.map(|timeout| Instant::now() + timeout);
+        let mut state = self.input.state.lock().unwrap();
+        while state.bytes.is_empty() && !state.closed && !buf.is_empty() {
+            state = match deadline {
+                Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                    Some(remaining) => {
+                        self.input
+                            .readable
+                            .wait_timeout(state, remaining)
+                            .unwrap()
+                            .0
+                    }
+                    None => return Err(io::Error::from(io::ErrorKind::TimedOut)),
+                },
+                None => self.input.readable.wait(state).unwrap(),
+            };
+        }
+        let len = buf.len().min(state.bytes.len());
+        for (dst, src) in buf.iter_mut().zip(state.bytes.drain(..len)) {
+            *dst = src;
+        }
+        Ok(len)
+    }
+}
+
+impl Write for DuplexStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self.output.state.lock().unwrap();
+        if state.closed {
+            return Err(io::Error::from(io::ErrorKind::BrokenPipe));
+        }
+        state.bytes.extend(buf);
+        self.output.readable.notify_all();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl TransportStream for DuplexStream {
+    /// Writes never block, so the write timeout is ignored
+    fn set_timeouts(
+        &mut self,
+        read_timeout: Option<Duration>,
+        _: Option<Duration>,
+    ) -> io::Result<()> {
+        self.read_timeout = read_timeout;
+        Ok(())
+    }
+}
+
+impl Drop for DuplexStream {
+    fn drop(&mut self) {
+        self.input.close();
+        self.output.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplex() {
+        let (mut client, mut server) = duplex();
+        client.write_all(b"ping").unwrap();
+        let mut buf = [0u8; 4];
+        server.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"ping");
+
+        server
+            .set_timeouts(Some(Duration::from_millis(10)), None)
+            .unwrap();
+        let err = server.read(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+
+        server.write_all(b"pong").unwrap();
+        drop(server);
+        // written bytes are read before EOF
+        let mut rest = Vec::new();
+        client.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, b"pong");
+        assert_eq!(
+            client.write(b"x").unwrap_err().kind(),
+            io::ErrorKind::BrokenPipe
+        );
+    }
+
+    #[cfg(any(feature = "rustls-tls", feature = "native-tls"))]
+    #[test]
+    fn test_host_of() {
+        assert_eq!(host_of("localhost:10800"), "localhost");
+        assert_eq!(host_of("[::1]:10800"), "::1");
+        assert_eq!(host_of("127.0.0.1:10800"), "127.0.0.1");
+    }
+}
@@ -3,19 +3,19 @@ use crate::protocol::complex_obj::IgniteField;
 /// Converts string into Java-like hash code
 // Note: we do not call lowercase() in here like the docs say
 // because _sometimes_ it needs to be upper case, like in CacheGetConfigReq
+// Like in Java, the hash is computed over UTF-16 code units
 pub fn string_to_java_hashcode(value: &str) -> i32 {
     let mut hash: i32 = 0;
-    for char in value.chars().into_iter() {
-        hash = 31i32.overflowing_mul(hash).0 + char as i32;
+    for unit in value.encode_utf16() {
+        hash = hash.wrapping_mul(31).wrapping_add(i32::from(unit));
     }
     hash
 }
 
 pub fn bytes_to_java_hashcode(data: &[u8]) -> i32 {
-    let len = data.len();
     let mut h: i32 = 1;
-    for i in 0..len {
-        h = h.wrapping_mul(31).wrapping_add(i32::from(data[i] as i8));
+    for b in data {
+        h = h.wrapping_mul(31).wrapping_add(i32::from(*b as i8));
     }
     h
 }
@@ -27,21 +27,27 @@ pub const FNV1_OFFSET_BASIS: i32 = 0x811C_9DC5_u32 as i32;
 pub const FNV1_PRIME: i32 = 0x0100_0193;
 
 pub fn get_schema_id(fields: &[IgniteField]) -> i32 {
-    fields
+    let field_ids: Vec<i32> = fields
         .iter()
         .map(|f| string_to_java_hashcode(&f.name.to_lowercase()))
-        .fold(FNV1_OFFSET_BASIS, |acc, field_id| {
-            let mut res = acc;
-            res ^= field_id & 0xFF;
-            res = res.overflowing_mul(FNV1_PRIME).0;
-            res ^= (field_id >> 8) & 0xFF;
-            res = res.overflowing_mul(FNV1_PRIME).0;
-            res ^= (field_id >> 16) & 0xFF;
-            res = res.overflowing_mul(FNV1_PRIME).0;
-            res ^= (field_id >> 24) & 0xFF;
-            res = res.overflowing_mul(FNV1_PRIME).0;
-            res
-        })
+        .collect();
+    get_schema_id_by_field_ids(&field_ids)
+}
+
+/// Computes schema id from ids of the schema's fields in their order
+pub fn get_schema_id_by_field_ids(field_ids: &[i32]) -> i32 {
+    field_ids.iter().fold(FNV1_OFFSET_BASIS, |acc, field_id| {
+        let mut res = acc;
+        res ^= field_id & 0xFF;
+        res = res.overflowing_mul(FNV1_PRIME).0;
+        res ^= (field_id >> 8) & 0xFF;
+        res = res.overflowing_mul(FNV1_PRIME).0;
+        res ^= (field_id >> 16) & 0xFF;
+        res = res.overflowing_mul(FNV1_PRIME).0;
+        res ^= (field_id >> 24) & 0xFF;
+        res = res.overflowing_mul(FNV1_PRIME).0;
+        res
+    })
 }
 
 #[cfg(test)]
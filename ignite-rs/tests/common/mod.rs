@@ -0,0 +1,26 @@
+//! Frames of the binary protocol for the tests which serve the client themselves
+//! rather than with `MockServer`, e.g. over TLS
+
+use std::io::{self, Read, Write};
+
+/// Handshake response which accepts the client
+pub const HANDSHAKE_OK: &[u8] = &[1, 0, 0, 0, 1];
+
+/// Reads the length-prefixed message
+pub fn read_message(stream: &mut impl Read) -> io::Result<Vec<u8>> {
+    let mut len = [0u8; 4];
+    stream.read_exact(&mut len)?;
+    let mut message = vec![0u8; i32::from_le_bytes(len) as usize];
+    stream.read_exact(&mut message)?;
+    Ok(message)
+}
+
+/// Answers the request successfully with the body
+pub fn write_response(stream: &mut impl Write, request: &[u8], body: &[u8]) -> io::Result<()> {
+    let mut response = (12 + body.len() as i32).to_le_bytes().to_vec();
+    response.extend_from_slice(&request[2..10]); // request id
+    response.extend_from_slice(&[0, 0, 0, 0]); // status
+    response.extend_from_slice(body);
+    stream.write_all(&response)?;
+    stream.flush()
+}
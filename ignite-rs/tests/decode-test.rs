@@ -0,0 +1,214 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque};
+
+use proptest::prelude::*;
+
+use ignite_rs::error::IgniteError;
+use ignite_rs::protocol::complex_obj::{ComplexObject, IgniteValue};
+use ignite_rs::{Date, Decimal, Enum, ReadableType, Time, Timestamp, Uuid, WritableType};
+use ignite_rs_derive::{IgniteEnum, IgniteObj};
+
+#[derive(IgniteObj, Debug, Clone, PartialEq)]
+struct Nested {
+    id: Uuid,
+    rate: f64,
+}
+
+#[derive(IgniteEnum, Debug, Clone, Copy, PartialEq)]
+enum Level {
+    Low,
+    High,
+}
+
+#[derive(IgniteObj, Debug, Clone, PartialEq)]
+struct Record {
+    name: String,
+    count: i32,
+    tags: Vec<String>,
+    scores: HashMap<String, i64>,
+    nested: Option<Nested>,
+    level: Level,
+    price: Decimal,
+    created: Timestamp,
+}
+
+const TYPE_CODES: &[u8] = &[
+    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 27,
+    28, 29, 30, 31, 33, 34, 36, 37, 38, 101, 103,
+];
+
+fn read<T: ReadableType>(bytes: &[u8]) {
+    let _ = T::read(&mut &bytes[..]);
+}
+
+/// Feeds the bytes to every readable type. Any result is fine as long as nothing panics
+fn read_all(bytes: &[u8]) {
+    read::<u8>(bytes);
+    read::<u16>(bytes);
+    read::<i16>(bytes);
+    read::<i32>(bytes);
+    read::<i64>(bytes);
+    read::<f32>(bytes);
+    read::<f64>(bytes);
+    read::<bool>(bytes);
+    read::<String>(bytes);
+    read::<Decimal>(bytes);
+    read::<Enum>(bytes);
+    read::<Uuid>(bytes);
+    read::<Date>(bytes);
+    read::<Time>(bytes);
+    read::<Timestamp>(bytes);
+
+    read::<Vec<u8>>(bytes);
+    read::<Vec<u16>>(bytes);
+    read::<Vec<i16>>(bytes);
+    read::<Vec<i32>>(bytes);
+    read::<Vec<i64>>(bytes);
+    read::<Vec<f32>>(bytes);
+    read::<Vec<f64>>(bytes);
+    read::<Vec<bool>>(bytes);
+    read::<Vec<Option<String>>>(bytes);
+    read::<Vec<String>>(bytes);
+    read::<VecDeque<Uuid>>(bytes);
+    read::<LinkedList<i32>>(bytes);
+    read::<HashSet<String>>(bytes);
+    read::<BTreeSet<i64>>(bytes);
+    read::<HashMap<String, Option<i64>>>(bytes);
+    read::<BTreeMap<i32, Vec<String>>>(bytes);
+
+    read::<Box<[String]>>(bytes);
+    read::<Box<[Uuid]>>(bytes);
+    read::<Box<[Date]>>(bytes);
+    read::<Box<[Time]>>(bytes);
+    read::<Box<[Timestamp]>>(bytes);
+    read::<Box<[Decimal]>>(bytes);
+    read::<Option<i32>>(bytes);
+
+    read::<ComplexObject>(bytes);
+    read::<IgniteValue>(bytes);
+    read::<Record>(bytes);
+    read::<Level>(bytes);
+}
+
+fn record() -> Record {
+    let mut scores = HashMap::new();
+    scores.insert("math".to_owned(), 5);
+    Record {
+        name: "Jane".to_owned(),
+        count: 3,
+        tags: vec!["a".to_owned(), "b".to_owned()],
+        scores,
+        nested: Some(Nested {
+            id: Uuid::new(1, 2),
+            rate: 0.5,
+        }),
+        level: Level::High,
+        price: "-12.345".parse().unwrap(),
+        created: Timestamp {
+            millis: 1_600_000_000_000,
+            nanos: 100,
+        },
+    }
+}
+
+fn valid_encodings() -> Vec<Vec<u8>> {
+    fn bytes(value: &impl WritableType) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        value.write(&mut bytes).unwrap();
+        bytes
+    }
+
+    vec![
+        bytes(&record()),
+        bytes(&vec!["x".to_owned(), "y".to_owned()]),
+        bytes(&vec![1i64, 2, 3]),
+        bytes(&vec![Some(Uuid::new(3, 4)), None].into_boxed_slice()),
+        bytes(&"4.2".parse::<Decimal>().unwrap()),
+        bytes(&Level::Low),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn should_not_panic_on_arbitrary_bytes(bytes in prop::collection::vec(any::<u8>(), 0..256)) {
+        read_all(&bytes);
+    }
+
+    #[test]
+    fn should_not_panic_on_arbitrary_payload(
+        type_code in prop::sample::select(TYPE_CODES),
+        payload in prop::collection::vec(any::<u8>(), 0..256),
+    ) {
+        let mut bytes = vec![type_code];
+        bytes.extend(payload);
+        read_all(&bytes);
+    }
+
+    #[test]
+    fn should_not_panic_on_corrupted_data(
+        encoding in prop::sample::select(valid_encodings()),
+        corruptions in prop::collection::vec((any::<prop::sample::Index>(), any::<u8>()), 1..8),
+        truncation in any::<prop::sample::Index>(),
+    ) {
+        let mut bytes = encoding;
+        for (index, value) in corruptions {
+            let index = index.index(bytes.len());
+            bytes[index] = value;
+        }
+        read_all(&bytes);
+        read_all(&bytes[..truncation.index(bytes.len())]);
+    }
+}
+
+#[test]
+fn should_read_valid_encodings() {
+    let bytes = &valid_encodings()[0];
+    assert_eq!(Record::read(&mut bytes.as_slice()).unwrap(), Some(record()));
+}
+
+#[test]
+fn should_fail_on_negative_length() {
+    // String of -1 length
+    let bytes = [9u8, 0xFF, 0xFF, 0xFF, 0xFF];
+    assert!(String::read(&mut &bytes[..]).is_err());
+    // array of i32::MAX length must not be allocated upfront
+    let bytes = [14u8, 0xFF, 0xFF, 0xFF, 0x7F, 1, 0, 0, 0];
+    assert!(Vec::<i32>::read(&mut &bytes[..]).is_err());
+}
+
+#[test]
+fn should_fail_on_invalid_map_length() {
+    // map of -1 entries is not an empty one
+    let bytes = [25u8, 0xFF, 0xFF, 0xFF, 0xFF, 1];
+    match HashMap::<i32, i32>::read(&mut &bytes[..]) {
+        Err(IgniteError::Protocol(_)) => {}
+        other => panic!("Protocol error expected: {:?}", other),
+    }
+    // map of i32::MAX entries exceeds the array limit before any entry is read
+    let bytes = [
+        25u8, 0xFF, 0xFF, 0xFF, 0x7F, 1, 3, 1, 0, 0, 0, 3, 2, 0, 0, 0,
+    ];
+    match BTreeMap::<i32, i32>::read(&mut &bytes[..]) {
+        Err(IgniteError::Protocol(msg)) => assert!(msg.contains("exceeds the limit"), "{}", msg),
+        other => panic!("Protocol error expected: {:?}", other),
+    }
+}
+
+#[test]
+fn should_fail_on_deep_nesting() {
+    // collection of one collection of one collection... with an int inside
+    let nested = |depth: usize| {
+        let mut bytes = [24u8, 1, 0, 0, 0, 1].repeat(depth);
+        bytes.extend_from_slice(&[3u8, 7, 0, 0, 0]);
+        bytes
+    };
+    let value = IgniteValue::read(&mut nested(127).as_slice()).unwrap();
+    assert!(matches!(value, Some(IgniteValue::Collection(1, _))));
+
+    // 1.2 MB is far below the message limit, but must not overflow the stack
+    match IgniteValue::read(&mut nested(200_000).as_slice()) {
+        Err(IgniteError::Protocol(msg)) => assert!(msg.contains("Nesting depth"), "{}", msg),
+        other => panic!("Protocol error expected: {:?}", other.map(|_| ())),
+    }
+    // the levels left on the error are released
+    assert!(IgniteValue::read(&mut nested(127).as_slice()).is_ok());
+}
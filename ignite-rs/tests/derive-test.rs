@@ -0,0 +1,726 @@
+use std::io::Cursor;
+use std::sync::Arc;
+
+use ignite_rs::affinity::partition;
+use ignite_rs::protocol::binary_type::{with_schemas, BinarySchema, BinaryType, SchemaRegistry};
+use ignite_rs::protocol::complex_obj::IgniteValue;
+use ignite_rs::protocol::{
+    write_i32, write_string, write_u16, write_u8, TypeCode, COMPLEX_OBJ_HEADER_LEN,
+    FLAG_COMPACT_FOOTER, FLAG_HAS_SCHEMA, FLAG_OFFSET_ONE_BYTE, FLAG_OFFSET_TWO_BYTES,
+    FLAG_USER_TYPE,
+};
+use ignite_rs::utils::{
+    bytes_to_java_hashcode, string_to_java_hashcode, FNV1_OFFSET_BASIS, FNV1_PRIME,
+};
+use ignite_rs::{Decimal, Enum, HashCode, IgniteQueryEntity, JavaType, ReadableType, WritableType};
+use ignite_rs_derive::{IgniteEnum, IgniteObj, IgniteQueryEntity};
+
+#[derive(IgniteEnum, Debug, Clone, Copy, PartialEq)]
+enum Color {
+    Red,
+    Green,
+    Blue,
+}
+
+#[derive(IgniteEnum, Debug, Clone, Copy, PartialEq)]
+#[ignite(type_name = "org.example.Weekend")]
+enum Weekend {
+    Saturday,
+    Sunday,
+}
+
+#[derive(IgniteEnum, Debug, Clone, Copy, PartialEq)]
+#[ignite(case_sensitive_ids)]
+enum Shape {
+    Circle,
+}
+
+fn to_bytes(value: &impl WritableType) -> Vec<u8> {
+    let mut bytes: Vec<u8> = Vec::new();
+    value.write(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), value.size());
+    bytes
+}
+
+#[test]
+fn should_write_enum() {
+    let bytes = to_bytes(&Color::Blue);
+    let expected = IgniteValue::Enum(Enum {
+        type_id: string_to_java_hashcode("color"),
+        ordinal: 2,
+    });
+    assert_eq!(
+        IgniteValue::read(&mut Cursor::new(&bytes)).unwrap(),
+        Some(expected)
+    );
+    assert_eq!(
+        Color::read(&mut Cursor::new(&bytes)).unwrap(),
+        Some(Color::Blue)
+    );
+
+    let bytes = to_bytes(&Weekend::Sunday);
+    let expected = IgniteValue::Enum(Enum {
+        type_id: string_to_java_hashcode("org.example.weekend"),
+        ordinal: 1,
+    });
+    assert_eq!(
+        IgniteValue::read(&mut Cursor::new(&bytes)).unwrap(),
+        Some(expected)
+    );
+    assert!(Color::read(&mut Cursor::new(&bytes)).is_err());
+
+    let bytes = to_bytes(&Shape::Circle);
+    let expected = IgniteValue::Enum(Enum {
+        type_id: string_to_java_hashcode("Shape"),
+        ordinal: 0,
+    });
+    assert_eq!(
+        IgniteValue::read(&mut Cursor::new(&bytes)).unwrap(),
+        Some(expected)
+    );
+}
+
+#[test]
+fn should_read_binary_enum() {
+    let mut bytes: Vec<u8> = Vec::new();
+    write_u8(&mut bytes, TypeCode::BinaryEnum as u8).unwrap();
+    write_i32(&mut bytes, string_to_java_hashcode("color")).unwrap();
+    write_i32(&mut bytes, 1).unwrap();
+    assert_eq!(
+        Color::read(&mut Cursor::new(&bytes)).unwrap(),
+        Some(Color::Green)
+    );
+
+    // unregistered type is identified by its class name
+    let mut bytes: Vec<u8> = Vec::new();
+    write_u8(&mut bytes, TypeCode::Enum as u8).unwrap();
+    write_i32(&mut bytes, 0).unwrap();
+    write_u8(&mut bytes, TypeCode::String as u8).unwrap();
+    write_string(&mut bytes, "org.example.Weekend").unwrap();
+    write_i32(&mut bytes, 0).unwrap();
+    assert_eq!(
+        Weekend::read(&mut Cursor::new(&bytes)).unwrap(),
+        Some(Weekend::Saturday)
+    );
+
+    let mut bytes: Vec<u8> = Vec::new();
+    write_u8(&mut bytes, TypeCode::Enum as u8).unwrap();
+    write_i32(&mut bytes, string_to_java_hashcode("color")).unwrap();
+    write_i32(&mut bytes, 3).unwrap();
+    assert!(Color::read(&mut Cursor::new(&bytes)).is_err());
+
+    // class name must be a String
+    let mut bytes: Vec<u8> = Vec::new();
+    write_u8(&mut bytes, TypeCode::Enum as u8).unwrap();
+    write_i32(&mut bytes, 0).unwrap();
+    write_u8(&mut bytes, TypeCode::Int as u8).unwrap();
+    write_i32(&mut bytes, 0).unwrap();
+    write_i32(&mut bytes, 0).unwrap();
+    assert!(Weekend::read(&mut Cursor::new(&bytes)).is_err());
+}
+
+#[test]
+fn should_write_enum_array() {
+    let colors: Box<[Option<Color>]> = vec![Some(Color::Red), None].into_boxed_slice();
+    let bytes = to_bytes(&colors);
+    let type_id = string_to_java_hashcode("color");
+    let expected = IgniteValue::ArrEnum(
+        type_id,
+        vec![
+            IgniteValue::Enum(Enum {
+                type_id,
+                ordinal: 0,
+            }),
+            IgniteValue::Null,
+        ],
+    );
+    assert_eq!(
+        IgniteValue::read(&mut Cursor::new(&bytes)).unwrap(),
+        Some(expected)
+    );
+    assert_eq!(
+        Box::<[Option<Color>]>::read(&mut Cursor::new(&bytes)).unwrap(),
+        Some(colors)
+    );
+
+    let colors = vec![Color::Green, Color::Blue];
+    let bytes = to_bytes(&colors);
+    assert_eq!(
+        Vec::<Color>::read(&mut Cursor::new(&bytes)).unwrap(),
+        Some(colors)
+    );
+}
+
+#[derive(IgniteObj, Debug, Clone, PartialEq)]
+#[ignite(type_name = "com.acme.Person")]
+struct Person {
+    #[ignite(rename = "firstName")]
+    first_name: String,
+    age: i32,
+    #[ignite(skip)]
+    cached: Option<String>,
+    #[ignite(default)]
+    nickname: String,
+}
+
+fn read_i32_at(bytes: &[u8], pos: usize) -> i32 {
+    i32::from_le_bytes([bytes[pos], bytes[pos + 1], bytes[pos + 2], bytes[pos + 3]])
+}
+
+#[test]
+fn should_apply_obj_attributes() {
+    let person = Person {
+        first_name: "John".to_string(),
+        age: 42,
+        cached: Some("cached".to_string()),
+        nickname: "Johnny".to_string(),
+    };
+    let bytes = to_bytes(&person);
+
+    assert_eq!(
+        read_i32_at(&bytes, 4),
+        string_to_java_hashcode("com.acme.person")
+    );
+    let field_ids: Vec<i32> = ["firstname", "age", "nickname"]
+        .iter()
+        .map(|name| string_to_java_hashcode(name))
+        .collect();
+    let schema_offset = read_i32_at(&bytes, 20) as usize;
+    let actual_ids: Vec<i32> = (schema_offset..bytes.len())
+        .step_by(8)
+        .map(|pos| read_i32_at(&bytes, pos))
+        .collect();
+    assert_eq!(actual_ids, field_ids);
+    assert_eq!(read_i32_at(&bytes, 16), get_schema_id(&field_ids));
+
+    let expected = Person {
+        cached: None,
+        ..person
+    };
+    assert_eq!(
+        Person::read(&mut Cursor::new(&bytes)).unwrap(),
+        Some(expected)
+    );
+
+    // NULL is read as Default
+    let person = Person {
+        first_name: "John".to_string(),
+        age: 42,
+        cached: None,
+        nickname: String::new(),
+    };
+    let mut bytes = to_bytes(&person);
+    let schema_offset = read_i32_at(&bytes, 20) as usize;
+    let nickname_pos = read_i32_at(&bytes, schema_offset + 20) as usize;
+    bytes.splice(nickname_pos..nickname_pos + 5, vec![TypeCode::Null as u8]);
+    let len = bytes.len() as i32;
+    bytes[12..16].copy_from_slice(&len.to_le_bytes());
+    bytes[20..24].copy_from_slice(&(schema_offset as i32 - 4).to_le_bytes());
+    assert_eq!(
+        Person::read(&mut Cursor::new(&bytes)).unwrap(),
+        Some(person)
+    );
+}
+
+#[derive(IgniteObj, Debug, Clone, PartialEq)]
+#[ignite(case_sensitive_ids)]
+struct Visit {
+    #[ignite(rename = "pageUrl")]
+    page_url: String,
+    #[ignite(rename = "Count")]
+    count: i32,
+}
+
+#[test]
+fn should_keep_case_of_case_sensitive_ids() {
+    let visit = Visit {
+        page_url: "/".to_string(),
+        count: 1,
+    };
+    let bytes = to_bytes(&visit);
+    assert_eq!(read_i32_at(&bytes, 4), string_to_java_hashcode("Visit"));
+    let field_ids = [
+        string_to_java_hashcode("pageUrl"),
+        string_to_java_hashcode("Count"),
+    ];
+    let schema_offset = read_i32_at(&bytes, 20) as usize;
+    assert_eq!(read_i32_at(&bytes, schema_offset), field_ids[0]);
+    assert_eq!(read_i32_at(&bytes, schema_offset + 8), field_ids[1]);
+    assert_eq!(read_i32_at(&bytes, 16), get_schema_id(&field_ids));
+    assert_eq!(Visit::read(&mut Cursor::new(&bytes)).unwrap(), Some(visit));
+}
+
+fn get_schema_id(field_ids: &[i32]) -> i32 {
+    field_ids.iter().fold(FNV1_OFFSET_BASIS, |acc, field_id| {
+        let mut res = acc;
+        for shift in &[0, 8, 16, 24] {
+            res ^= (field_id >> shift) & 0xFF;
+            res = res.wrapping_mul(FNV1_PRIME);
+        }
+        res
+    })
+}
+
+#[derive(IgniteObj, Debug, Clone, PartialEq)]
+struct Account {
+    id: i64,
+    name: Option<String>,
+    #[ignite(default)]
+    balance: i32,
+}
+
+/// Builds a complex object like Java does: with any field order, offset size and footer type
+fn build_object(
+    type_id: i32,
+    schema_id: i32,
+    fields: &[(i32, Vec<u8>)],
+    offset_size: usize,
+    compact: bool,
+) -> Vec<u8> {
+    let mut data: Vec<u8> = Vec::new();
+    let mut footer: Vec<u8> = Vec::new();
+    for (field_id, value) in fields {
+        let offset = (COMPLEX_OBJ_HEADER_LEN as usize + data.len()) as u32;
+        if !compact {
+            footer.extend_from_slice(&field_id.to_le_bytes());
+        }
+        footer.extend_from_slice(&offset.to_le_bytes()[..offset_size]);
+        data.extend_from_slice(value);
+    }
+
+    let mut flags = FLAG_USER_TYPE | FLAG_HAS_SCHEMA;
+    flags |= match offset_size {
+        1 => FLAG_OFFSET_ONE_BYTE,
+        2 => FLAG_OFFSET_TWO_BYTES,
+        _ => 0,
+    };
+    if compact {
+        flags |= FLAG_COMPACT_FOOTER;
+    }
+
+    let len = COMPLEX_OBJ_HEADER_LEN as usize + data.len() + footer.len();
+    let mut bytes: Vec<u8> = Vec::new();
+    write_u8(&mut bytes, TypeCode::ComplexObj as u8).unwrap();
+    write_u8(&mut bytes, 1).unwrap();
+    write_u16(&mut bytes, flags).unwrap();
+    write_i32(&mut bytes, type_id).unwrap();
+    write_i32(&mut bytes, 0).unwrap(); // hash code
+    write_i32(&mut bytes, len as i32).unwrap();
+    write_i32(&mut bytes, schema_id).unwrap();
+    write_i32(
+        &mut bytes,
+        (COMPLEX_OBJ_HEADER_LEN as usize + data.len()) as i32,
+    )
+    .unwrap();
+    bytes.extend_from_slice(&data);
+    bytes.extend_from_slice(&footer);
+    bytes
+}
+
+#[test]
+fn should_read_any_footer() {
+    let type_id = string_to_java_hashcode("account");
+    let id_field = string_to_java_hashcode("id");
+    let name_field = string_to_java_hashcode("name");
+    let balance_field = string_to_java_hashcode("balance");
+    let fields = vec![
+        (name_field, to_bytes(&"Bob".to_string())),
+        (balance_field, to_bytes(&100i32)),
+        (id_field, to_bytes(&7i64)),
+    ];
+    let expected = Account {
+        id: 7,
+        name: Some("Bob".to_string()),
+        balance: 100,
+    };
+
+    for offset_size in &[1, 2, 4] {
+        let bytes = build_object(type_id, 1, &fields, *offset_size, false);
+        let actual = Account::read(&mut Cursor::new(&bytes)).unwrap();
+        assert_eq!(
+            actual,
+            Some(expected.clone()),
+            "offset size {}",
+            offset_size
+        );
+    }
+
+    // compact footer requires a known schema
+    let bytes = build_object(type_id, 42, &fields, 1, true);
+    assert!(Account::read(&mut Cursor::new(&bytes)).is_err());
+    let registry = Arc::new(SchemaRegistry::default());
+    registry.register(&BinaryType {
+        type_id,
+        type_name: "Account".to_string(),
+        affinity_key_field_name: None,
+        fields: vec![],
+        enum_values: None,
+        schemas: vec![BinarySchema {
+            schema_id: 42,
+            field_ids: vec![name_field, balance_field, id_field],
+        }],
+    });
+    let actual = with_schemas(&registry, || Account::read(&mut Cursor::new(&bytes))).unwrap();
+    assert_eq!(actual, Some(expected));
+
+    // schemas of another registry, e.g. of another cluster's client, aren't used
+    let other = Arc::new(SchemaRegistry::default());
+    assert!(with_schemas(&other, || Account::read(&mut Cursor::new(&bytes))).is_err());
+    assert!(Account::read(&mut Cursor::new(&bytes)).is_err());
+
+    // compact footer of own schema doesn't require metadata
+    let account = Account {
+        id: 1,
+        name: None,
+        balance: 2,
+    };
+    let own_fields = vec![
+        (id_field, to_bytes(&1i64)),
+        (name_field, to_bytes(&None::<String>)),
+        (balance_field, to_bytes(&2i32)),
+    ];
+    let own_schema_id = read_i32_at(&to_bytes(&account), 16);
+    let bytes = build_object(type_id, own_schema_id, &own_fields, 2, true);
+    let actual = Account::read(&mut Cursor::new(&bytes)).unwrap();
+    assert_eq!(actual, Some(account));
+}
+
+#[test]
+fn should_read_missing_fields() {
+    let type_id = string_to_java_hashcode("account");
+    let id_field = string_to_java_hashcode("id");
+    let name_field = string_to_java_hashcode("name");
+
+    // Option and #[ignite(default)] fields fall back to None and Default
+    let fields = vec![(id_field, to_bytes(&7i64))];
+    let bytes = build_object(type_id, 1, &fields, 4, false);
+    let expected = Account {
+        id: 7,
+        name: None,
+        balance: 0,
+    };
+    assert_eq!(
+        Account::read(&mut Cursor::new(&bytes)).unwrap(),
+        Some(expected)
+    );
+
+    // other fields are required
+    let fields = vec![(name_field, to_bytes(&"Bob".to_string()))];
+    let bytes = build_object(type_id, 1, &fields, 4, false);
+    assert!(Account::read(&mut Cursor::new(&bytes)).is_err());
+
+    let fields = vec![(id_field, to_bytes(&None::<i64>))];
+    let bytes = build_object(type_id, 1, &fields, 4, false);
+    assert!(Account::read(&mut Cursor::new(&bytes)).is_err());
+}
+
+#[derive(IgniteObj, Debug, Clone, Copy, PartialEq)]
+#[ignite(hash_code, java_type)]
+struct UserId(i64);
+
+#[derive(IgniteObj, Debug, Clone, PartialEq)]
+struct Point(i32, i32);
+
+#[derive(IgniteObj, Debug, Clone, PartialEq)]
+struct Marker;
+
+#[derive(IgniteObj, Debug, Clone, PartialEq)]
+struct Tagged<T> {
+    id: UserId,
+    value: T,
+}
+
+#[test]
+fn should_write_newtype_transparently() {
+    let bytes = to_bytes(&UserId(42));
+    assert_eq!(bytes, to_bytes(&42i64));
+    assert_eq!(
+        UserId::read(&mut Cursor::new(&bytes)).unwrap(),
+        Some(UserId(42))
+    );
+    assert_eq!(UserId::read(&mut Cursor::new(&[101u8])).unwrap(), None);
+
+    let ids = vec![UserId(1), UserId(2)];
+    let bytes = to_bytes(&ids);
+    assert_eq!(
+        Vec::<UserId>::read(&mut Cursor::new(&bytes)).unwrap(),
+        Some(ids)
+    );
+}
+
+#[test]
+fn should_write_tuple_and_unit_structs() {
+    let point = Point(3, -4);
+    let bytes = to_bytes(&point);
+    assert_eq!(read_i32_at(&bytes, 4), string_to_java_hashcode("point"));
+    // fields are named by position
+    let schema_offset = read_i32_at(&bytes, 20) as usize;
+    assert_eq!(
+        read_i32_at(&bytes, schema_offset),
+        string_to_java_hashcode("0")
+    );
+    assert_eq!(
+        read_i32_at(&bytes, schema_offset + 8),
+        string_to_java_hashcode("1")
+    );
+    assert_eq!(Point::read(&mut Cursor::new(&bytes)).unwrap(), Some(point));
+
+    let bytes = to_bytes(&Marker);
+    assert_eq!(read_i32_at(&bytes, 4), string_to_java_hashcode("marker"));
+    assert_eq!(bytes.len(), COMPLEX_OBJ_HEADER_LEN as usize);
+    assert_eq!(
+        Marker::read(&mut Cursor::new(&bytes)).unwrap(),
+        Some(Marker)
+    );
+}
+
+#[test]
+fn should_write_generic_struct() {
+    let tagged = Tagged {
+        id: UserId(7),
+        value: "seven".to_string(),
+    };
+    let bytes = to_bytes(&tagged);
+    assert_eq!(
+        Tagged::<String>::read(&mut Cursor::new(&bytes)).unwrap(),
+        Some(tagged)
+    );
+
+    let tagged = Tagged {
+        id: UserId(8),
+        value: Point(1, 2),
+    };
+    let bytes = to_bytes(&tagged);
+    assert_eq!(
+        Tagged::<Point>::read(&mut Cursor::new(&bytes)).unwrap(),
+        Some(tagged)
+    );
+}
+
+#[derive(IgniteObj, Debug, Clone, PartialEq)]
+struct OrderKey {
+    order_id: i64,
+    #[ignite(affinity_key, rename = "customerId")]
+    customer_id: UserId,
+}
+
+/// Newtype of a type without a hash code is still a valid value
+#[derive(IgniteObj, Debug, Clone, PartialEq)]
+struct Blob(Vec<u8>);
+
+#[derive(IgniteObj, Debug, Clone, PartialEq)]
+#[ignite(hash_code, java_type)]
+struct Id<T>(T);
+
+#[test]
+fn should_hash_like_java() {
+    let key = OrderKey {
+        order_id: 1,
+        customer_id: UserId(2),
+    };
+    let bytes = to_bytes(&key);
+    // hash code written in the header
+    assert_eq!(key.hash_code(), read_i32_at(&bytes, 8));
+    let data_end = read_i32_at(&bytes, 20) as usize;
+    assert_eq!(
+        key.hash_code(),
+        bytes_to_java_hashcode(&bytes[COMPLEX_OBJ_HEADER_LEN as usize..data_end])
+    );
+
+    assert_eq!(OrderKey::AFFINITY_KEY_FIELD, Some("customerId"));
+    assert_eq!(key.affinity_hash_code(), 2i64.hash_code());
+    assert_eq!(partition(&key, 1024), partition(&2i64, 1024));
+
+    assert_eq!(UserId(2).hash_code(), 2i64.hash_code());
+    assert_eq!(UserId::AFFINITY_KEY_FIELD, None);
+    assert_eq!(Id(2i64).hash_code(), 2i64.hash_code());
+    assert_eq!(Id::<String>::JAVA_TYPE_NAME, "java.lang.String");
+    assert_eq!(Point(1, 2).affinity_hash_code(), Point(1, 2).hash_code());
+    assert_eq!(
+        Color::Green.hash_code(),
+        string_to_java_hashcode("color").wrapping_mul(31) + 1
+    );
+    assert_eq!(to_bytes(&Blob(vec![1])), to_bytes(&vec![1u8]));
+}
+
+/// Value which always fails to be written
+#[derive(Debug, Clone, PartialEq)]
+struct Unencodable;
+
+impl WritableType for Unencodable {
+    fn write(&self, _: &mut dyn std::io::Write) -> std::io::Result<()> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "unencodable",
+        ))
+    }
+
+    fn size(&self) -> usize {
+        0
+    }
+}
+
+impl ReadableType for Unencodable {
+    fn read_unwrapped(
+        _: TypeCode,
+        _: &mut impl std::io::Read,
+    ) -> ignite_rs::error::IgniteResult<Option<Self>> {
+        Ok(None)
+    }
+}
+
+#[derive(IgniteObj, Debug, Clone, PartialEq)]
+struct BrokenKey {
+    id: i64,
+    value: Unencodable,
+}
+
+#[test]
+fn should_hash_unwritable_to_zero() {
+    let key = BrokenKey {
+        id: 1,
+        value: Unencodable,
+    };
+    assert_eq!(key.hash_code(), 0);
+}
+
+#[derive(IgniteObj, IgniteQueryEntity)]
+#[ignite(type_name = "com.acme.Employee")]
+struct Employee {
+    #[ignite(key)]
+    id: i64,
+    #[ignite(index, not_null)]
+    name: String,
+    #[ignite(precision = 10, scale = 2)]
+    salary: Option<Decimal>,
+    #[ignite(index = "dept_city_idx")]
+    department: Option<String>,
+    #[ignite(index = "dept_city_idx", java_type = "java.lang.Object")]
+    city: Option<Color>,
+    #[ignite(skip)]
+    _cached: Option<String>,
+}
+
+#[derive(IgniteObj, IgniteQueryEntity)]
+#[ignite(key_type = "OrderKey", table = "ORDERS")]
+struct Order {
+    #[ignite(key)]
+    order_id: i64,
+    #[ignite(key, rename = "customerId")]
+    customer_id: UserId,
+    amount: f64,
+}
+
+#[test]
+fn should_generate_query_entity() {
+    let entity = Employee::query_entity();
+    assert_eq!(entity.key_type(), "java.lang.Long");
+    assert_eq!(entity.value_type(), "com.acme.Employee");
+    assert_eq!(entity.table(), "Employee");
+    assert_eq!(entity.key_field(), Some("id"));
+
+    let fields: Vec<(&str, &str, bool, bool)> = entity
+        .fields()
+        .iter()
+        .map(|f| (f.name(), f.type_name(), f.is_key_field(), f.is_not_null()))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            ("id", "java.lang.Long", true, false),
+            ("name", "java.lang.String", false, true),
+            ("salary", "java.math.BigDecimal", false, false),
+            ("department", "java.lang.String", false, false),
+            ("city", "java.lang.Object", false, false),
+        ]
+    );
+    assert_eq!(entity.fields()[2].precision(), 10);
+    assert_eq!(entity.fields()[2].scale(), 2);
+    assert_eq!(entity.fields()[0].precision(), -1);
+
+    let indexes: Vec<(&str, &[(String, bool)])> = entity
+        .indexes()
+        .iter()
+        .map(|index| (index.name(), index.fields()))
+        .collect();
+    assert_eq!(
+        indexes,
+        vec![
+            ("employee_name_idx", &[("name".to_string(), false)][..]),
+            (
+                "dept_city_idx",
+                &[
+                    ("department".to_string(), false),
+                    ("city".to_string(), false)
+                ][..]
+            ),
+        ]
+    );
+
+    let entity = Order::query_entity();
+    assert_eq!(entity.key_type(), "OrderKey");
+    assert_eq!(entity.table(), "ORDERS");
+    assert_eq!(entity.key_field(), None);
+    let fields: Vec<(&str, &str, bool)> = entity
+        .fields()
+        .iter()
+        .map(|f| (f.name(), f.type_name(), f.is_key_field()))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            ("order_id", "java.lang.Long", true),
+            ("customerId", "java.lang.Long", true),
+            ("amount", "java.lang.Double", false),
+        ]
+    );
+    assert_eq!(Point::JAVA_TYPE_NAME, "Point");
+    assert_eq!(Color::JAVA_TYPE_NAME, "Color");
+}
+
+/// Type and field ids the server derives from the query entity
+fn query_entity_ids(entity: &ignite_rs::cache::QueryEntity) -> (i32, Vec<i32>) {
+    let type_id = string_to_java_hashcode(&entity.value_type().to_lowercase());
+    let field_ids = entity
+        .fields()
+        .iter()
+        .map(|f| string_to_java_hashcode(&f.name().to_lowercase()))
+        .collect();
+    (type_id, field_ids)
+}
+
+/// Type and field ids written by IgniteObj
+fn written_ids(bytes: &[u8]) -> (i32, Vec<i32>) {
+    let schema_offset = read_i32_at(bytes, 20) as usize;
+    let field_ids = (schema_offset..bytes.len())
+        .step_by(8)
+        .map(|pos| read_i32_at(bytes, pos))
+        .collect();
+    (read_i32_at(bytes, 4), field_ids)
+}
+
+#[test]
+fn should_write_ids_of_query_entity() {
+    let employee = Employee {
+        id: 1,
+        name: "John".to_string(),
+        salary: None,
+        department: None,
+        city: Some(Color::Red),
+        _cached: None,
+    };
+    assert_eq!(
+        written_ids(&to_bytes(&employee)),
+        query_entity_ids(&Employee::query_entity())
+    );
+
+    let order = Order {
+        order_id: 1,
+        customer_id: UserId(2),
+        amount: 3.0,
+    };
+    assert_eq!(
+        written_ids(&to_bytes(&order)),
+        query_entity_ids(&Order::query_entity())
+    );
+}
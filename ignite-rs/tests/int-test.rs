@@ -51,6 +51,10 @@ mod int_test {
             ComplexObjectSchema {
                 type_name: vs.type_name().to_string(),
                 fields: vec![
+                    IgniteField {
+                        name: "ID".to_string(),
+                        r#type: IgniteType::Uuid
+                    },
                     IgniteField {
                         name: "BOOL".to_string(),
                         r#type: IgniteType::Bool
@@ -59,6 +63,10 @@ mod int_test {
                         name: "DEC".to_string(),
                         r#type: IgniteType::Decimal(-1, -1)
                     },
+                    IgniteField {
+                        name: "DOUBLE".to_string(),
+                        r#type: IgniteType::Double
+                    },
                     IgniteField {
                         name: "INT".to_string(),
                         r#type: IgniteType::Int
@@ -67,10 +75,18 @@ mod int_test {
                         name: "NULL_INT".to_string(),
                         r#type: IgniteType::Int
                     },
+                    IgniteField {
+                        name: "REAL".to_string(),
+                        r#type: IgniteType::Float
+                    },
                     IgniteField {
                         name: "SMALL".to_string(),
                         r#type: IgniteType::Short
                     },
+                    IgniteField {
+                        name: "TINY".to_string(),
+                        r#type: IgniteType::Byte
+                    },
                     IgniteField {
                         name: "CHAR".to_string(),
                         r#type: IgniteType::String
@@ -79,10 +95,22 @@ mod int_test {
                         name: "VAR".to_string(),
                         r#type: IgniteType::String
                     },
+                    IgniteField {
+                        name: "DATE".to_string(),
+                        r#type: IgniteType::Date
+                    },
+                    IgniteField {
+                        name: "TIME".to_string(),
+                        r#type: IgniteType::Time
+                    },
                     IgniteField {
                         name: "TS".to_string(),
                         r#type: IgniteType::Timestamp
-                    }
+                    },
+                    IgniteField {
+                        name: "BIN".to_string(),
+                        r#type: IgniteType::ArrByte
+                    },
                 ]
             }
         );
@@ -113,14 +141,21 @@ mod int_test {
                     fields: vec![],
                 }),
                 values: vec![
+                    IgniteValue::Uuid("123e4567-e89b-12d3-a456-426614174000".parse().unwrap()),
                     IgniteValue::Bool(true),
-                    IgniteValue::Decimal(1, vec![20]),
+                    IgniteValue::Decimal("2.0".parse().unwrap()),
+                    IgniteValue::Double(5.5),
                     IgniteValue::Int(3),
                     IgniteValue::Null,
+                    IgniteValue::Float(6.5),
                     IgniteValue::Short(4),
+                    IgniteValue::Byte(7),
                     IgniteValue::String("c".to_string()),
                     IgniteValue::String("varchar".to_string()),
+                    IgniteValue::Date(1687305600000),
+                    IgniteValue::Time(45296000),
                     IgniteValue::Timestamp(1687350896000, 0),
+                    IgniteValue::ArrByte(vec![1, 2]),
                 ],
             }),
         )];
@@ -0,0 +1,245 @@
+use std::time::Duration;
+
+use ignite_rs::cache::{CacheConfiguration, QueryEntity, QueryField};
+use ignite_rs::error::{
+    IgniteError, STATUS_CACHE_DOES_NOT_EXIST, STATUS_CACHE_EXISTS, STATUS_FAILED,
+};
+use ignite_rs::mock::{Fault, MockServer, OpCode};
+use ignite_rs::{Client, Ignite};
+use ignite_rs_derive::IgniteObj;
+
+#[derive(IgniteObj, Clone, Debug, PartialEq)]
+struct Person {
+    name: String,
+    age: i32,
+    tags: Vec<String>,
+}
+
+fn connect(server: &MockServer) -> Client {
+    ignite_rs::new_client(server.config()).unwrap()
+}
+
+fn status<T>(result: Result<T, IgniteError>) -> Option<i32> {
+    result.err().and_then(|err| err.status())
+}
+
+#[test]
+fn should_manage_caches() {
+    let server = MockServer::new();
+    let mut client = connect(&server);
+    assert_eq!(client.get_cache_names().unwrap(), Vec::<String>::new());
+
+    client.create_cache::<i32, i32>("a").unwrap();
+    let created = client.create_cache::<i32, i32>("a");
+    assert_eq!(status(created), Some(STATUS_CACHE_EXISTS));
+    client.get_or_create_cache::<i32, i32>("a").unwrap();
+    client.get_or_create_cache::<i32, i32>("b").unwrap();
+
+    let mut config = CacheConfiguration::new("c");
+    config.num_backup = 2;
+    config.sql_schema = Some("PUBLIC".to_owned());
+    config.query_entities = Some(vec![QueryEntity::builder("java.lang.Long", "Person")
+        .field(QueryField::builder("name", "java.lang.String").build())
+        .build()]);
+    client
+        .create_cache_with_config::<i64, i32>(&config)
+        .unwrap();
+    client
+        .get_or_create_cache_with_config::<i64, i32>(&config)
+        .unwrap();
+
+    let actual = client.get_cache_config("c").unwrap();
+    assert_eq!(actual.name, "c");
+    assert_eq!(actual.num_backup, 2);
+    assert_eq!(actual.sql_schema.as_deref(), Some("PUBLIC"));
+    let entities = actual.query_entities.unwrap();
+    assert_eq!(entities[0].table(), "Person");
+    assert_eq!(entities[0].fields()[0].name(), "name");
+    assert_eq!(client.get_cache_config("a").unwrap().num_backup, 0);
+
+    assert_eq!(client.get_cache_names().unwrap(), vec!["a", "b", "c"]);
+    let cache = client.get_or_create_cache::<i32, i32>("a").unwrap();
+    client.destroy_cache("a").unwrap();
+    assert_eq!(client.get_cache_names().unwrap(), vec!["b", "c"]);
+    assert_eq!(
+        status(client.destroy_cache("a")),
+        Some(STATUS_CACHE_DOES_NOT_EXIST)
+    );
+    assert_eq!(
+        status(client.get_cache_config("a")),
+        Some(STATUS_CACHE_DOES_NOT_EXIST)
+    );
+    assert_eq!(status(cache.get(&1)), Some(STATUS_CACHE_DOES_NOT_EXIST));
+}
+
+#[test]
+fn should_perform_key_value_operations() {
+    let server = MockServer::new();
+    let mut client = connect(&server);
+    let cache = client.get_or_create_cache::<i32, String>("cache").unwrap();
+    let one = "one".to_owned();
+    let two = "two".to_owned();
+    let three = "three".to_owned();
+
+    assert_eq!(cache.get(&1).unwrap(), None);
+    cache.put(&1, &one).unwrap();
+    assert_eq!(cache.get(&1).unwrap(), Some(one.clone()));
+    cache
+        .put_all(&[(2, two.clone()), (3, three.clone())])
+        .unwrap();
+    assert_eq!(
+        cache.get_all(&[1, 3, 4]).unwrap(),
+        vec![(Some(1), Some(one.clone())), (Some(3), Some(three.clone()))]
+    );
+    assert_eq!(cache.get_size().unwrap(), 3);
+
+    assert!(cache.contains_key(&2).unwrap());
+    assert!(!cache.contains_key(&4).unwrap());
+    assert!(cache.contains_keys(&[1, 2]).unwrap());
+    assert!(!cache.contains_keys(&[1, 4]).unwrap());
+
+    assert!(!cache.put_if_absent(&1, &two).unwrap());
+    assert!(cache.put_if_absent(&4, &two).unwrap());
+    assert_eq!(cache.get_and_put(&4, &three).unwrap(), Some(two.clone()));
+    assert_eq!(
+        cache.get_and_put_if_absent(&4, &one).unwrap(),
+        Some(three.clone())
+    );
+    assert_eq!(cache.get_and_put_if_absent(&5, &one).unwrap(), None);
+    assert_eq!(cache.get_and_replace(&5, &two).unwrap(), Some(one.clone()));
+    assert_eq!(cache.get_and_replace(&6, &two).unwrap(), None);
+    assert!(!cache.contains_key(&6).unwrap());
+
+    assert!(cache.replace(&5, &three).unwrap());
+    assert!(!cache.replace(&6, &three).unwrap());
+    assert!(!cache.replace_if_equals(&5, &one, &two).unwrap());
+    assert!(cache.replace_if_equals(&5, &three, &two).unwrap());
+    assert_eq!(cache.get(&5).unwrap(), Some(two.clone()));
+
+    assert_eq!(cache.get_and_remove(&5).unwrap(), Some(two.clone()));
+    assert!(!cache.remove_if_equals(&4, &one).unwrap());
+    assert!(cache.remove_if_equals(&4, &three).unwrap());
+    assert!(cache.remove_key(&3).unwrap());
+    assert!(!cache.remove_key(&3).unwrap());
+    assert_eq!(
+        cache.query_scan(10).unwrap(),
+        vec![(Some(1), Some(one.clone())), (Some(2), Some(two.clone()))]
+    );
+
+    cache.clear_key(&1).unwrap();
+    assert_eq!(cache.get_size().unwrap(), 1);
+    cache.put_all(&[(1, one.clone()), (3, three)]).unwrap();
+    cache.clear_keys(&[1, 2]).unwrap();
+    assert_eq!(cache.get_size().unwrap(), 1);
+    cache.remove_keys(&[3]).unwrap();
+    assert_eq!(cache.get_size().unwrap(), 0);
+    cache.put(&1, &one).unwrap();
+    cache.remove_all().unwrap();
+    assert_eq!(cache.get_size().unwrap(), 0);
+    cache.put(&1, &one).unwrap();
+    cache.clear().unwrap();
+    assert_eq!(cache.get_size().unwrap(), 0);
+}
+
+#[test]
+fn should_store_complex_objects() {
+    let server = MockServer::new();
+    let mut client = connect(&server);
+    let cache = client
+        .get_or_create_cache::<String, Person>("people")
+        .unwrap();
+    let person = Person {
+        name: "Alice".to_owned(),
+        age: 42,
+        tags: vec!["admin".to_owned()],
+    };
+    cache.put(&person.name, &person).unwrap();
+    assert_eq!(
+        cache.get(&"Alice".to_owned()).unwrap(),
+        Some(person.clone())
+    );
+
+    // the caches are shared by the clients of the server
+    let other = connect(&server)
+        .get_or_create_cache::<String, Person>("people")
+        .unwrap();
+    assert!(other
+        .replace_if_equals(&person.name, &person, &person)
+        .unwrap());
+    assert_eq!(server.connections(), 2);
+}
+
+#[test]
+fn should_inject_errors() {
+    let server = MockServer::new();
+    let mut client = connect(&server);
+    let cache = client.get_or_create_cache::<i32, i32>("cache").unwrap();
+    cache.put(&1, &2).unwrap();
+
+    server.inject(
+        OpCode::CacheGet,
+        Fault::Error(STATUS_FAILED, "boom".to_owned()),
+    );
+    match cache.get(&1) {
+        Err(IgniteError::Server { status, message }) => {
+            assert_eq!(status, STATUS_FAILED);
+            assert_eq!(message, "boom");
+        }
+        other => panic!("Server error expected: {:?}", other),
+    }
+    // only the next request fails
+    assert_eq!(cache.get(&1).unwrap(), Some(2));
+    assert_eq!(
+        server.requests(),
+        vec![
+            OpCode::CacheGetOrCreateWithName,
+            OpCode::CachePut,
+            OpCode::CacheGet,
+            OpCode::CacheGet
+        ]
+    );
+}
+
+#[test]
+fn should_inject_latency() {
+    let server = MockServer::new();
+    let mut client = connect(&server);
+    let cache = client.get_or_create_cache::<i32, i32>("cache").unwrap();
+
+    let timeout = Duration::from_millis(50);
+    server.set_latency(Some(Duration::from_millis(500)));
+    match cache.with_timeout(timeout).get(&1) {
+        Err(IgniteError::Timeout(actual)) => assert_eq!(actual, timeout),
+        other => panic!("Timeout expected: {:?}", other),
+    }
+
+    server.set_latency(None);
+    server.inject(OpCode::CacheGet, Fault::Delay(Duration::from_millis(500)));
+    assert!(matches!(
+        cache.with_timeout(timeout).get(&1),
+        Err(IgniteError::Timeout(_))
+    ));
+
+    // the timed out connections are replaced
+    assert_eq!(cache.with_timeout(timeout).get(&1).unwrap(), None);
+    assert_eq!(server.connections(), 3);
+}
+
+#[test]
+fn should_authenticate() {
+    let server = MockServer::new().with_credentials("ignite", "secret");
+
+    let mut conf = server.config();
+    conf.username = Some("ignite".to_owned());
+    conf.password = Some("wrong".to_owned());
+    match ignite_rs::new_client(conf) {
+        Err(IgniteError::Authentication(_)) => {}
+        other => panic!("Authentication error expected: {:?}", other.map(|_| ())),
+    }
+
+    let mut conf = server.config();
+    conf.username = Some("ignite".to_owned());
+    conf.password = Some("secret".to_owned());
+    let mut client = ignite_rs::new_client(conf).unwrap();
+    assert_eq!(client.get_cache_names().unwrap(), Vec::<String>::new());
+}
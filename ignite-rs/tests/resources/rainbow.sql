@@ -1,23 +1,24 @@
 -- https://ignite.apache.org/docs/latest/sql-reference/data-types
 create table rainbow (
---      id UUID, -- java.util.UUID -- TODO: add support for this type
+     id UUID, -- java.util.UUID
      big BIGINT, -- java.lang.Long
      bool BOOLEAN, -- java.lang.Boolean
      dec DECIMAL, -- java.math.BigDecimal
---      double DOUBLE, -- java.lang.Double -- TODO: add support for this type
+     double DOUBLE, -- java.lang.Double
      int INT, -- java.lang.Integer
      null_int INT, -- java.lang.Integer
---      real REAL, -- java.lang.Float -- TODO: add support for this type
+     real REAL, -- java.lang.Float
      small SMALLINT, -- java.lang.Short
---      tiny TINYINT, -- java.lang.Byte -- TODO: add support for this type
+     tiny TINYINT, -- java.lang.Byte
      char CHAR, -- java.lang.String
      var VARCHAR, -- java.lang.String
---      date DATE, -- java.sql.Date -- TODO: add support for this type
---      time TIME, -- java.sql.Time -- TODO: add support for this type
+     date DATE, -- java.sql.Date
+     time TIME, -- java.sql.Time
      ts TIMESTAMP, -- java.sql.Timestamp
---      bin BINARY, -- byte[] -- TODO: add support for this type
+     bin BINARY, -- byte[]
      primary key (big)
 );
 
-insert into rainbow (big, bool, dec, int, null_int, small, char, var, ts) values
-    (1, true, 2.0, 3, null, 4, 'c', 'varchar', timestamp '2023-06-21 12:34:56 UTC');
+insert into rainbow (id, big, bool, dec, double, int, null_int, real, small, tiny, char, var, date, time, ts, bin) values
+    ('123e4567-e89b-12d3-a456-426614174000', 1, true, 2.0, 5.5, 3, null, 6.5, 4, 7, 'c', 'varchar',
+     date '2023-06-21', time '12:34:56', timestamp '2023-06-21 12:34:56 UTC', x'0102');
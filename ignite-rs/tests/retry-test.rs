@@ -0,0 +1,158 @@
+use std::io;
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use ignite_rs::error::{IgniteError, IgniteResult};
+use ignite_rs::mock::{Fault, MockServer, OpCode};
+use ignite_rs::protocol::TypeCode;
+use ignite_rs::retry::FixedRetry;
+use ignite_rs::transport::TransportStream;
+use ignite_rs::{ClientConfig, Ignite, ReadableType, WritableType};
+
+fn retrying_config(server: &MockServer) -> ClientConfig {
+    let mut conf = server.config();
+    conf.retry_policy = Arc::new(FixedRetry::new(3, Duration::from_millis(10)));
+    conf
+}
+
+/// Number of the operation's requests the server has received
+fn requests(server: &MockServer, op: OpCode) -> usize {
+    server
+        .requests()
+        .into_iter()
+        .filter(|req| *req == op)
+        .count()
+}
+
+#[test]
+fn should_retry_idempotent_operations() {
+    let server = MockServer::new();
+    let mut client = ignite_rs::new_client(retrying_config(&server)).unwrap();
+    let cache = client.get_or_create_cache::<i32, i32>("cache").unwrap();
+
+    // reconnected and retried
+    server.inject(OpCode::CacheGet, Fault::Disconnect);
+    assert_eq!(cache.get(&1).unwrap(), None);
+    assert_eq!(server.connections(), 2);
+
+    // not idempotent, so the error is returned
+    server.inject(OpCode::CacheGetAndPut, Fault::Disconnect);
+    let err = cache.get_and_put(&1, &2).unwrap_err();
+    assert!(err.is_connection_error(), "{}", err);
+    assert_eq!(requests(&server, OpCode::CacheGetAndPut), 1);
+
+    // the next request reconnects
+    cache.put(&1, &2).unwrap();
+    assert_eq!(server.connections(), 3);
+}
+
+#[test]
+fn should_not_retry_by_default() {
+    let server = MockServer::new();
+    let mut client = ignite_rs::new_client(server.config()).unwrap();
+    let cache = client.get_or_create_cache::<i32, i32>("cache").unwrap();
+
+    server.inject(OpCode::CacheGet, Fault::Disconnect);
+    let err = cache.get(&1).unwrap_err();
+    assert!(err.is_connection_error(), "{}", err);
+    assert_eq!(cache.get(&1).unwrap(), None);
+    assert_eq!(requests(&server, OpCode::CacheGet), 2);
+}
+
+#[test]
+fn should_not_retry_scan_query() {
+    let server = MockServer::new();
+    let mut client = ignite_rs::new_client(retrying_config(&server)).unwrap();
+    let cache = client.get_or_create_cache::<i32, i32>("cache").unwrap();
+
+    // the repeated scan would open another cursor on the node
+    server.inject(OpCode::QueryScan, Fault::Disconnect);
+    let err = cache.query_scan(10).unwrap_err();
+    assert!(err.is_connection_error(), "{}", err);
+    assert_eq!(requests(&server, OpCode::QueryScan), 1);
+    assert_eq!(cache.get(&1).unwrap(), None);
+}
+
+#[test]
+fn should_not_retry_malformed_response() {
+    let server = MockServer::new();
+    let mut client = ignite_rs::new_client(retrying_config(&server)).unwrap();
+    let cache = client.get_or_create_cache::<i32, i32>("cache").unwrap();
+    cache.put(&1, &2).unwrap();
+
+    // the response is read as a whole, so its missing value is not a connection failure
+    server.inject(OpCode::CacheGet, Fault::Truncate);
+    match cache.get(&1) {
+        Err(err @ IgniteError::Protocol(_)) => assert!(!err.is_connection_error()),
+        other => panic!("Protocol error expected: {:?}", other),
+    }
+    assert_eq!(requests(&server, OpCode::CacheGet), 1);
+    assert_eq!(cache.get(&1).unwrap(), Some(2));
+}
+
+/// Value which always fails to be written
+struct Unencodable;
+
+impl WritableType for Unencodable {
+    fn write(&self, _: &mut dyn Write) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::InvalidInput, "unencodable"))
+    }
+
+    fn size(&self) -> usize {
+        0
+    }
+}
+
+impl ReadableType for Unencodable {
+    fn read_unwrapped(_: TypeCode, _: &mut impl Read) -> IgniteResult<Option<Self>> {
+        Ok(None)
+    }
+}
+
+#[test]
+fn should_not_retry_encoding_failures() {
+    let server = MockServer::new();
+    let mut client = ignite_rs::new_client(retrying_config(&server)).unwrap();
+    let cache = client
+        .get_or_create_cache::<i32, Unencodable>("cache")
+        .unwrap();
+
+    // the value fails again after reconnecting, so it's returned at once
+    match cache.put(&1, &Unencodable) {
+        Err(err @ IgniteError::Io(_)) => assert!(!err.is_connection_error()),
+        other => panic!("I/O error expected: {:?}", other.map(|_| ())),
+    }
+    assert_eq!(server.connections(), 1);
+    assert_eq!(cache.get(&1).unwrap().map(|_| ()), None);
+    assert_eq!(server.connections(), 2);
+}
+
+#[test]
+fn should_not_retry_tls_failures() {
+    // the first connection breaks on get, the next ones fail the TLS handshake
+    let server = MockServer::new();
+    let connections = Arc::new(AtomicUsize::new(0));
+    let (node, counter) = (server.clone(), connections.clone());
+    let transport = move |_: &str, _: Option<Duration>| -> IgniteResult<Box<dyn TransportStream>> {
+        if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+            return Ok(Box::new(node.connect()));
+        }
+        Err(IgniteError::Tls(
+            "invalid peer certificate: UnknownIssuer".into(),
+        ))
+    };
+    let mut conf = retrying_config(&server);
+    conf.transport = Some(Arc::new(transport));
+    let mut client = ignite_rs::new_client(conf).unwrap();
+    let cache = client.get_or_create_cache::<i32, i32>("cache").unwrap();
+
+    // the handshake can't succeed, so it's not repeated
+    server.inject(OpCode::CacheGet, Fault::Disconnect);
+    match cache.get(&1) {
+        Err(err @ IgniteError::Tls(_)) => assert!(!err.is_connection_error()),
+        other => panic!("TLS error expected: {:?}", other),
+    }
+    assert_eq!(connections.load(Ordering::SeqCst), 2);
+}
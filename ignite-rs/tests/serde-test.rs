@@ -0,0 +1,188 @@
+#![cfg(feature = "serde")]
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use ignite_rs::protocol::TypeCode;
+use ignite_rs::serde_binary::{from_reader, to_bytes, Serde};
+use ignite_rs::{Date, Decimal, ReadableType, Timestamp, Uuid, WritableType};
+use ignite_rs_derive::{IgniteEnum, IgniteObj};
+
+#[derive(IgniteObj, Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Person {
+    name: String,
+    age: i32,
+    email: Option<String>,
+    tags: Vec<String>,
+}
+
+#[derive(IgniteEnum, Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+enum Color {
+    Red,
+    Green,
+    Blue,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Rect(f64, f64),
+    Polygon { points: Vec<Point> },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+struct Point(i32, i32);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Marker;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Drawing {
+    id: Uuid,
+    created: Timestamp,
+    day: Date,
+    price: Decimal,
+    owner: Person,
+    color: Color,
+    shapes: Vec<Shape>,
+    layers: HashMap<String, i64>,
+    origin: Point,
+    marker: Marker,
+    small: i8,
+    big: u64,
+    letter: char,
+    pair: (u8, bool),
+    nothing: Option<i32>,
+}
+
+fn person() -> Person {
+    Person {
+        name: "Jane".to_owned(),
+        age: 42,
+        email: None,
+        tags: vec!["admin".to_owned(), "dev".to_owned()],
+    }
+}
+
+fn written(value: &impl WritableType) -> Vec<u8> {
+    let mut bytes: Vec<u8> = Vec::new();
+    value.write(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), value.size());
+    bytes
+}
+
+#[test]
+fn should_write_like_derive() {
+    let person = person();
+    let bytes = to_bytes(&person).unwrap();
+    assert_eq!(bytes, written(&person));
+    assert_eq!(to_bytes(&Color::Blue).unwrap(), written(&Color::Blue));
+
+    // both ways are readable by each other
+    let read: Person = from_reader(&mut written(&person).as_slice()).unwrap();
+    assert_eq!(read, person);
+    let read = Person::read(&mut bytes.as_slice()).unwrap().unwrap();
+    assert_eq!(read, person);
+}
+
+#[test]
+fn should_round_trip() {
+    let mut layers = HashMap::new();
+    layers.insert("background".to_owned(), 1);
+    layers.insert("foreground".to_owned(), 2);
+    let drawing = Drawing {
+        id: "123e4567-e89b-12d3-a456-426614174000".parse().unwrap(),
+        created: Timestamp {
+            millis: 1_600_000_000_000,
+            nanos: 123_456,
+        },
+        day: Date {
+            millis: 1_599_955_200_000,
+        },
+        price: "-12.345".parse().unwrap(),
+        owner: person(),
+        color: Color::Green,
+        shapes: vec![
+            Shape::Empty,
+            Shape::Circle(1.5),
+            Shape::Rect(2.0, 3.0),
+            Shape::Polygon {
+                points: vec![Point(0, 0), Point(1, 1)],
+            },
+        ],
+        layers,
+        origin: Point(-1, 7),
+        marker: Marker,
+        small: -5,
+        big: 1 << 40,
+        letter: 'Ж',
+        pair: (255, true),
+        nothing: None,
+    };
+
+    let bytes = to_bytes(&drawing).unwrap();
+    assert_eq!(bytes[0], TypeCode::ComplexObj as u8);
+    let read: Drawing = from_reader(&mut bytes.as_slice()).unwrap();
+    assert_eq!(read, drawing);
+}
+
+#[test]
+fn should_write_crate_types_natively() {
+    let uuid = Uuid::new(1, 2);
+    assert_eq!(to_bytes(&uuid).unwrap(), written(&uuid));
+    let timestamp = Timestamp {
+        millis: 10,
+        nanos: 20,
+    };
+    assert_eq!(to_bytes(&timestamp).unwrap(), written(&timestamp));
+    let decimal: Decimal = "3.14".parse().unwrap();
+    assert_eq!(to_bytes(&decimal).unwrap(), written(&decimal));
+
+    // strings are written for UUIDs and decimals by other serializers
+    let read: String = from_reader(&mut written(&uuid).as_slice()).unwrap();
+    assert_eq!(read, uuid.to_string());
+}
+
+#[test]
+fn should_read_arrays_as_sequences() {
+    let ints: Vec<i32> = from_reader(&mut written(&vec![1i32, 2, 3]).as_slice()).unwrap();
+    assert_eq!(ints, vec![1, 2, 3]);
+    let bytes: Vec<u8> = from_reader(&mut written(&vec![1u8, 2]).as_slice()).unwrap();
+    assert_eq!(bytes, vec![1, 2]);
+    let strings = vec![Some("a".to_owned()), None].into_boxed_slice();
+    let read: Vec<Option<String>> = from_reader(&mut written(&strings).as_slice()).unwrap();
+    assert_eq!(read, vec![Some("a".to_owned()), None]);
+}
+
+#[test]
+fn should_wrap_serde_types() {
+    let value = Serde(vec![Point(1, 2)]);
+    let bytes = written(&value);
+    let read = Serde::<Vec<Point>>::read(&mut bytes.as_slice()).unwrap();
+    assert_eq!(read, Some(value));
+
+    let null = written(&None::<i32>);
+    let read = Serde::<Point>::read(&mut null.as_slice()).unwrap();
+    assert_eq!(read, None);
+    let read = Serde::<Option<Point>>::read(&mut null.as_slice()).unwrap();
+    assert_eq!(read, Some(Serde(None)));
+}
+
+#[test]
+fn should_fail_on_invalid_data() {
+    #[derive(Serialize, Deserialize, Debug)]
+    struct Other {
+        name: String,
+    }
+
+    let bytes = to_bytes(&person()).unwrap();
+    assert!(from_reader::<Other>(&mut bytes.as_slice()).is_err());
+    assert!(from_reader::<i32>(&mut bytes.as_slice()).is_err());
+    assert!(to_bytes(&u64::MAX).is_err());
+    assert!(to_bytes(&'😀').is_err());
+
+    let truncated = &bytes[..bytes.len() - 1];
+    assert!(from_reader::<Person>(&mut &truncated[..]).is_err());
+}
@@ -0,0 +1,67 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use ignite_rs::error::IgniteError;
+use ignite_rs::mock::{Fault, MockServer, OpCode};
+use ignite_rs::Ignite;
+
+fn assert_timeout(err: IgniteError, timeout: Duration) {
+    match err {
+        IgniteError::Timeout(actual) => assert_eq!(actual, timeout),
+        other => panic!("Timeout expected: {}", other),
+    }
+}
+
+#[test]
+fn should_replace_connection_after_timeout() {
+    let server = MockServer::new();
+    let mut client = ignite_rs::new_client(server.config()).unwrap();
+    let cache = client.get_or_create_cache::<i32, i32>("cache").unwrap();
+
+    server.inject(OpCode::CacheGet, Fault::Delay(Duration::from_millis(500)));
+    let timeout = Duration::from_millis(100);
+    let start = Instant::now();
+    let err = cache.with_timeout(timeout).get(&1).unwrap_err();
+    assert_timeout(err, timeout);
+    assert!(start.elapsed() < Duration::from_millis(400));
+
+    // the late response of the first connection is not read
+    assert_eq!(cache.get(&1).unwrap(), None);
+    assert_eq!(server.connections(), 2);
+}
+
+#[test]
+fn should_wait_for_connection_within_timeout() {
+    let server = MockServer::new();
+    let mut client = ignite_rs::new_client(server.config()).unwrap();
+    let cache = client.get_or_create_cache::<i32, i32>("cache").unwrap();
+
+    // holds the connection for 500 ms
+    server.inject(OpCode::CacheGet, Fault::Delay(Duration::from_millis(500)));
+    let slow_cache = cache.with_timeout(Duration::from_secs(5));
+    let slow = thread::spawn(move || slow_cache.get(&1).map(|_| ()).map_err(|e| e.to_string()));
+    thread::sleep(Duration::from_millis(100));
+
+    let timeout = Duration::from_millis(100);
+    assert_timeout(cache.with_timeout(timeout).get(&1).unwrap_err(), timeout);
+
+    // the slow request isn't affected
+    slow.join().unwrap().unwrap();
+    assert_eq!(cache.get(&1).unwrap(), None);
+    assert_eq!(server.connections(), 1);
+}
+
+#[test]
+fn should_use_default_timeout() {
+    let server = MockServer::new();
+    let timeout = Duration::from_millis(100);
+    let mut conf = server.config();
+    conf.operation_timeout = Some(timeout);
+    let mut client = ignite_rs::new_client(conf).unwrap();
+
+    server.inject(
+        OpCode::CacheGetNames,
+        Fault::Delay(Duration::from_millis(500)),
+    );
+    assert_timeout(client.get_cache_names().unwrap_err(), timeout);
+}
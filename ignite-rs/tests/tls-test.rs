@@ -0,0 +1,272 @@
+#![cfg(any(feature = "rustls-tls", feature = "native-tls"))]
+
+mod common;
+
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use ignite_rs::error::{IgniteError, IgniteResult};
+use ignite_rs::tls::{TlsBackend, TlsConfig, TlsConfigBuilder};
+use ignite_rs::transport::{duplex, TransportStream};
+use ignite_rs::{ClientConfig, Ignite};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{RootCertStore, ServerConfig, ServerConnection, StreamOwned};
+
+use common::{read_message, write_response, HANDSHAKE_OK};
+
+const RESOURCES: &str = "tests/resources/tls";
+
+fn resource(name: &str) -> String {
+    format!("{}/{}", RESOURCES, name)
+}
+
+/// Backends enabled by the features
+fn backends() -> Vec<TlsBackend> {
+    vec![
+        #[cfg(feature = "rustls-tls")]
+        TlsBackend::Rustls,
+        #[cfg(feature = "native-tls")]
+        TlsBackend::NativeTls,
+    ]
+}
+
+fn read_certs(name: &str) -> Vec<CertificateDer<'static>> {
+    CertificateDer::pem_file_iter(resource(name))
+        .unwrap()
+        .map(Result::unwrap)
+        .collect()
+}
+
+fn server_config(client_auth: bool) -> Arc<ServerConfig> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .unwrap();
+    let builder = if client_auth {
+        let mut roots = RootCertStore::empty();
+        roots.add_parsable_certificates(read_certs("ca.pem"));
+        let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+            .build()
+            .unwrap();
+        builder.with_client_cert_verifier(verifier)
+    } else {
+        builder.with_no_client_auth()
+    };
+    let key = PrivateKeyDer::from_pem_file(resource("server.key")).unwrap();
+    Arc::new(
+        builder
+            .with_single_cert(read_certs("server.pem"), key)
+            .unwrap(),
+    )
+}
+
+/// Accepts the handshake and answers every request with an empty list of cache names.
+/// Sends the SNI hostname of the connection
+fn serve<S: Read + Write + Send + 'static>(
+    conf: Arc<ServerConfig>,
+    stream: S,
+    sni_sender: Sender<Option<String>>,
+) {
+    let mut stream = StreamOwned::new(ServerConnection::new(conf).unwrap(), stream);
+    thread::spawn(move || {
+        // fails if the TLS handshake fails
+        if read_message(&mut stream).is_err() {
+            return;
+        }
+        let hostname = stream.conn.server_name().map(str::to_owned);
+        let _ = sni_sender.send(hostname); // not every test checks it
+        stream.write_all(HANDSHAKE_OK).unwrap();
+        stream.flush().unwrap();
+        while let Ok(request) = read_message(&mut stream) {
+            write_response(&mut stream, &request, &[0, 0, 0, 0]).unwrap(); // no names
+        }
+    });
+}
+
+/// TLS server with the certificate of `localhost`
+fn start_server(client_auth: bool) -> (String, Receiver<Option<String>>) {
+    let conf = server_config(client_auth);
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let (sni_sender, sni) = channel();
+    thread::spawn(move || {
+        for tcp_stream in listener.incoming().map(Result::unwrap) {
+            serve(conf.clone(), tcp_stream, sni_sender.clone());
+        }
+    });
+    (addr, sni)
+}
+
+fn connect(addr: &str, tls: TlsConfig) -> Result<Vec<String>, IgniteError> {
+    let conf = ClientConfig::builder().address(addr).tls(tls).build()?;
+    ignite_rs::new_client(conf)?.get_cache_names()
+}
+
+fn assert_tls_error(result: Result<Vec<String>, IgniteError>) {
+    match result {
+        Err(err @ IgniteError::Tls(_)) => assert!(!err.is_connection_error()),
+        Err(err) => panic!("TLS error expected: {:?}", err),
+        Ok(_) => panic!("TLS error expected"),
+    }
+}
+
+#[test]
+fn should_connect_with_client_certificate() {
+    let (addr, sni) = start_server(true);
+    for backend in backends() {
+        let tls = TlsConfig::builder()
+            .backend(backend)
+            .ca_file(resource("ca.pem"))
+            .client_cert_files(resource("client.pem"), resource("client.key"))
+            .server_name("localhost")
+            .build()
+            .unwrap();
+
+        assert_eq!(connect(&addr, tls).unwrap(), Vec::<String>::new());
+        assert_eq!(sni.recv().unwrap().as_deref(), Some("localhost"));
+    }
+}
+
+#[test]
+fn should_load_pem_contents() {
+    let (addr, _) = start_server(true);
+    for backend in backends() {
+        let tls = TlsConfig::builder()
+            .backend(backend)
+            .ca_pem(&fs::read(resource("ca.pem")).unwrap())
+            .client_cert_pem(
+                &fs::read(resource("client.pem")).unwrap(),
+                &fs::read(resource("client.key")).unwrap(),
+            )
+            .server_name("localhost")
+            .build()
+            .unwrap();
+
+        connect(&addr, tls).unwrap();
+    }
+}
+
+#[test]
+fn should_verify_host_being_connected() {
+    let (addr, sni) = start_server(false);
+    let port = &addr[addr.rfind(':').unwrap()..];
+    for backend in backends() {
+        let tls = || {
+            TlsConfig::builder()
+                .backend(backend)
+                .ca_file(resource("ca.pem"))
+                .build()
+                .unwrap()
+        };
+
+        connect(&format!("localhost{}", port), tls()).unwrap();
+        assert_eq!(sni.recv().unwrap().as_deref(), Some("localhost"));
+
+        // certificate is issued for localhost, not for the IP
+        assert_tls_error(connect(&addr, tls()));
+    }
+}
+
+#[test]
+fn should_fail_without_client_certificate() {
+    let (addr, _) = start_server(true);
+    for backend in backends() {
+        let tls = TlsConfig::builder()
+            .backend(backend)
+            .ca_file(resource("ca.pem"))
+            .server_name("localhost")
+            .build()
+            .unwrap();
+
+        assert_tls_error(connect(&addr, tls));
+    }
+}
+
+#[test]
+fn should_fail_with_untrusted_server_certificate() {
+    let (addr, _) = start_server(false);
+    for backend in backends() {
+        let tls = TlsConfig::builder()
+            .backend(backend)
+            .ca_file(resource("other-ca.pem"))
+            .server_name("localhost")
+            .build()
+            .unwrap();
+
+        assert_tls_error(connect(&addr, tls));
+    }
+}
+
+#[test]
+fn should_accept_invalid_certificate_if_allowed() {
+    let (addr, _) = start_server(false);
+    for backend in backends() {
+        let tls = TlsConfig::builder()
+            .backend(backend)
+            .danger_accept_invalid_certs(true)
+            .server_name("some.host")
+            .build()
+            .unwrap();
+
+        connect(&addr, tls).unwrap();
+    }
+}
+
+#[test]
+fn should_encrypt_custom_transport() {
+    let conf = server_config(false);
+    let (sni_sender, sni) = channel();
+    for backend in backends() {
+        let (conf, sni_sender) = (conf.clone(), sni_sender.clone());
+        let transport =
+            move |_: &str, _: Option<Duration>| -> IgniteResult<Box<dyn TransportStream>> {
+                let (client, server) = duplex();
+                serve(conf.clone(), server, sni_sender.clone());
+                Ok(Box::new(client))
+            };
+        let tls = TlsConfig::builder()
+            .backend(backend)
+            .ca_file(resource("ca.pem"))
+            .build()
+            .unwrap();
+        let conf = ClientConfig::builder()
+            .address("localhost")
+            .transport(transport)
+            .tls(tls)
+            .build()
+            .unwrap();
+
+        let mut client = ignite_rs::new_client(conf).unwrap();
+        assert_eq!(client.get_cache_names().unwrap(), Vec::<String>::new());
+        assert_eq!(sni.recv().unwrap().as_deref(), Some("localhost"));
+    }
+}
+
+#[test]
+fn should_validate_pem_files() {
+    let invalid_config = |builder: TlsConfigBuilder| match builder.build() {
+        Err(IgniteError::InvalidConfig(msg)) => msg,
+        Err(err) => panic!("InvalidConfig expected: {}", err),
+        Ok(_) => panic!("InvalidConfig expected"),
+    };
+
+    for backend in backends() {
+        let builder = || TlsConfig::builder().backend(backend);
+        assert!(invalid_config(builder()).contains("CA"));
+        let missing = builder().ca_file(resource("missing.pem"));
+        assert!(invalid_config(missing).contains("missing.pem"));
+        let not_cert = builder().ca_file(resource("client.key"));
+        assert!(invalid_config(not_cert).contains("client.key"));
+        let no_key = builder()
+            .ca_file(resource("ca.pem"))
+            .client_cert_files(resource("client.pem"), resource("client.pem"));
+        assert!(invalid_config(no_key).contains("private key"));
+    }
+}
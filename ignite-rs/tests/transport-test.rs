@@ -0,0 +1,87 @@
+mod common;
+
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use ignite_rs::error::{IgniteError, IgniteResult};
+use ignite_rs::transport::{duplex, DuplexStream, TransportStream};
+use ignite_rs::{ClientConfig, Ignite};
+
+use common::{read_message, write_response, HANDSHAKE_OK};
+
+/// Bodies of the responses
+const EMPTY_NAMES: &[u8] = &[0, 0, 0, 0];
+const NULL: &[u8] = &[101];
+
+/// Server which accepts the handshake and answers every request with the body.
+/// Requests after the first `answered` ones are not answered
+fn serve(mut stream: DuplexStream, body: &'static [u8], answered: usize) {
+    thread::spawn(move || {
+        read_message(&mut stream).unwrap();
+        stream.write_all(HANDSHAKE_OK).unwrap();
+        let mut count = 0;
+        while let Ok(request) = read_message(&mut stream) {
+            count += 1;
+            if count > answered {
+                continue;
+            }
+            let _ = write_response(&mut stream, &request, body);
+        }
+    });
+}
+
+#[test]
+fn should_connect_over_custom_transport() {
+    let addrs = Arc::new(Mutex::new(Vec::new()));
+    let connected = addrs.clone();
+    let transport =
+        move |addr: &str, _: Option<Duration>| -> IgniteResult<Box<dyn TransportStream>> {
+            connected.lock().unwrap().push(addr.to_owned());
+            if addr.starts_with("down") {
+                return Err(IgniteError::InvalidConfig("Node is down".to_owned()));
+            }
+            let (client, server) = duplex();
+            serve(server, EMPTY_NAMES, usize::MAX);
+            Ok(Box::new(client))
+        };
+    let conf = ClientConfig::builder()
+        .address("down")
+        .address("up")
+        .transport(transport)
+        .build()
+        .unwrap();
+
+    let mut client = ignite_rs::new_client(conf).unwrap();
+    assert_eq!(client.get_cache_names().unwrap(), Vec::<String>::new());
+    assert_eq!(*addrs.lock().unwrap(), vec!["down:10800", "up:10800"]);
+}
+
+#[test]
+fn should_apply_timeout_to_custom_transport() {
+    let connections = Arc::new(Mutex::new(0));
+    let counter = connections.clone();
+    let transport = move |_: &str, _: Option<Duration>| -> IgniteResult<Box<dyn TransportStream>> {
+        let mut count = counter.lock().unwrap();
+        *count += 1;
+        let (client, server) = duplex();
+        // the first connection answers only get_or_create_cache
+        serve(server, NULL, if *count == 1 { 1 } else { usize::MAX });
+        Ok(Box::new(client))
+    };
+    let mut conf = ClientConfig::new("node:10800");
+    conf.transport = Some(Arc::new(transport));
+    let mut client = ignite_rs::new_client(conf).unwrap();
+    let cache = client.get_or_create_cache::<i32, i32>("cache").unwrap();
+
+    let timeout = Duration::from_millis(100);
+    match cache.with_timeout(timeout).get(&1) {
+        Err(IgniteError::Timeout(actual)) => assert_eq!(actual, timeout),
+        other => panic!("Timeout expected: {:?}", other.map(|_| ())),
+    }
+
+    // the timed out stream is replaced
+    assert_eq!(cache.get(&1).unwrap(), None);
+    assert_eq!(*connections.lock().unwrap(), 2);
+}
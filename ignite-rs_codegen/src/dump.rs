@@ -0,0 +1,100 @@
+//! Offline dump of cache schemas. Saved as JSON, so the code could be generated
+//! without a connection to the cluster
+
+use ignite_rs::cache::{QueryEntity, QueryField};
+use serde::{Deserialize, Serialize};
+
+/// Query entities of a single cache
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheSchema {
+    pub cache: String,
+    pub entities: Vec<EntitySchema>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntitySchema {
+    pub key_type: String,
+    pub value_type: String,
+    pub table: String,
+    #[serde(default)]
+    pub key_field: Option<String>,
+    #[serde(default)]
+    pub value_field: Option<String>,
+    pub fields: Vec<FieldSchema>,
+    #[serde(default)]
+    pub aliases: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldSchema {
+    pub name: String,
+    pub type_name: String,
+    #[serde(default)]
+    pub key_field: bool,
+    #[serde(default)]
+    pub not_null: bool,
+    #[serde(default = "unset")]
+    pub precision: i32,
+    #[serde(default = "unset")]
+    pub scale: i32,
+}
+
+fn unset() -> i32 {
+    -1
+}
+
+impl From<&QueryEntity> for EntitySchema {
+    fn from(entity: &QueryEntity) -> Self {
+        EntitySchema {
+            key_type: entity.key_type().to_owned(),
+            value_type: entity.value_type().to_owned(),
+            table: entity.table().to_owned(),
+            key_field: entity.key_field().map(str::to_owned),
+            value_field: entity.value_field().map(str::to_owned),
+            fields: entity.fields().iter().map(FieldSchema::from).collect(),
+            aliases: entity.field_aliases().to_vec(),
+        }
+    }
+}
+
+impl From<&QueryField> for FieldSchema {
+    fn from(field: &QueryField) -> Self {
+        FieldSchema {
+            name: field.name().to_owned(),
+            type_name: field.type_name().to_owned(),
+            key_field: field.is_key_field(),
+            not_null: field.is_not_null(),
+            precision: field.precision(),
+            scale: field.scale(),
+        }
+    }
+}
+
+impl From<&EntitySchema> for QueryEntity {
+    fn from(schema: &EntitySchema) -> Self {
+        let mut builder =
+            QueryEntity::builder(&schema.key_type, &schema.value_type).table(&schema.table);
+        if let Some(ref key_field) = schema.key_field {
+            builder = builder.key_field(key_field);
+        }
+        if let Some(ref value_field) = schema.value_field {
+            builder = builder.value_field(value_field);
+        }
+        for field in schema.fields.iter() {
+            let mut field_builder = QueryField::builder(&field.name, &field.type_name)
+                .precision(field.precision)
+                .scale(field.scale);
+            if field.key_field {
+                field_builder = field_builder.key_field();
+            }
+            if field.not_null {
+                field_builder = field_builder.not_null();
+            }
+            builder = builder.field(field_builder.build());
+        }
+        for (field, alias) in schema.aliases.iter() {
+            builder = builder.alias(field, alias);
+        }
+        builder.build()
+    }
+}
@@ -0,0 +1,386 @@
+//! Rust code generation from query entities
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use ignite_rs::cache::{QueryEntity, QueryField};
+use ignite_rs::protocol::complex_obj::IgniteType;
+
+use crate::dump::CacheSchema;
+
+/// Rust keywords which can't be used as field names
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "do", "dyn", "else",
+    "enum", "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod",
+    "move", "mut", "priv", "pub", "ref", "return", "self", "Self", "static", "struct", "super",
+    "trait", "true", "try", "type", "typeof", "union", "unsafe", "use", "where", "while", "yield",
+];
+
+/// Generates #[derive(IgniteObj)] structs for keys and values of all query entities.
+/// Keys and values of builtin types, like java.lang.Long, don't get a struct
+pub fn generate(caches: &[CacheSchema]) -> String {
+    let mut generator = Generator::default();
+    for cache in caches.iter() {
+        for schema in cache.entities.iter() {
+            generator.add_entity(&cache.cache, &QueryEntity::from(schema));
+        }
+    }
+    generator.finish()
+}
+
+#[derive(Default)]
+struct Generator {
+    /// Type names of already generated structs
+    type_names: BTreeSet<String>,
+    /// Idents of already generated structs
+    idents: BTreeSet<String>,
+    /// Types to import from ignite_rs
+    imports: BTreeSet<&'static str>,
+    structs: String,
+}
+
+impl Generator {
+    fn add_entity(&mut self, cache: &str, entity: &QueryEntity) {
+        // fields are split the same way as ComplexObjectSchema::infer_schemas does
+        let is_key = |f: &&QueryField| f.is_key_field() || entity.key_field() == Some(f.name());
+        let key_fields: Vec<&QueryField> = entity.fields().iter().filter(is_key).collect();
+        let val_fields: Vec<&QueryField> = entity.fields().iter().filter(|f| !is_key(f)).collect();
+
+        if entity.key_field().is_none() && !is_builtin(entity.key_type()) {
+            let doc = format!("Key of the cache \"{}\", table {}", cache, entity.table());
+            self.add_struct(&doc, entity.key_type(), entity.table(), "Key", &key_fields);
+        }
+        if entity.value_field().is_none() && !is_builtin(entity.value_type()) {
+            let doc = format!("Value of the cache \"{}\", table {}", cache, entity.table());
+            self.add_struct(&doc, entity.value_type(), entity.table(), "", &val_fields);
+        }
+    }
+
+    fn add_struct(
+        &mut self,
+        doc: &str,
+        type_name: &str,
+        table: &str,
+        suffix: &str,
+        fields: &[&QueryField],
+    ) {
+        if !self.type_names.insert(type_name.to_owned()) {
+            return; // the same type is used by several caches
+        }
+
+        let mut ident = struct_ident(type_name, table, suffix);
+        if self.idents.contains(&ident) {
+            let base = ident.clone();
+            let mut i = 2;
+            while self.idents.contains(&ident) {
+                ident = format!("{}{}", base, i);
+                i += 1;
+            }
+        }
+        self.idents.insert(ident.clone());
+
+        let out = &mut self.structs;
+        let _ = writeln!(out);
+        let _ = writeln!(out, "/// {}", doc);
+        let _ = writeln!(out, "#[derive(IgniteObj, Clone, Debug, PartialEq)]");
+        if ident != type_name {
+            let _ = writeln!(out, "#[ignite(type_name = \"{}\")]", escape(type_name));
+        }
+        let _ = writeln!(out, "pub struct {} {{", ident);
+        let mut field_idents: BTreeSet<String> = BTreeSet::new();
+        for field in fields.iter() {
+            let mut field_ident = field_ident(field.name());
+            while !field_idents.insert(field_ident.clone()) {
+                field_ident.push('_');
+            }
+            // ids are computed from lowercased names, so only the case may differ
+            if field_ident.to_lowercase() != field.name().to_lowercase() {
+                let _ = writeln!(out, "    #[ignite(rename = \"{}\")]", escape(field.name()));
+            }
+            let ty = rust_type(field, &mut self.imports);
+            let _ = writeln!(out, "    pub {}: {},", field_ident, ty);
+        }
+        let _ = writeln!(out, "}}");
+    }
+
+    fn finish(self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "// Generated by ignite-codegen. Do not edit");
+        if !self.structs.is_empty() {
+            let _ = writeln!(out);
+            if self.imports.contains("IgniteValue") {
+                let _ = writeln!(out, "use ignite_rs::protocol::complex_obj::IgniteValue;");
+            }
+            let imports: Vec<&str> = self
+                .imports
+                .iter()
+                .filter(|import| **import != "IgniteValue")
+                .cloned()
+                .collect();
+            match imports.as_slice() {
+                [] => {}
+                [import] => {
+                    let _ = writeln!(out, "use ignite_rs::{};", import);
+                }
+                _ => {
+                    let _ = writeln!(out, "use ignite_rs::{{{}}};", imports.join(", "));
+                }
+            }
+            let _ = writeln!(out, "use ignite_rs_derive::IgniteObj;");
+        }
+        out.push_str(&self.structs);
+        out
+    }
+}
+
+fn is_builtin(java_type: &str) -> bool {
+    IgniteType::from_java_type(java_type, -1, -1).is_some()
+}
+
+/// Rust type of the field. Nullable fields are optional.
+/// Fields of user-defined Java types are read dynamically as IgniteValue
+fn rust_type(field: &QueryField, imports: &mut BTreeSet<&'static str>) -> String {
+    let mut import = |name: &'static str| {
+        imports.insert(name);
+        name
+    };
+    let ty = match IgniteType::from_java_type(field.type_name(), -1, -1) {
+        Some(IgniteType::Long) => "i64".to_owned(),
+        Some(IgniteType::Int) => "i32".to_owned(),
+        Some(IgniteType::Short) => "i16".to_owned(),
+        Some(IgniteType::Byte) => "u8".to_owned(),
+        Some(IgniteType::Float) => "f32".to_owned(),
+        Some(IgniteType::Double) => "f64".to_owned(),
+        Some(IgniteType::Char) => "u16".to_owned(),
+        Some(IgniteType::Bool) => "bool".to_owned(),
+        Some(IgniteType::String) => "String".to_owned(),
+        Some(IgniteType::Uuid) => import("Uuid").to_owned(),
+        Some(IgniteType::Date) => import("Date").to_owned(),
+        Some(IgniteType::Time) => import("Time").to_owned(),
+        Some(IgniteType::Timestamp) => import("Timestamp").to_owned(),
+        Some(IgniteType::Decimal(_, _)) => import("Decimal").to_owned(),
+        Some(IgniteType::ArrByte) => "Vec<u8>".to_owned(),
+        Some(IgniteType::ArrShort) => "Vec<i16>".to_owned(),
+        Some(IgniteType::ArrInt) => "Vec<i32>".to_owned(),
+        Some(IgniteType::ArrLong) => "Vec<i64>".to_owned(),
+        Some(IgniteType::ArrFloat) => "Vec<f32>".to_owned(),
+        Some(IgniteType::ArrDouble) => "Vec<f64>".to_owned(),
+        Some(IgniteType::ArrChar) => "Vec<u16>".to_owned(),
+        Some(IgniteType::ArrBool) => "Vec<bool>".to_owned(),
+        Some(IgniteType::ArrString) => "Box<[Option<String>]>".to_owned(),
+        Some(IgniteType::ArrUuid) => format!("Box<[Option<{}>]>", import("Uuid")),
+        Some(IgniteType::ArrDate) => format!("Box<[Option<{}>]>", import("Date")),
+        Some(IgniteType::ArrTime) => format!("Box<[Option<{}>]>", import("Time")),
+        Some(IgniteType::ArrTimestamp) => format!("Box<[Option<{}>]>", import("Timestamp")),
+        Some(IgniteType::ArrDecimal) => format!("Box<[Option<{}>]>", import("Decimal")),
+        _ => import("IgniteValue").to_owned(),
+    };
+    if field.is_not_null() {
+        ty
+    } else {
+        format!("Option<{}>", ty)
+    }
+}
+
+/// Struct name after the simple name of the Java type.
+/// Types of tables created with SQL have generated names, so the table name is used instead
+fn struct_ident(type_name: &str, table: &str, suffix: &str) -> String {
+    let simple_name = type_name.rsplit(['.', '$']).next().unwrap_or("");
+    let name = if simple_name.starts_with("SQL_") || simple_name.is_empty() {
+        format!("{}{}", to_pascal_case(table), suffix)
+    } else {
+        to_pascal_case(simple_name)
+    };
+    match name.chars().next() {
+        Some(c) if c.is_ascii_alphabetic() => name,
+        _ => format!("T{}", name),
+    }
+}
+
+/// Converts "PERSON_ADDRESS", "personAddress" or "PersonAddress" into "PersonAddress"
+fn to_pascal_case(name: &str) -> String {
+    let mut result = String::new();
+    for word in name.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            result.push(first.to_ascii_uppercase());
+            let rest: String = chars.collect();
+            if word.chars().any(|c| c.is_ascii_lowercase()) {
+                result.push_str(&rest);
+            } else {
+                result.push_str(&rest.to_ascii_lowercase());
+            }
+        }
+    }
+    result
+}
+
+/// Converts "FIRST_NAME", "firstName" or "userID" into a snake case identifier
+fn field_ident(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut ident = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !ident.ends_with('_') {
+                ident.push('_');
+            }
+            continue;
+        }
+        if c.is_ascii_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_ascii_lowercase());
+            let starts_word = prev.is_ascii_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_ascii_uppercase() && next_is_lower);
+            if starts_word && !ident.ends_with('_') {
+                ident.push('_');
+            }
+        }
+        ident.push(c.to_ascii_lowercase());
+    }
+    let ident = ident.trim_matches('_').to_owned();
+    if ident.is_empty() {
+        return "field".to_owned();
+    }
+    if ident.starts_with(|c: char| c.is_ascii_digit()) || KEYWORDS.contains(&ident.as_str()) {
+        return format!("{}_", ident);
+    }
+    ident
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dump::{EntitySchema, FieldSchema};
+
+    fn field(name: &str, type_name: &str, key_field: bool, not_null: bool) -> FieldSchema {
+        FieldSchema {
+            name: name.to_owned(),
+            type_name: type_name.to_owned(),
+            key_field,
+            not_null,
+            precision: -1,
+            scale: -1,
+        }
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(to_pascal_case("PERSON_ADDRESS"), "PersonAddress");
+        assert_eq!(to_pascal_case("personAddress"), "PersonAddress");
+        assert_eq!(to_pascal_case("City"), "City");
+        assert_eq!(field_ident("FIRST_NAME"), "first_name");
+        assert_eq!(field_ident("firstName"), "first_name");
+        assert_eq!(field_ident("userID"), "user_id");
+        assert_eq!(field_ident("HTTPServer"), "http_server");
+        assert_eq!(field_ident("TYPE"), "type_");
+        assert_eq!(field_ident("1st"), "1st_");
+        assert_eq!(
+            struct_ident("SQL_PUBLIC_PERSON_1d77a9c4", "PERSON", "Key"),
+            "PersonKey"
+        );
+        assert_eq!(struct_ident("com.acme.Outer$Inner", "INNER", ""), "Inner");
+    }
+
+    #[test]
+    fn test_generate_sql_table() {
+        // CREATE TABLE PERSON (ID INT, CITY_ID INT, NAME VARCHAR NOT NULL, firstName VARCHAR,
+        // SALARY DECIMAL(10, 2), TYPE VARCHAR, PRIMARY KEY (ID, CITY_ID))
+        let caches = vec![CacheSchema {
+            cache: "SQL_PUBLIC_PERSON".to_owned(),
+            entities: vec![EntitySchema {
+                key_type: "SQL_PUBLIC_PERSON_1d77a9c4_KEY".to_owned(),
+                value_type: "SQL_PUBLIC_PERSON_1d77a9c4".to_owned(),
+                table: "PERSON".to_owned(),
+                key_field: None,
+                value_field: None,
+                fields: vec![
+                    field("ID", "java.lang.Integer", true, false),
+                    field("CITY_ID", "java.lang.Integer", true, false),
+                    field("NAME", "java.lang.String", false, true),
+                    field("firstName", "java.lang.String", false, false),
+                    field("SALARY", "java.math.BigDecimal", false, false),
+                    field("TYPE", "java.lang.String", false, false),
+                    field("ADDRESS", "com.acme.Address", false, false),
+                ],
+                aliases: vec![],
+            }],
+        }];
+
+        let expected = r#"// Generated by ignite-codegen. Do not edit
+
+use ignite_rs::protocol::complex_obj::IgniteValue;
+use ignite_rs::Decimal;
+use ignite_rs_derive::IgniteObj;
+
+/// Key of the cache "SQL_PUBLIC_PERSON", table PERSON
+#[derive(IgniteObj, Clone, Debug, PartialEq)]
+#[ignite(type_name = "SQL_PUBLIC_PERSON_1d77a9c4_KEY")]
+pub struct PersonKey {
+    pub id: Option<i32>,
+    pub city_id: Option<i32>,
+}
+
+/// Value of the cache "SQL_PUBLIC_PERSON", table PERSON
+#[derive(IgniteObj, Clone, Debug, PartialEq)]
+#[ignite(type_name = "SQL_PUBLIC_PERSON_1d77a9c4")]
+pub struct Person {
+    pub name: String,
+    #[ignite(rename = "firstName")]
+    pub first_name: Option<String>,
+    pub salary: Option<Decimal>,
+    #[ignite(rename = "TYPE")]
+    pub type_: Option<String>,
+    pub address: Option<IgniteValue>,
+}
+"#;
+        assert_eq!(generate(&caches), expected);
+    }
+
+    #[test]
+    fn test_generate_builtin_key() {
+        let entity = EntitySchema {
+            key_type: "java.lang.Long".to_owned(),
+            value_type: "com.acme.City".to_owned(),
+            table: "CITY".to_owned(),
+            key_field: Some("ID".to_owned()),
+            value_field: None,
+            fields: vec![
+                field("ID", "java.lang.Long", false, false),
+                field("FOUNDED", "java.util.Date", false, true),
+                field("TAGS", "[Ljava.lang.String;", false, false),
+            ],
+            aliases: vec![],
+        };
+        // the same type in two caches is generated once
+        let caches = vec![
+            CacheSchema {
+                cache: "CITY".to_owned(),
+                entities: vec![entity.clone()],
+            },
+            CacheSchema {
+                cache: "CITY_BACKUP".to_owned(),
+                entities: vec![entity],
+            },
+        ];
+
+        let expected = r#"// Generated by ignite-codegen. Do not edit
+
+use ignite_rs::Date;
+use ignite_rs_derive::IgniteObj;
+
+/// Value of the cache "CITY", table CITY
+#[derive(IgniteObj, Clone, Debug, PartialEq)]
+#[ignite(type_name = "com.acme.City")]
+pub struct City {
+    pub founded: Date,
+    pub tags: Option<Box<[Option<String>]>>,
+}
+"#;
+        assert_eq!(generate(&caches), expected);
+    }
+}
@@ -0,0 +1,205 @@
+//! Generates #[derive(IgniteObj)] structs for keys and values of caches' SQL tables.
+//! Schemas are read from the cluster or from a JSON dump saved earlier
+
+use std::fs;
+use std::process;
+
+use ignite_rs::{ClientConfig, Ignite};
+
+use crate::dump::{CacheSchema, EntitySchema};
+
+mod dump;
+mod generate;
+
+const USAGE: &str = "Usage: ignite-codegen [OPTIONS] [CACHE]...
+
+Generates #[derive(IgniteObj)] structs for keys and values of the caches' SQL tables.
+All caches are used if none is given.
+
+Options:
+    --addr <HOST:PORT>      Address of the cluster [default: localhost:10800]
+    --username <USERNAME>   User name
+    --password <PASSWORD>   Password
+    --dump <FILE>           Save schemas read from the cluster to the JSON file
+    --from-dump <FILE>      Read schemas from the JSON file instead of the cluster
+    --out <FILE>            Write the code to the file instead of stdout
+    -h, --help              Print this message";
+
+#[derive(Debug, Default, PartialEq)]
+struct Args {
+    addr: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+    dump: Option<String>,
+    from_dump: Option<String>,
+    out: Option<String>,
+    caches: Vec<String>,
+    help: bool,
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut result = Args::default();
+    let mut args = args;
+    while let Some(arg) = args.next() {
+        let option = match arg.as_str() {
+            "-h" | "--help" => {
+                result.help = true;
+                continue;
+            }
+            "--addr" => &mut result.addr,
+            "--username" => &mut result.username,
+            "--password" => &mut result.password,
+            "--dump" => &mut result.dump,
+            "--from-dump" => &mut result.from_dump,
+            "--out" => &mut result.out,
+            _ if arg.starts_with('-') => return Err(format!("Unknown option {}", arg)),
+            _ => {
+                result.caches.push(arg);
+                continue;
+            }
+        };
+        match args.next() {
+            Some(value) => *option = Some(value),
+            None => return Err(format!("Value of {} is missing", arg)),
+        }
+    }
+    if result.dump.is_some() && result.from_dump.is_some() {
+        return Err("--dump and --from-dump can't be used together".to_owned());
+    }
+    Ok(result)
+}
+
+/// Reads query entities of the caches from the cluster
+fn read_schemas(args: &Args) -> Result<Vec<CacheSchema>, String> {
+    let addr = args.addr.as_deref().unwrap_or("localhost:10800");
+    let mut config = ClientConfig::new(addr);
+    config.username = args.username.clone();
+    config.password = args.password.clone();
+    let mut client = ignite_rs::new_client(config).map_err(|err| err.to_string())?;
+
+    let caches = if args.caches.is_empty() {
+        client.get_cache_names().map_err(|err| err.to_string())?
+    } else {
+        args.caches.clone()
+    };
+
+    let mut schemas = Vec::new();
+    for cache in caches {
+        let config = client
+            .get_cache_config(&cache)
+            .map_err(|err| format!("Can't read configuration of {}: {}", cache, err))?;
+        let entities: Vec<EntitySchema> = config
+            .query_entities
+            .iter()
+            .flatten()
+            .map(EntitySchema::from)
+            .collect();
+        schemas.push(CacheSchema { cache, entities });
+    }
+    Ok(schemas)
+}
+
+/// Reads query entities of the caches from the dump
+fn read_dump(path: &str, caches: &[String]) -> Result<Vec<CacheSchema>, String> {
+    let json = fs::read_to_string(path).map_err(|err| format!("Can't read {}: {}", path, err))?;
+    let schemas: Vec<CacheSchema> =
+        serde_json::from_str(&json).map_err(|err| format!("Invalid dump {}: {}", path, err))?;
+    Ok(schemas
+        .into_iter()
+        .filter(|schema| caches.is_empty() || caches.contains(&schema.cache))
+        .collect())
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let schemas = match args.from_dump {
+        Some(ref path) => read_dump(path, &args.caches)?,
+        None => read_schemas(&args)?,
+    };
+
+    if let Some(ref path) = args.dump {
+        let json = serde_json::to_string_pretty(&schemas).map_err(|err| err.to_string())?;
+        fs::write(path, json).map_err(|err| format!("Can't write {}: {}", path, err))?;
+    }
+
+    let code = generate::generate(&schemas);
+    match args.out {
+        Some(ref path) => {
+            fs::write(path, code).map_err(|err| format!("Can't write {}: {}", path, err))
+        }
+        None => {
+            print!("{}", code);
+            Ok(())
+        }
+    }
+}
+
+fn main() {
+    let result = parse_args(std::env::args().skip(1)).and_then(|args| {
+        if args.help {
+            println!("{}", USAGE);
+            Ok(())
+        } else {
+            run(args)
+        }
+    });
+    if let Err(err) = result {
+        eprintln!("error: {}", err);
+        eprintln!();
+        eprintln!("{}", USAGE);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Result<Args, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let parsed = args(&["--addr", "ignite:10800", "--out", "schema.rs", "A", "B"]).unwrap();
+        assert_eq!(parsed.addr.as_deref(), Some("ignite:10800"));
+        assert_eq!(parsed.out.as_deref(), Some("schema.rs"));
+        assert_eq!(parsed.caches, vec!["A".to_owned(), "B".to_owned()]);
+
+        assert!(args(&["--help"]).unwrap().help);
+        assert!(args(&["--addr"]).is_err());
+        assert!(args(&["--verbose"]).is_err());
+        assert!(args(&["--dump", "a.json", "--from-dump", "b.json"]).is_err());
+    }
+
+    #[test]
+    fn test_dump_round_trip() {
+        let path = std::env::temp_dir().join(format!("ignite-codegen-{}.json", process::id()));
+        let json = r#"[{
+            "cache": "CITY",
+            "entities": [{
+                "key_type": "java.lang.Long",
+                "value_type": "com.acme.City",
+                "table": "CITY",
+                "key_field": "ID",
+                "fields": [
+                    {"name": "ID", "type_name": "java.lang.Long"},
+                    {"name": "NAME", "type_name": "java.lang.String", "not_null": true}
+                ]
+            }]
+        }, {
+            "cache": "OTHER",
+            "entities": []
+        }]"#;
+        fs::write(&path, json).unwrap();
+        let schemas = read_dump(path.to_str().unwrap(), &["CITY".to_owned()]).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(schemas.len(), 1);
+        let entity = &schemas[0].entities[0];
+        assert_eq!(entity.fields[0].precision, -1);
+        assert!(entity.fields[1].not_null);
+
+        let code = generate::generate(&schemas);
+        assert!(code.contains("pub struct City {\n    pub name: String,\n}"));
+    }
+}
@@ -1,55 +1,605 @@
 use proc_macro2::{Ident, TokenStream};
 use quote::*;
 use syn::spanned::Spanned;
-use syn::{Data, DeriveInput, Fields, FieldsNamed};
+use syn::{
+    parse_quote, Attribute, Data, DataEnum, DeriveInput, Fields, GenericParam, Generics, Index,
+    Lit, Member, Meta, NestedMeta,
+};
 
-#[proc_macro_derive(IgniteObj)]
+#[proc_macro_derive(IgniteObj, attributes(ignite))]
 pub fn derive_ignite_obj(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = syn::parse_macro_input!(item as DeriveInput);
 
-    let type_name = &input.ident; // name of the struct
     let output = match input.data {
-        Data::Struct(ref st) => match st.fields {
-            Fields::Named(ref fields) => {
-                let write_tokens = impl_write_type(type_name, fields);
-                let read_tokens = impl_read_type(type_name, fields);
-
-                quote! {
-                    #write_tokens
-                    #read_tokens
+        Data::Struct(ref st) => {
+            let result = match st.fields {
+                Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => {
+                    impl_newtype(&input, &fields.unnamed[0])
                 }
+                _ => impl_obj(&input, &st.fields),
+            };
+            result.unwrap_or_else(|err| err.to_compile_error())
+        }
+        _ => quote_spanned! { input.span() => compile_error!("Struct expected!");},
+    };
+
+    proc_macro::TokenStream::from(output)
+}
+
+/// Implements traits for a struct written as a complex object.
+/// Fields of tuple structs are named by their positions: "0", "1", etc.
+fn impl_obj(input: &DeriveInput, fields: &Fields) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+    let attrs = get_type_attrs(input)?;
+    let type_name = &attrs.type_name;
+    let type_id = name_id(type_name, attrs.case_sensitive_ids);
+    let fields = get_fields(fields, attrs.case_sensitive_ids)?;
+    let generics = add_trait_bounds(&input.generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let write_tokens = impl_write_type(ident, &generics, type_id, &fields);
+    let read_tokens = impl_read_type(ident, &generics, type_id, &fields);
+    let hash_tokens = impl_hash_code(ident, &generics, &fields);
+
+    Ok(quote! {
+        #write_tokens
+        #read_tokens
+        #hash_tokens
+        impl #impl_generics ignite_rs::CollectionItem for #ident #ty_generics #where_clause {}
+
+        impl #impl_generics ignite_rs::JavaType for #ident #ty_generics #where_clause {
+            const JAVA_TYPE_NAME: &'static str = #type_name;
+        }
+    })
+}
+
+/// Implements traits for a newtype struct. It is written transparently, as its only field.
+/// The wrapped type may be not hashable, so HashCode and JavaType of the wrapped type
+/// are implemented only if requested by #[ignite(hash_code)] and #[ignite(java_type)]
+fn impl_newtype(input: &DeriveInput, field: &syn::Field) -> syn::Result<TokenStream> {
+    if let Some(meta) = ignite_attrs(&field.attrs)?.first() {
+        let msg = "Attributes are not supported by the field of newtype structs";
+        return Err(syn::Error::new(meta.span(), msg));
+    }
+    let (mut hash_code, mut java_type) = (false, false);
+    for meta in ignite_attrs(&input.attrs)? {
+        match meta {
+            Meta::Path(ref path) if path.is_ident("hash_code") => hash_code = true,
+            Meta::Path(ref path) if path.is_ident("java_type") => java_type = true,
+            _ => {
+                let msg =
+                    "Only hash_code and java_type attributes are supported by newtype structs";
+                return Err(syn::Error::new(meta.span(), msg));
+            }
+        }
+    }
+
+    let ident = &input.ident;
+    let ty = &field.ty;
+    let generics = add_trait_bounds(&input.generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let hash_tokens = if hash_code {
+        let mut hash_generics = generics.clone();
+        hash_generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote!(#ty: ignite_rs::HashCode));
+        let hash_where_clause = &hash_generics.where_clause;
+        quote! {
+            impl #impl_generics ignite_rs::HashCode for #ident #ty_generics #hash_where_clause {
+                const AFFINITY_KEY_FIELD: Option<&'static str> = <#ty as ignite_rs::HashCode>::AFFINITY_KEY_FIELD;
+
+                fn hash_code(&self) -> i32 {
+                    ignite_rs::HashCode::hash_code(&self.0)
+                }
+
+                fn affinity_hash_code(&self) -> i32 {
+                    ignite_rs::HashCode::affinity_hash_code(&self.0)
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let java_tokens = if java_type {
+        let mut java_generics = generics.clone();
+        java_generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote!(#ty: ignite_rs::JavaType));
+        let java_where_clause = &java_generics.where_clause;
+        quote! {
+            impl #impl_generics ignite_rs::JavaType for #ident #ty_generics #java_where_clause {
+                const JAVA_TYPE_NAME: &'static str = <#ty as ignite_rs::JavaType>::JAVA_TYPE_NAME;
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    Ok(quote! {
+        impl #impl_generics ignite_rs::WritableType for #ident #ty_generics #where_clause {
+            fn write(&self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
+                ignite_rs::WritableType::write(&self.0, writer)
+            }
+
+            fn size(&self) -> usize {
+                ignite_rs::WritableType::size(&self.0)
+            }
+        }
+
+        impl #impl_generics ignite_rs::ReadableType for #ident #ty_generics #where_clause {
+            fn read_unwrapped(type_code: ignite_rs::protocol::TypeCode, reader: &mut impl std::io::Read) -> ignite_rs::error::IgniteResult<Option<Self>> {
+                let value = <#ty as ignite_rs::ReadableType>::read_unwrapped(type_code, reader)?;
+                Ok(value.map(#ident))
+            }
+        }
+
+        impl #impl_generics ignite_rs::CollectionItem for #ident #ty_generics #where_clause {}
+
+        #hash_tokens
+        #java_tokens
+    })
+}
+
+/// Adds WritableType + ReadableType bounds to every type parameter
+fn add_trait_bounds(generics: &Generics) -> Generics {
+    let mut generics = generics.clone();
+    for param in generics.params.iter_mut() {
+        if let GenericParam::Type(ref mut type_param) = *param {
+            type_param
+                .bounds
+                .push(parse_quote!(ignite_rs::WritableType));
+            type_param
+                .bounds
+                .push(parse_quote!(ignite_rs::ReadableType));
+        }
+    }
+    generics
+}
+
+#[proc_macro_derive(IgniteQueryEntity, attributes(ignite))]
+pub fn derive_ignite_query_entity(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(item as DeriveInput);
+
+    let output = match input.data {
+        Data::Struct(ref st) => {
+            impl_query_entity(&input, &st.fields).unwrap_or_else(|err| err.to_compile_error())
+        }
+        _ => quote_spanned! { input.span() => compile_error!("Struct expected!");},
+    };
+
+    proc_macro::TokenStream::from(output)
+}
+
+/// Implements ignite_rs::IgniteQueryEntity trait. Java types of the columns are inferred
+/// with ignite_rs::JavaType, unless given by #[ignite(java_type = "...")]
+fn impl_query_entity(input: &DeriveInput, fields: &Fields) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+    let attrs = get_type_attrs(input)?;
+    // the server computes the ids of the value type and its fields from the lowercased names
+    if attrs.case_sensitive_ids {
+        let msg = "#[ignite(case_sensitive_ids)] is not supported by IgniteQueryEntity";
+        return Err(syn::Error::new(input.ident.span(), msg));
+    }
+    let fields = get_fields(fields, attrs.case_sensitive_ids)?;
+    let fields: Vec<&Field> = fields.iter().filter(|f| !f.skip).collect();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let java_type = |field: &Field| match field.java_type {
+        Some(ref java_type) => quote! { #java_type },
+        None => {
+            let ty = field.ty;
+            quote! { <#ty as ignite_rs::JavaType>::JAVA_TYPE_NAME }
+        }
+    };
+
+    // a key of a single field is the field itself, otherwise it's a complex object
+    let key_fields: Vec<&&Field> = fields.iter().filter(|f| f.key).collect();
+    let (key_type, key_field) = match (&attrs.key_type, key_fields.as_slice()) {
+        (Some(key_type), _) => (quote! { #key_type }, quote! {}),
+        (None, [field]) => {
+            let name = &field.name;
+            (java_type(field), quote! { .key_field(#name) })
+        }
+        (None, _) => {
+            let msg = "Either one #[ignite(key)] field or #[ignite(key_type = \"...\")] expected";
+            return Err(syn::Error::new(input.ident.span(), msg));
+        }
+    };
+
+    let table_name = attrs
+        .table
+        .clone()
+        .unwrap_or_else(|| attrs.type_name.rsplit('.').next().unwrap().to_owned());
+    let table = attrs.table.as_ref().map(|table| quote! { .table(#table) });
+
+    let query_fields = fields.iter().map(|f| {
+        let name = &f.name;
+        let java_type = java_type(f);
+        let key = if f.key {
+            quote! { .key_field() }
+        } else {
+            quote! {}
+        };
+        let not_null = if f.not_null {
+            quote! { .not_null() }
+        } else {
+            quote! {}
+        };
+        let precision = f.precision;
+        let scale = f.scale;
+        quote! {
+            .field(
+                ignite_rs::cache::QueryField::builder(#name, #java_type)
+                    #key
+                    #not_null
+                    .precision(#precision)
+                    .scale(#scale)
+                    .build()
+            )
+        }
+    });
+
+    // fields with the same index name make up a single index, in the order of declaration
+    let mut indexes: Vec<(String, Vec<String>)> = Vec::new();
+    for field in fields.iter() {
+        let index_name = match field.index {
+            None => continue,
+            Some(ref name) if name.is_empty() => {
+                format!("{}_{}_idx", table_name, field.name).to_lowercase()
             }
-            _ => quote_spanned! { st.fields.span() => compile_error!("Named struct expected!");},
+            Some(ref name) => name.clone(),
+        };
+        match indexes.iter_mut().find(|(name, _)| *name == index_name) {
+            Some((_, index_fields)) => index_fields.push(field.name.clone()),
+            None => indexes.push((index_name, vec![field.name.clone()])),
+        }
+    }
+    let query_indexes = indexes.iter().map(|(name, index_fields)| {
+        quote! {
+            .index(
+                ignite_rs::cache::QueryIndex::builder(#name, ignite_rs::cache::IndexType::Sorted)
+                    #( .field(#index_fields, false) )*
+                    .build()
+            )
+        }
+    });
+
+    let value_type = &attrs.type_name;
+    Ok(quote! {
+        impl #impl_generics ignite_rs::IgniteQueryEntity for #ident #ty_generics #where_clause {
+            fn query_entity() -> ignite_rs::cache::QueryEntity {
+                ignite_rs::cache::QueryEntity::builder(#key_type, #value_type)
+                    #table
+                    #key_field
+                    #( #query_fields )*
+                    #( #query_indexes )*
+                    .build()
+            }
+        }
+    })
+}
+
+/// Struct's field with its #[ignite(...)] attributes applied
+struct Field<'a> {
+    member: Member,
+    ty: &'a syn::Type,
+    /// Name of the field in the binary object. Field's ident, position or #[ignite(rename = "...")]
+    name: String,
+    /// #[ignite(skip)]: the field is not written and is read as Default
+    skip: bool,
+    /// #[ignite(default)]: the field is read as Default if it's NULL
+    default: bool,
+    /// #[ignite(affinity_key)]: the field is used to map the object to a partition
+    affinity_key: bool,
+    /// #[ignite(key)]: the field is a part of the key in the query entity
+    key: bool,
+    /// #[ignite(index)] or #[ignite(index = "...")]: name of the index the field belongs to.
+    /// Empty for a single-field index with the default name
+    index: Option<String>,
+    /// #[ignite(not_null)]: the column has the NOT NULL constraint
+    not_null: bool,
+    /// #[ignite(precision = ...)]
+    precision: i32,
+    /// #[ignite(scale = ...)]
+    scale: i32,
+    /// #[ignite(java_type = "...")]: Java type name of the column instead of the inferred one
+    java_type: Option<String>,
+    /// #[ignite(case_sensitive_ids)] of the struct: the id is computed from the name as is
+    case_sensitive_id: bool,
+}
+
+impl Field<'_> {
+    /// Java-compatible field id
+    fn id(&self) -> i32 {
+        name_id(&self.name, self.case_sensitive_id)
+    }
+}
+
+fn get_fields(fields: &Fields, case_sensitive_ids: bool) -> syn::Result<Vec<Field<'_>>> {
+    let mut result = Vec::new();
+    for (i, f) in fields.iter().enumerate() {
+        let (member, name) = match f.ident {
+            Some(ref ident) => (Member::Named(ident.clone()), ident.to_string()),
+            None => (Member::Unnamed(Index::from(i)), i.to_string()),
+        };
+        let mut field = Field {
+            member,
+            ty: &f.ty,
+            name,
+            skip: false,
+            default: false,
+            affinity_key: false,
+            key: false,
+            index: None,
+            not_null: false,
+            precision: -1,
+            scale: -1,
+            java_type: None,
+            case_sensitive_id: case_sensitive_ids,
+        };
+        for meta in ignite_attrs(&f.attrs)? {
+            match meta {
+                Meta::NameValue(ref nv) if nv.path.is_ident("rename") => {
+                    field.name = lit_str(&nv.lit)?;
+                }
+                Meta::Path(ref path) if path.is_ident("skip") => field.skip = true,
+                Meta::Path(ref path) if path.is_ident("default") => field.default = true,
+                Meta::Path(ref path) if path.is_ident("affinity_key") => field.affinity_key = true,
+                Meta::Path(ref path) if path.is_ident("key") => field.key = true,
+                Meta::Path(ref path) if path.is_ident("index") => field.index = Some(String::new()),
+                Meta::NameValue(ref nv) if nv.path.is_ident("index") => {
+                    field.index = Some(lit_str(&nv.lit)?);
+                }
+                Meta::Path(ref path) if path.is_ident("not_null") => field.not_null = true,
+                Meta::NameValue(ref nv) if nv.path.is_ident("precision") => {
+                    field.precision = lit_i32(&nv.lit)?;
+                }
+                Meta::NameValue(ref nv) if nv.path.is_ident("scale") => {
+                    field.scale = lit_i32(&nv.lit)?;
+                }
+                Meta::NameValue(ref nv) if nv.path.is_ident("java_type") => {
+                    field.java_type = Some(lit_str(&nv.lit)?);
+                }
+                _ => return Err(syn::Error::new(meta.span(), "Unknown attribute")),
+            }
+        }
+        result.push(field);
+    }
+
+    let mut ids: Vec<i32> = Vec::new();
+    for field in result.iter().filter(|f| !f.skip) {
+        if ids.contains(&field.id()) {
+            let msg = format!("Field id of \"{}\" is not unique", field.name);
+            return Err(syn::Error::new(Spanned::span(&field.member), msg));
+        }
+        ids.push(field.id());
+    }
+
+    let mut affinity_keys = result.iter().filter(|f| f.affinity_key);
+    if let Some(field) = affinity_keys.next() {
+        if field.skip {
+            let msg = "Skipped field can't be an affinity key";
+            return Err(syn::Error::new(Spanned::span(&field.member), msg));
+        }
+        if let Some(field) = affinity_keys.next() {
+            let msg = "Only one field can be an affinity key";
+            return Err(syn::Error::new(Spanned::span(&field.member), msg));
+        }
+    }
+    Ok(result)
+}
+
+#[proc_macro_derive(IgniteEnum, attributes(ignite))]
+pub fn derive_ignite_enum(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(item as DeriveInput);
+
+    let output = match input.data {
+        Data::Enum(ref en) => match get_type_attrs(&input) {
+            Ok(attrs) => impl_enum(&input.ident, &attrs, en),
+            Err(err) => err.to_compile_error(),
         },
-        _ => quote_spanned! { input.span() => compile_error!("Named struct expected!");},
+        _ => quote_spanned! { input.span() => compile_error!("Enum expected!");},
     };
 
     proc_macro::TokenStream::from(output)
 }
 
+/// Implements ignite_rs::WritableType, ReadableType, ArrayItem, HashCode and JavaType traits
+/// for a fieldless enum.
+/// Variant's ordinal is its position, like in Java
+fn impl_enum(ident: &Ident, attrs: &TypeAttrs, en: &DataEnum) -> TokenStream {
+    if let Some(variant) = en.variants.iter().find(|v| !v.fields.is_empty()) {
+        return quote_spanned! { variant.span() => compile_error!("Fieldless enum expected!");};
+    }
+    for variant in en.variants.iter() {
+        match ignite_attrs(&variant.attrs) {
+            Ok(metas) if metas.is_empty() => {}
+            Ok(metas) => {
+                let msg = "Attributes are not supported by enum variants";
+                return syn::Error::new(metas[0].span(), msg).to_compile_error();
+            }
+            Err(err) => return err.to_compile_error(),
+        }
+    }
+
+    let type_name = &attrs.type_name;
+    let type_id = name_id(type_name, attrs.case_sensitive_ids);
+    let variants: Vec<&Ident> = en.variants.iter().map(|v| &v.ident).collect();
+    let ordinals: Vec<i32> = (0..variants.len() as i32).collect();
+
+    quote! {
+        impl ignite_rs::WritableType for #ident {
+            fn write(&self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
+                let ordinal: i32 = match self {
+                    #( #ident::#variants => #ordinals, )*
+                };
+                ignite_rs::Enum { type_id: #type_id, ordinal }.write(writer)
+            }
+
+            fn size(&self) -> usize {
+                1 + 4 + 4 // type code, type id, ordinal
+            }
+        }
+
+        impl ignite_rs::ReadableType for #ident {
+            fn read_unwrapped(type_code: ignite_rs::protocol::TypeCode, reader: &mut impl std::io::Read) -> ignite_rs::error::IgniteResult<Option<Self>> {
+                match type_code {
+                    ignite_rs::protocol::TypeCode::Null => Ok(None),
+                    ignite_rs::protocol::TypeCode::Enum | ignite_rs::protocol::TypeCode::BinaryEnum => {
+                        let value = ignite_rs::protocol::read_enum(reader)?;
+                        if value.type_id != #type_id {
+                            return Err(ignite_rs::error::IgniteError::Protocol(
+                                format!("Unknown type id! {} expected!", #type_id),
+                            ));
+                        }
+                        match value.ordinal {
+                            #( #ordinals => Ok(Some(#ident::#variants)), )*
+                            _ => Err(ignite_rs::error::IgniteError::Protocol(
+                                format!("Unknown ordinal {} of {}!", value.ordinal, #type_name),
+                            )),
+                        }
+                    }
+                    _ => Err(ignite_rs::error::IgniteError::Protocol("Expected Enum!".to_owned())),
+                }
+            }
+        }
+
+        impl ignite_rs::ArrayItem for #ident {
+            const ARRAY_TYPE_CODE: ignite_rs::protocol::TypeCode = ignite_rs::protocol::TypeCode::ArrEnum;
+            const ARRAY_TYPE_ID: Option<i32> = Some(#type_id);
+        }
+
+        impl ignite_rs::CollectionItem for #ident {}
+
+        impl ignite_rs::HashCode for #ident {
+            fn hash_code(&self) -> i32 {
+                let ordinal: i32 = match self {
+                    #( #ident::#variants => #ordinals, )*
+                };
+                ignite_rs::HashCode::hash_code(&ignite_rs::Enum { type_id: #type_id, ordinal })
+            }
+        }
+
+        impl ignite_rs::JavaType for #ident {
+            const JAVA_TYPE_NAME: &'static str = #type_name;
+        }
+    }
+}
+
+/// Type's #[ignite(...)] attributes
+struct TypeAttrs {
+    /// Type name given by #[ignite(type_name = "...")] or the name of the type itself
+    type_name: String,
+    /// #[ignite(table = "...")]: name of the SQL table
+    table: Option<String>,
+    /// #[ignite(key_type = "...")]: Java type name of the composite key
+    key_type: Option<String>,
+    /// #[ignite(case_sensitive_ids)]: type and field ids are computed from the names as is,
+    /// instead of the lowercased ones like Java's default id mapper does
+    case_sensitive_ids: bool,
+}
+
+fn get_type_attrs(input: &DeriveInput) -> syn::Result<TypeAttrs> {
+    // enums are not objects, so they have no tables and keys
+    let is_enum = matches!(input.data, Data::Enum(_));
+    let mut attrs = TypeAttrs {
+        type_name: input.ident.to_string(),
+        table: None,
+        key_type: None,
+        case_sensitive_ids: false,
+    };
+    for meta in ignite_attrs(&input.attrs)? {
+        match meta {
+            Meta::NameValue(ref nv) if nv.path.is_ident("type_name") => {
+                attrs.type_name = lit_str(&nv.lit)?;
+            }
+            Meta::NameValue(ref nv) if nv.path.is_ident("table") && !is_enum => {
+                attrs.table = Some(lit_str(&nv.lit)?);
+            }
+            Meta::NameValue(ref nv) if nv.path.is_ident("key_type") && !is_enum => {
+                attrs.key_type = Some(lit_str(&nv.lit)?);
+            }
+            Meta::Path(ref path) if path.is_ident("case_sensitive_ids") => {
+                attrs.case_sensitive_ids = true
+            }
+            _ if is_enum => {
+                let msg = "Only type_name and case_sensitive_ids attributes are supported by enums";
+                return Err(syn::Error::new(meta.span(), msg));
+            }
+            _ => return Err(syn::Error::new(meta.span(), "Unknown attribute")),
+        }
+    }
+    Ok(attrs)
+}
+
+/// Items of all #[ignite(...)] attributes
+fn ignite_attrs(attrs: &[Attribute]) -> syn::Result<Vec<Meta>> {
+    let mut metas = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("ignite")) {
+        match attr.parse_meta()? {
+            Meta::List(list) => {
+                for nested in list.nested {
+                    match nested {
+                        NestedMeta::Meta(meta) => metas.push(meta),
+                        NestedMeta::Lit(lit) => {
+                            return Err(syn::Error::new(lit.span(), "Unknown attribute"))
+                        }
+                    }
+                }
+            }
+            meta => return Err(syn::Error::new(meta.span(), "#[ignite(...)] expected")),
+        }
+    }
+    Ok(metas)
+}
+
+fn lit_str(lit: &Lit) -> syn::Result<String> {
+    match lit {
+        Lit::Str(str) => Ok(str.value()),
+        _ => Err(syn::Error::new(lit.span(), "String expected")),
+    }
+}
+
+fn lit_i32(lit: &Lit) -> syn::Result<i32> {
+    match lit {
+        Lit::Int(int) => int.base10_parse(),
+        _ => Err(syn::Error::new(lit.span(), "Integer expected")),
+    }
+}
+
 /// Implements ignite_rs::WritableType trait
-fn impl_write_type(type_name: &Ident, fields: &FieldsNamed) -> TokenStream {
-    let type_id: i32 = get_type_id(type_name);
-    let schema_id = get_schema_id(fields);
-
-    let fields_schema = fields.named.iter().map(|f| {
-        let field_name = &f.ident;
-        quote_spanned! { field_name.span() =>
-            ignite_rs::protocol::write_i32(&mut schema, ignite_rs::utils::string_to_java_hashcode(stringify!(#field_name)))?; // field id
+fn impl_write_type(
+    ident: &Ident,
+    generics: &Generics,
+    type_id: i32,
+    fields: &[Field],
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let fields: Vec<&Field> = fields.iter().filter(|f| !f.skip).collect();
+    let schema_id = get_schema_id(&fields);
+
+    let fields_schema = fields.iter().map(|f| {
+        let field_name = &f.member;
+        let field_id = f.id();
+        quote_spanned! { Spanned::span(field_name) =>
+            ignite_rs::protocol::write_i32(&mut schema, #field_id)?; // field id
             ignite_rs::protocol::write_i32(&mut schema, ignite_rs::protocol::COMPLEX_OBJ_HEADER_LEN + fields.len() as i32)?; // field offset
-            self.#field_name.write(&mut fields)?;
+            ignite_rs::WritableType::write(&self.#field_name, &mut fields)?;
         }
     });
 
-    let fields_schema_size = fields.named.iter().map(|f| {
-        let field_name = &f.ident;
-        quote_spanned! { field_name.span() =>
-            size += self.#field_name.size() + 4 + 4; // field's size, field id, fields offset
+    let fields_schema_size = fields.iter().map(|f| {
+        let field_name = &f.member;
+        quote_spanned! { Spanned::span(field_name) =>
+            size += ignite_rs::WritableType::size(&self.#field_name) + 4 + 4; // field's size, field id, fields offset
         }
     });
 
     quote! {
-        impl ignite_rs::WritableType for #type_name {
+        impl #impl_generics ignite_rs::WritableType for #ident #ty_generics #where_clause {
             fn write(&self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
                 ignite_rs::protocol::write_u8(writer, ignite_rs::protocol::TypeCode::ComplexObj as u8)?;
                 ignite_rs::protocol::write_u8(writer,1)?; //version. always 1
@@ -83,85 +633,109 @@ fn impl_write_type(type_name: &Ident, fields: &FieldsNamed) -> TokenStream {
     }
 }
 
-/// Implements ReadableType trait
-fn impl_read_type(type_name: &Ident, fields: &FieldsNamed) -> TokenStream {
-    let exp_type_id: i32 = get_type_id(type_name);
-    let fields_count = fields.named.len();
+/// Implements ReadableType trait.
+/// Fields are located by the object's footer, so their order doesn't matter.
+/// Missing fields are read as NULL
+fn impl_read_type(
+    ident: &Ident,
+    generics: &Generics,
+    exp_type_id: i32,
+    fields: &[Field],
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let written: Vec<&Field> = fields.iter().filter(|f| !f.skip).collect();
+    let schema_id = get_schema_id(&written);
+    let field_ids = written.iter().map(|f| f.id());
 
-    let fields_read = fields.named.iter().map(|f| {
-        let field_name = &f.ident;
-        let ty = &f.ty;
-        let formatted_name = format_ident!("_{}", field_name.as_ref().unwrap().to_string());
-        quote_spanned! { field_name.span() =>
-            let #formatted_name = <#ty>::read(reader)?.unwrap(); // get option value
+    let fields_read = fields.iter().map(|f| {
+        let field_name = &f.member;
+        let ty = f.ty;
+        let field_id = f.id();
+        let name = &f.name;
+        if f.skip {
+            quote_spanned! { Spanned::span(field_name) =>
+                #field_name: Default::default(), // skipped field
+            }
+        } else if f.default {
+            quote_spanned! { Spanned::span(field_name) =>
+                #field_name: obj.read_field::<#ty>(#field_id)?.unwrap_or_default(),
+            }
+        } else {
+            quote_spanned! { Spanned::span(field_name) =>
+                #field_name: obj.read_field::<#ty>(#field_id)?.ok_or_else(|| {
+                    ignite_rs::error::IgniteError::Protocol(format!("Field {} is NULL or missing!", #name))
+                })?,
+            }
         }
     });
 
-    let field_pairs = fields.named.iter().map(|f| {
-        let field_name = f.ident.as_ref().unwrap();
-        let formatted_name = format_ident!("_{}", field_name);
-        quote! (#field_name: #formatted_name,)
-    });
-
     quote! {
-            impl ignite_rs::ReadableType for #type_name {
+        impl #impl_generics ignite_rs::ReadableType for #ident #ty_generics #where_clause {
             fn read_unwrapped(type_code: ignite_rs::protocol::TypeCode, reader: &mut impl std::io::Read) -> ignite_rs::error::IgniteResult<Option<Self>> {
-                let value: Option<Self> = match type_code {
-                    ignite_rs::protocol::TypeCode::Null => None,
-                    _ => {
-                        ignite_rs::protocol::read_u8(reader)?; // read version. skip
-
-                        let flags = ignite_rs::protocol::read_u16(reader)?; // read and parse flags
-                        if (flags & ignite_rs::protocol::FLAG_HAS_SCHEMA) == 0 {
-                            return Err(ignite_rs::error::IgniteError::from("Serialized object schema expected!"));
-                        }
-                        if (flags & ignite_rs::protocol::FLAG_COMPACT_FOOTER) != 0 {
-                            return Err(ignite_rs::error::IgniteError::from("Compact footer is not supported!"));
-                        }
-                        if (flags & ignite_rs::protocol::FLAG_OFFSET_ONE_BYTE) != 0 || (flags & ignite_rs::protocol::FLAG_OFFSET_TWO_BYTES) != 0 {
-                            return Err(ignite_rs::error::IgniteError::from("Schema offset=4 is expected!"));
-                        }
-
-                        let type_id = ignite_rs::protocol::read_i32(reader)?; // read and check type_id
-                        if type_id != #exp_type_id {
-                            return Err(ignite_rs::error::IgniteError::from(
-                                format!("Unknown type id! {} expected!", #exp_type_id).as_str(),
+                match type_code {
+                    ignite_rs::protocol::TypeCode::Null => Ok(None),
+                    ignite_rs::protocol::TypeCode::ComplexObj => {
+                        let obj = ignite_rs::protocol::binary_type::BinaryObjectFields::read(reader, #schema_id, &[#(#field_ids),*])?;
+                        if obj.type_id() != #exp_type_id {
+                            return Err(ignite_rs::error::IgniteError::Protocol(
+                                format!("Unknown type id! {} expected!", #exp_type_id),
                             ));
                         }
 
-                        ignite_rs::protocol::read_i32(reader)?; // read hashcode
-                        ignite_rs::protocol::read_i32(reader)?; // read length (with header)
-                        ignite_rs::protocol::read_i32(reader)?; // read schema id
-                        ignite_rs::protocol::read_i32(reader)?; // read schema offset
+                        Ok(Some(#ident {
+                            #( #fields_read)*
+                        }))
+                    }
+                    _ => Err(ignite_rs::error::IgniteError::Protocol("Expected Complex Object!".to_owned())),
+                }
+            }
+        }
+    }
+}
 
-                        #( #fields_read)*
+/// Implements ignite_rs::HashCode trait.
+/// The hash code is the one written in the object's header: Java's BinaryArrayIdentityResolver
+/// computes it over the bytes of the fields. The object which can't be written hashes to 0
+fn impl_hash_code(ident: &Ident, generics: &Generics, fields: &[Field]) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let members = fields.iter().filter(|f| !f.skip).map(|f| &f.member);
 
-                        // read schema
-                        for _ in 0..#fields_count {
-                            ignite_rs::protocol::read_i64(reader)?; // read one field (id and offset)
-                        }
+    let affinity_tokens = match fields.iter().find(|f| f.affinity_key) {
+        Some(field) => {
+            let member = &field.member;
+            let name = &field.name;
+            quote! {
+                const AFFINITY_KEY_FIELD: Option<&'static str> = Some(#name);
 
-                        Some(
-                            #type_name{
-                                #(#field_pairs)*
-                            }
-                        )
-                    }
-                };
-                Ok(value)
+                fn affinity_hash_code(&self) -> i32 {
+                    ignite_rs::HashCode::hash_code(&self.#member)
+                }
+            }
+        }
+        None => quote! {},
+    };
+
+    quote! {
+        impl #impl_generics ignite_rs::HashCode for #ident #ty_generics #where_clause {
+            #affinity_tokens
+
+            fn hash_code(&self) -> i32 {
+                let mut fields: Vec<u8> = Vec::new();
+                let written = (|| -> std::io::Result<()> {
+                    #( ignite_rs::WritableType::write(&self.#members, &mut fields)?; )*
+                    Ok(())
+                })();
+                written.map_or(0, |_| ignite_rs::utils::bytes_to_java_hashcode(fields.as_slice()))
             }
         }
     }
 }
 
-/// Schema ID based on field hashcodes
-fn get_schema_id(fields: &FieldsNamed) -> i32 {
+/// Schema ID based on field ids
+fn get_schema_id(fields: &[&Field]) -> i32 {
     fields
-        .named
         .iter()
-        .map(|field| field.ident.as_ref().unwrap()) // can unwrap because fields are named
-        .map(|ident| ident.to_string())
-        .map(|name| string_to_java_hashcode(&name))
+        .map(|field| field.id())
         .fold(FNV1_OFFSET_BASIS, |acc, hash| {
             let mut res = acc;
             res ^= hash & 0xFF;
@@ -176,21 +750,26 @@ fn get_schema_id(fields: &FieldsNamed) -> i32 {
         })
 }
 
-/// Java-like hashcode of type's name
-fn get_type_id(ident: &Ident) -> i32 {
-    string_to_java_hashcode(&ident.to_string())
-}
-
 /// FNV1 hash offset basis
 const FNV1_OFFSET_BASIS: i32 = 0x811C_9DC5_u32 as i32;
 /// FNV1 hash prime
 const FNV1_PRIME: i32 = 0x0100_0193;
 
-/// Converts string into Java-like hash code
+/// Id of the type or field name: its Java-like hash code.
+/// Java's default id mapper lowercases the name first
+fn name_id(name: &str, case_sensitive: bool) -> i32 {
+    if case_sensitive {
+        string_to_java_hashcode(name)
+    } else {
+        string_to_java_hashcode(&name.to_lowercase())
+    }
+}
+
+/// Converts string into Java-like hash code. Computed over UTF-16 code units
 fn string_to_java_hashcode(value: &str) -> i32 {
     let mut hash: i32 = 0;
-    for char in value.chars().into_iter() {
-        hash = 31i32.overflowing_mul(hash).0 + char as i32;
+    for unit in value.encode_utf16() {
+        hash = hash.wrapping_mul(31).wrapping_add(i32::from(unit));
     }
     hash
 }